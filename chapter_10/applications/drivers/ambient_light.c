@@ -0,0 +1,42 @@
+// Ambient Light API
+
+#include "ambient_light.h"
+#include "tock.h"
+
+bool ambient_light_is_present (void) {
+  // send command number 0 to the driver
+  syscall_return_t ret = command (DRIVER_NUM_AMBIENT_LIGHT, 0, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+returncode_t ambient_light_get_level (unsigned int *level) {
+  // Send command number 1 to the driver, the driver
+  // returns the light level
+  syscall_return_t ret = command (DRIVER_NUM_AMBIENT_LIGHT, 1, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS_U32) {
+    *level = ret.data[0];
+    return RETURNCODE_SUCCESS;
+  } else if (ret.type == TOCK_SYSCALL_FAILURE) {
+    return tock_status_to_returncode(ret.data[0]);
+  } else {
+    return RETURNCODE_EBADRVAL;
+  }
+}
+
+returncode_t ambient_light_get_brightness (unsigned int *brightness) {
+  // Send command number 2 to the driver, the driver
+  // returns the brightness of the LED matrix
+  syscall_return_t ret = command (DRIVER_NUM_AMBIENT_LIGHT, 2, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS_U32) {
+    *brightness = ret.data[0];
+    return RETURNCODE_SUCCESS;
+  } else if (ret.type == TOCK_SYSCALL_FAILURE) {
+    return tock_status_to_returncode(ret.data[0]);
+  } else {
+    return RETURNCODE_EBADRVAL;
+  }
+}
@@ -0,0 +1,29 @@
+// Ambient Light API
+
+// Make sure this file is included only once
+#pragma once
+
+#include "tock.h"
+
+#define DRIVER_NUM_AMBIENT_LIGHT 0xa0005
+
+// Make sure that functions are exported as C functions and not C++
+// This prevents the compiler from exporing the functions using
+// the C++ name mangling style 
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Verifies if the driver is present.
+bool ambient_light_is_present (void);
+
+// Reads the last measured light level, from 0 (dark) to 255 (bright).
+returncode_t ambient_light_get_level (unsigned int *level);
+
+// Reads the brightness of the LED matrix, set from the light
+// level, from 0 to 255 (fully on).
+returncode_t ambient_light_get_brightness (unsigned int *brightness);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,55 @@
+// Grayscale Display API
+
+#include "grayscale_display.h"
+#include "tock.h"
+
+bool grayscale_display_is_present (void) {
+  // send command number 0 to the driver
+  syscall_return_t ret = command (DRIVER_NUM_GRAYSCALE_DISPLAY, 0, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+returncode_t grayscale_display_show (const uint8_t brightness[GRAYSCALE_DISPLAY_LEDS]) {
+  // Allow the framebuffer with the driver
+  allow_ro_return_t allow_ret = allow_readonly (DRIVER_NUM_GRAYSCALE_DISPLAY, 0, brightness, GRAYSCALE_DISPLAY_LEDS);
+  if (allow_ret.success) {
+    // Send command number 1 to the driver so that it copies
+    // and displays the framebuffer
+    syscall_return_t ret = command (DRIVER_NUM_GRAYSCALE_DISPLAY, 1, 0, 0);
+    // The driver has its own copy of the framebuffer,
+    // unallow the buffer so that we can access it.
+    allow_readonly (DRIVER_NUM_GRAYSCALE_DISPLAY, 0, NULL, 0);
+    if (ret.type == TOCK_SYSCALL_SUCCESS) {
+      return RETURNCODE_SUCCESS;
+    } else {
+      return tock_status_to_returncode(ret.data[0]);
+    }
+  } else {
+    return tock_status_to_returncode(allow_ret.status);
+  }
+}
+
+returncode_t grayscale_display_clear (void) {
+  // Send command number 2 to the driver
+  syscall_return_t ret = command (DRIVER_NUM_GRAYSCALE_DISPLAY, 2, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
+
+returncode_t grayscale_display_set_orientation (unsigned int degrees, bool mirror) {
+  // Send command number 3 to the driver with the rotation
+  // and whether the brightness is mirrored
+  syscall_return_t ret = command (DRIVER_NUM_GRAYSCALE_DISPLAY, 3, degrees, mirror ? 1 : 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
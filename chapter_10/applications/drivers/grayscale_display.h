@@ -0,0 +1,41 @@
+// Grayscale Display API
+
+// Make sure this file is included only once
+#pragma once
+
+#include "tock.h"
+
+#define DRIVER_NUM_GRAYSCALE_DISPLAY 0xa0004
+
+// The number of LEDs, each one has a brightness
+// from 0 (off) to 255 (fully on)
+#define GRAYSCALE_DISPLAY_LEDS 25
+
+// Make sure that functions are exported as C functions and not C++
+// This prevents the compiler from exporing the functions using
+// the C++ name mangling style 
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Verifies if the driver is present.
+bool grayscale_display_is_present (void);
+
+// Displays the brightness of each LED, LED 0 is upper left,
+// LED 24 is lower right.
+//
+// The LEDs are shared with the text display driver, which
+// overwrites them while it displays text. Do not use both
+// displays at the same time.
+returncode_t grayscale_display_show (const uint8_t brightness[GRAYSCALE_DISPLAY_LEDS]);
+
+// Turns off all the LEDs.
+returncode_t grayscale_display_clear (void);
+
+// Rotates the displayed brightness clockwise by degrees (0, 90, 180
+// or 270) and mirrors it horizontally if mirror is true.
+returncode_t grayscale_display_set_orientation (unsigned int degrees, bool mirror);
+
+#ifdef __cplusplus
+}
+#endif
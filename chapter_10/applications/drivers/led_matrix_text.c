@@ -23,3 +23,72 @@ bool led_matrix_text_set_speed (unsigned int speed) {
     return false;
   }
 }
+
+bool led_matrix_text_set_character_mode (void) {
+  // Send command number 2 to the driver with argument 1 (r2) set
+  // to 0 (character mode).
+  syscall_return_t ret = command (DRIVER_NUM_LED_MATRIX_TEXT, 2, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+bool led_matrix_text_set_scroll_mode (unsigned int gap) {
+  // Send command number 2 to the driver with argument 1 (r2) set
+  // to 1 (scroll mode) and argument 2 (r3) set to the number of
+  // blank columns displayed before the text repeats.
+  syscall_return_t ret = command (DRIVER_NUM_LED_MATRIX_TEXT, 2, 1, gap);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+bool led_matrix_text_set_blank (unsigned int blank_ms) {
+  // Send command number 3 to the driver with argument 1 (r2) set
+  // to the time in ms that the LEDs are off between two characters.
+  syscall_return_t ret = command (DRIVER_NUM_LED_MATRIX_TEXT, 3, blank_ms, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+bool led_matrix_text_set_replace_mode (bool replace) {
+  // Send command number 4 to the driver with argument 1 (r2) set
+  // to 1 if printing replaces the text or to 0 if it writes the
+  // text at the cursor.
+  syscall_return_t ret = command (DRIVER_NUM_LED_MATRIX_TEXT, 4, replace ? 1 : 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+bool led_matrix_text_save_boot_message (void) {
+  // Send command number 5 to the driver, the driver writes
+  // the text, the speed and the display mode to the flash.
+  syscall_return_t ret = command (DRIVER_NUM_LED_MATRIX_TEXT, 5, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
+
+bool led_matrix_text_set_orientation (unsigned int degrees, bool mirror) {
+  // Send command number 6 to the driver with argument 1 (r2) set
+  // to the rotation in degrees and argument 2 (r3) set to 1 if
+  // the text is mirrored.
+  syscall_return_t ret = command (DRIVER_NUM_LED_MATRIX_TEXT, 6, degrees, mirror ? 1 : 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
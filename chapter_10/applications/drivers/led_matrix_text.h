@@ -7,6 +7,11 @@
 
 #define DRIVER_NUM_LED_MATRIX_TEXT 0xa0003
 
+// The degree sign, the driver displays Latin-1 bytes, so a "°"
+// written in a UTF-8 source file would be displayed as two
+// unknown characters, use "21" LED_MATRIX_TEXT_DEGREE "C" instead
+#define LED_MATRIX_TEXT_DEGREE "\xb0"
+
 // Make sure that functions are exported as C functions and not C++
 // This prevents the compiler from exporing the functions using
 // the C++ name mangling style 
@@ -20,6 +25,30 @@ bool led_matrix_text_is_present (void);
 // Set the display speed in ms.
 bool led_matrix_text_set_speed (unsigned int speed);
 
+// Display one character at a time.
+bool led_matrix_text_set_character_mode (void);
+
+// Scroll the text one column at a time, with gap blank
+// columns before the text repeats.
+bool led_matrix_text_set_scroll_mode (unsigned int gap);
+
+// Turn off the LEDs for blank_ms between two characters, so that
+// repeated characters are distinguishable (0 disables the gap).
+bool led_matrix_text_set_blank (unsigned int blank_ms);
+
+// Set whether printing replaces the whole text (true) or
+// writes the text at the cursor (false, the default).
+bool led_matrix_text_set_replace_mode (bool replace);
+
+// Save the displayed text, the speed and the display mode as the boot
+// message, displayed after the next reset. Saving an empty screen removes
+// the boot message. Fails if the boot message was saved too recently.
+bool led_matrix_text_save_boot_message (void);
+
+// Rotate the text clockwise by degrees (0, 90, 180 or 270) and
+// mirror it horizontally if mirror is true.
+bool led_matrix_text_set_orientation (unsigned int degrees, bool mirror);
+
 #ifdef __cplusplus
 }
 #endif
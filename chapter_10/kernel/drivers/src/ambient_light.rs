@@ -0,0 +1,397 @@
+use core::cell::Cell;
+use kernel::hil::adc::{AdcChannel, Client};
+use kernel::hil::gpio::{Configure, FloatingState, Output};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks, Ticks, Time};
+use kernel::process::{Error, ProcessId};
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::utilities::cells::OptionalCell;
+use kernel::ErrorCode;
+
+/// The driver number
+///
+/// As this is not one of Tock's standard drivers,
+/// its number has to be higher or equal to 0xa0000.
+///
+/// Our previous driver was 0xa0004 so we use the
+/// number available.
+pub const DRIVER_NUM: usize = 0xa0005;
+
+/// The brightness of the LED matrix when it is never blanked
+pub const FULL_BRIGHTNESS: u8 = u8::MAX;
+
+/// The time in microseconds that the reverse biased LEDs
+/// discharge before their voltage is sampled
+const DISCHARGE_US: u32 = 1000;
+
+/// The time in microseconds after which a sample that
+/// the ADC has not returned is dropped
+const SAMPLE_TIMEOUT_US: u32 = 10000;
+
+/// What the driver does until the alarm fires
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Phase {
+    /// The LED matrix displays a row
+    On,
+    /// The row is blanked until the end of its time slot
+    Blank,
+    /// The LED matrix is blanked and the LEDs discharge
+    Discharge,
+    /// The voltage of a column is being sampled
+    Sample(usize),
+}
+
+/// Structure representing the ambient light driver
+///
+/// The driver uses the LEDs of the matrix as light sensors. While the
+/// matrix is blanked, it reverse biases the LEDs of some columns and lets
+/// them discharge: the more light, the faster the voltage of the columns
+/// drops. The ADC samples that voltage.
+///
+/// The driver is the alarm of the LED matrix refresh, so it sees each row
+/// that the refresh displays, whatever the drivers display. It keeps a row
+/// on for a part of its time slot and blanks it for the rest, the darker
+/// the room the shorter the part. Once every sample period, it holds the
+/// refresh at the end of a frame and samples the light.
+pub struct AmbientLight<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> {
+    /// The pins that power the rows of LEDs, the anodes, which
+    /// are disconnected to blank the matrix
+    rows: &'a [&'a P],
+
+    /// The pins of the columns of LEDs used as sensors, the cathodes
+    columns: &'a [&'a P],
+
+    /// The ADC channels connected to the `columns` pins
+    channels: &'a [&'a C],
+
+    /// The alarm used to switch between phases
+    alarm: &'a A,
+
+    /// The time in milliseconds between two samples
+    sample_period_ms: u32,
+
+    /// The brightness used in complete darkness
+    min_brightness: u8,
+
+    /// The LED matrix refresh, notified when the time slot of a row ends
+    client: OptionalCell<&'a dyn AlarmClient>,
+
+    /// The time slot of the row that the refresh displays, the
+    /// reference and the duration of the refresh's alarm
+    slot: OptionalCell<(A::Ticks, A::Ticks)>,
+
+    /// The number of rows displayed since the frame started
+    slots: Cell<usize>,
+
+    /// The time of the last sample
+    last_sample: Cell<A::Ticks>,
+
+    /// What the driver does until the alarm fires
+    phase: Cell<Phase>,
+
+    /// The sum of the columns' voltages of the current sample
+    sum: Cell<u32>,
+
+    /// The last measured light level, from 0 (dark) to 255 (bright)
+    level: Cell<u8>,
+
+    /// The brightness of the matrix, the ratio of each row's time
+    /// slot during which it is on, from 0 to `FULL_BRIGHTNESS`
+    brightness: Cell<u8>,
+}
+
+impl<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> AmbientLight<'a, P, C, A> {
+    /// Initializes a new driver structure
+    pub fn new(
+        rows: &'a [&'a P],
+        columns: &'a [&'a P],
+        channels: &'a [&'a C],
+        alarm: &'a A,
+        sample_period_ms: u32,
+        min_brightness: u8,
+    ) -> Self {
+        if columns.len() == 0 || columns.len() != channels.len() {
+            panic!(
+                "Expecting one ADC channel for each column, {} columns and {} channels supplied",
+                columns.len(),
+                channels.len()
+            );
+        }
+        AmbientLight {
+            rows,
+            columns,
+            channels,
+            alarm,
+            sample_period_ms,
+            min_brightness,
+            client: OptionalCell::empty(),
+            slot: OptionalCell::empty(),
+            slots: Cell::new(0),
+            last_sample: Cell::new(A::Ticks::from(0)),
+            phase: Cell::new(Phase::On),
+            sum: Cell::new(0),
+            level: Cell::new(u8::MAX),
+            brightness: Cell::new(FULL_BRIGHTNESS),
+        }
+    }
+
+    /// Starts measuring the light, the first sample is taken
+    /// after a sample period
+    ///
+    /// This has to be called before the LED matrix is initialized.
+    pub fn start(&self) {
+        // Blanked rows must not float, so that the LEDs stay off
+        // and the columns are reverse biased.
+        for row in self.rows.iter() {
+            row.set_floating_state(FloatingState::PullDown);
+        }
+        self.slots.set(0);
+        self.last_sample.set(self.alarm.now());
+    }
+
+    /// Returns the last measured light level, from 0 (dark) to 255 (bright)
+    pub fn level(&self) -> u8 {
+        self.level.get()
+    }
+
+    /// Returns the brightness of the LED matrix
+    pub fn brightness(&self) -> u8 {
+        self.brightness.get()
+    }
+
+    /// Sets up the alarm to fire after `us` microseconds
+    fn schedule(&self, us: u32) {
+        self.alarm
+            .set_alarm(self.alarm.now(), self.alarm.ticks_from_us(us));
+    }
+
+    /// Returns the part of a time slot of `dt` ticks
+    /// during which the row is on
+    fn on_ticks(&self, dt: A::Ticks) -> A::Ticks {
+        A::Ticks::from(
+            (dt.into_u32() as u64 * self.brightness.get() as u64 / FULL_BRIGHTNESS as u64) as u32,
+        )
+    }
+
+    /// Disconnects the rows, turning off all the LEDs
+    fn blank(&self) {
+        for row in self.rows.iter() {
+            row.make_input();
+        }
+    }
+
+    /// Reconnects the rows, the matrix displays the image again
+    fn unblank(&self) {
+        for row in self.rows.iter() {
+            row.make_output();
+        }
+    }
+
+    /// Called when the on part of a row's time slot ends
+    fn end_on(&self) {
+        match self.slot.get() {
+            Some((reference, dt)) if self.alarm.now().wrapping_sub(reference) < dt => {
+                self.blank();
+                self.phase.set(Phase::Blank);
+                self.alarm.set_alarm(reference, dt);
+            }
+            _ => self.end_slot(),
+        }
+    }
+
+    /// Called when the time slot of a row ends, samples the light
+    /// at the end of a frame if the sample period has elapsed
+    fn end_slot(&self) {
+        let slots = (self.slots.get() + 1) % self.rows.len();
+        self.slots.set(slots);
+        let elapsed = self.alarm.now().wrapping_sub(self.last_sample.get());
+        if slots == 0 && elapsed >= self.alarm.ticks_from_ms(self.sample_period_ms) {
+            self.start_sample();
+        } else {
+            self.refresh();
+        }
+    }
+
+    /// Lets the LED matrix display the next row
+    fn refresh(&self) {
+        if self.slot.take().is_some() {
+            self.client.map(|client| client.alarm());
+        }
+    }
+
+    /// Blanks the matrix and reverse biases the columns' LEDs
+    fn start_sample(&self) {
+        self.last_sample.set(self.alarm.now());
+        self.blank();
+        for column in self.columns.iter() {
+            // The rows are pulled down, a high cathode charges the
+            // LEDs, which then discharge through the light they receive.
+            column.make_output();
+            column.set();
+            column.make_input();
+        }
+        self.sum.set(0);
+        self.phase.set(Phase::Discharge);
+        self.schedule(DISCHARGE_US);
+    }
+
+    /// Samples the voltage of the column `index`
+    fn sample(&self, index: usize) {
+        self.phase.set(Phase::Sample(index));
+        if self.channels[index].sample().is_ok() {
+            self.schedule(SAMPLE_TIMEOUT_US);
+        } else {
+            // Keep the previous light level, try again at the next sample.
+            self.end_sample();
+        }
+    }
+
+    /// Reconnects the columns to the matrix and lets
+    /// the refresh go on
+    fn end_sample(&self) {
+        for column in self.columns.iter() {
+            column.make_output();
+        }
+        let _ = self.alarm.disarm();
+        self.phase.set(Phase::On);
+        self.refresh();
+    }
+
+    /// Updates the light level and the brightness using
+    /// the average voltage of the columns
+    fn update(&self, voltage: u32) {
+        // A high voltage means that the LEDs have barely discharged.
+        let level = u8::MAX - (voltage >> 8) as u8;
+        self.level.set(level);
+        let min_brightness = self.min_brightness as u32;
+        self.brightness.set(
+            (min_brightness
+                + (FULL_BRIGHTNESS as u32 - min_brightness) * level as u32 / u8::MAX as u32)
+                as u8,
+        );
+    }
+
+    /// Returns whether the matrix is held blanked to sample the light
+    fn is_sampling(&self) -> bool {
+        matches!(self.phase.get(), Phase::Discharge | Phase::Sample(_))
+    }
+}
+
+/// This implementation allows `AmbientLight` to use an alarm.
+impl<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> AlarmClient
+    for AmbientLight<'a, P, C, A>
+{
+    /// Called when the current phase ends
+    fn alarm(&self) {
+        match self.phase.get() {
+            Phase::On => self.end_on(),
+            Phase::Blank => self.end_slot(),
+            Phase::Discharge => self.sample(0),
+            Phase::Sample(index) => {
+                // The ADC has not answered, keep the previous light level.
+                let _ = self.channels[index].stop_sampling();
+                self.end_sample();
+            }
+        }
+    }
+}
+
+/// This implementation allows `AmbientLight` to be the alarm of the LED matrix refresh.
+impl<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> Time for AmbientLight<'a, P, C, A> {
+    type Frequency = A::Frequency;
+    type Ticks = A::Ticks;
+
+    fn now(&self) -> Self::Ticks {
+        self.alarm.now()
+    }
+}
+
+impl<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> Alarm<'a>
+    for AmbientLight<'a, P, C, A>
+{
+    fn set_alarm_client(&'a self, client: &'a dyn AlarmClient) {
+        self.client.set(client);
+    }
+
+    /// Called by the refresh once it displays a row,
+    /// `dt` is the time slot of the row
+    fn set_alarm(&self, reference: Self::Ticks, dt: Self::Ticks) {
+        self.slot.set((reference, dt));
+        // A sample holds the refresh until it is done.
+        if !self.is_sampling() {
+            self.unblank();
+            self.phase.set(Phase::On);
+            self.alarm.set_alarm(reference, self.on_ticks(dt));
+        }
+    }
+
+    fn get_alarm(&self) -> Self::Ticks {
+        self.slot.map_or(self.alarm.get_alarm(), |(reference, dt)| {
+            reference.wrapping_add(dt)
+        })
+    }
+
+    fn disarm(&self) -> Result<(), ErrorCode> {
+        self.slot.clear();
+        if self.is_sampling() {
+            Ok(())
+        } else {
+            self.unblank();
+            self.phase.set(Phase::On);
+            self.alarm.disarm()
+        }
+    }
+
+    fn is_armed(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    fn minimum_dt(&self) -> Self::Ticks {
+        self.alarm.minimum_dt()
+    }
+}
+
+/// This implementation allows `AmbientLight` to receive the ADC samples.
+impl<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> Client for AmbientLight<'a, P, C, A> {
+    fn sample_ready(&self, sample: u16) {
+        if let Phase::Sample(index) = self.phase.get() {
+            self.sum.set(self.sum.get() + sample as u32);
+            if index + 1 < self.channels.len() {
+                self.sample(index + 1);
+            } else {
+                self.update(self.sum.get() / self.channels.len() as u32);
+                self.end_sample();
+            }
+        }
+    }
+}
+
+/// This implementation allows `AmbientLight` to expose a syscall API
+impl<'a, P: Configure + Output, C: AdcChannel, A: Alarm<'a>> SyscallDriver
+    for AmbientLight<'a, P, C, A>
+{
+    fn allocate_grant(&self, _: ProcessId) -> Result<(), Error> {
+        // there is no grant used by this driver, we just ignore
+        // the function call and return success
+        Ok(())
+    }
+
+    fn command(
+        &self,
+        command_number: usize,
+        _r2: usize,
+        _r3: usize,
+        _process_id: ProcessId,
+    ) -> CommandReturn {
+        match command_number {
+            // Tock's convention states that all syscall drivers must return *success* or *success_...* for
+            // command number 0. This allows processes to verify if a driver is present.
+            0 => CommandReturn::success(),
+            // Return the last measured light level, from 0 (dark) to 255 (bright)
+            1 => CommandReturn::success_u32(self.level.get() as u32),
+            // Return the brightness of the LED matrix, from 0 to 255
+            2 => CommandReturn::success_u32(self.brightness.get() as u32),
+            // Inform the process that we do not understand the command
+            _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
+        }
+    }
+}
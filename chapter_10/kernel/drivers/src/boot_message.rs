@@ -0,0 +1,165 @@
+use crate::display::{BootMessageStore, TextSettings};
+use kernel::ErrorCode;
+use tickv::error_codes::ErrorCode as TicKVErrorCode;
+use tickv::flash_controller::FlashController;
+use tickv::tickv::{TicKV, MAIN_KEY};
+
+/// The maximum length of the boot message's text
+pub const MAX_TEXT_LEN: usize = 64;
+
+/// The keys of the two slots that store the boot message
+///
+/// A new message is written to the slot that does not store the
+/// current one, which is only invalidated once the new one is saved.
+const SLOT_KEYS: [&[u8]; 2] = [
+    b"led-matrix-text/boot-message/0",
+    b"led-matrix-text/boot-message/1",
+];
+
+/// The length of the record's header: the sequence number (4 bytes),
+/// the speed (4 bytes), the mode (1 byte), the gap (2 bytes),
+/// the blank time (4 bytes) and the length of the text (2 bytes)
+const HEADER_LEN: usize = 17;
+
+/// The length of the largest record
+const RECORD_LEN: usize = HEADER_LEN + MAX_TEXT_LEN;
+
+/// Computes the hash of a key (64 bits FNV-1a)
+///
+/// TicKV identifies the keys by their hash, that has to stay
+/// the same from a kernel version to the other.
+pub fn hash(key: &[u8]) -> u64 {
+    key.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Verifies if the sequence number `sequence` was
+/// assigned after the sequence number `other`
+fn is_newer(sequence: u32, other: u32) -> bool {
+    (sequence.wrapping_sub(other) as i32) > 0
+}
+
+/// Converts a TicKV error into a kernel error
+fn into_error_code(error: TicKVErrorCode) -> ErrorCode {
+    match error {
+        TicKVErrorCode::RegionFull | TicKVErrorCode::FlashFull => ErrorCode::NOMEM,
+        TicKVErrorCode::ObjectTooLarge | TicKVErrorCode::BufferTooSmall(_) => ErrorCode::SIZE,
+        _ => ErrorCode::FAIL,
+    }
+}
+
+/// Structure representing a boot message store that uses
+/// a TicKV key-value region of the flash
+///
+/// The flash controller has to be synchronous, the store is only
+/// used at boot and when a process saves a new boot message.
+pub struct TicKVStore<'a, C: FlashController<S>, const S: usize> {
+    tickv: TicKV<'a, C, S>,
+}
+
+impl<'a, C: FlashController<S>, const S: usize> TicKVStore<'a, C, S> {
+    /// Initializes a new store structure
+    ///
+    /// The key-value region uses `flash_size` bytes of the flash,
+    /// in regions of `S` bytes. The controller's addresses
+    /// are relative to the start of the key-value region.
+    pub fn new(controller: C, read_buffer: &'a mut [u8; S], flash_size: usize) -> Self {
+        TicKVStore {
+            tickv: TicKV::new(controller, read_buffer, flash_size),
+        }
+    }
+
+    /// Prepares the key-value region, erasing it if it
+    /// does not store a TicKV database
+    pub fn initialise(&self) -> Result<(), ErrorCode> {
+        self.tickv
+            .initialise(hash(MAIN_KEY))
+            .map(|_| ())
+            .map_err(into_error_code)
+    }
+
+    /// Reads the record stored in `slot` and returns its sequence number
+    fn read(&self, slot: usize, record: &mut [u8; RECORD_LEN]) -> Option<u32> {
+        self.tickv.get_key(hash(SLOT_KEYS[slot]), record).ok()?;
+        Some(u32::from_le_bytes([
+            record[0], record[1], record[2], record[3],
+        ]))
+    }
+
+    /// Reads the latest record and returns its slot and sequence number
+    ///
+    /// Both slots store a record if the board was reset
+    /// before the previous one was invalidated.
+    fn latest(&self, record: &mut [u8; RECORD_LEN]) -> Option<(usize, u32)> {
+        let mut other = [0; RECORD_LEN];
+        match (self.read(0, record), self.read(1, &mut other)) {
+            (Some(first), Some(second)) if !is_newer(second, first) => Some((0, first)),
+            (Some(first), None) => Some((0, first)),
+            (_, Some(second)) => {
+                record.copy_from_slice(&other);
+                Some((1, second))
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Invalidates the record stored in `slot`, if any
+    fn invalidate(&self, slot: usize) -> Result<(), ErrorCode> {
+        match self.tickv.invalidate_key(hash(SLOT_KEYS[slot])) {
+            Ok(_) | Err(TicKVErrorCode::KeyNotFound) => Ok(()),
+            Err(error) => Err(into_error_code(error)),
+        }
+    }
+}
+
+impl<'a, C: FlashController<S>, const S: usize> BootMessageStore for TicKVStore<'a, C, S> {
+    fn load(&self, text: &mut [u8]) -> Option<(usize, TextSettings)> {
+        let mut record = [0; RECORD_LEN];
+        self.latest(&mut record)?;
+        let settings = TextSettings {
+            speed: u32::from_le_bytes([record[4], record[5], record[6], record[7]]),
+            scroll: record[8] == 1,
+            gap: u16::from_le_bytes([record[9], record[10]]) as usize,
+            blank_ms: u32::from_le_bytes([record[11], record[12], record[13], record[14]]),
+        };
+        let len = u16::from_le_bytes([record[15], record[16]]) as usize;
+        let len = len.min(MAX_TEXT_LEN).min(text.len());
+        text[..len].copy_from_slice(&record[HEADER_LEN..HEADER_LEN + len]);
+        Some((len, settings))
+    }
+
+    fn save(&self, text: &[u8], settings: TextSettings) -> Result<(), ErrorCode> {
+        // Write the new message to the slot that does not store the current one.
+        let mut record = [0; RECORD_LEN];
+        let (slot, sequence) = match self.latest(&mut record) {
+            Some((current, sequence)) => (1 - current, sequence.wrapping_add(1)),
+            None => (0, 0),
+        };
+        let len = text.len().min(MAX_TEXT_LEN);
+        record[0..4].copy_from_slice(&sequence.to_le_bytes());
+        record[4..8].copy_from_slice(&settings.speed.to_le_bytes());
+        record[8] = settings.scroll as u8;
+        record[9..11].copy_from_slice(&(settings.gap.min(u16::MAX as usize) as u16).to_le_bytes());
+        record[11..15].copy_from_slice(&settings.blank_ms.to_le_bytes());
+        record[15..17].copy_from_slice(&(len as u16).to_le_bytes());
+        record[HEADER_LEN..HEADER_LEN + len].copy_from_slice(&text[..len]);
+        let record = &record[..HEADER_LEN + len];
+
+        // TicKV does not replace values, an older message left
+        // in the slot has to be invalidated first.
+        self.invalidate(slot)?;
+        let key = hash(SLOT_KEYS[slot]);
+        match self.tickv.append_key(key, record) {
+            Err(TicKVErrorCode::RegionFull) | Err(TicKVErrorCode::FlashFull) => {
+                // Reclaim the space of the invalidated messages and try again.
+                self.tickv.garbage_collect().map_err(into_error_code)?;
+                self.tickv.append_key(key, record)
+            }
+            res => res,
+        }
+        .map_err(into_error_code)?;
+        // The new message is saved, the previous one is not needed anymore.
+        self.invalidate(1 - slot)
+    }
+}
@@ -0,0 +1,178 @@
+use crate::display::{TextPause, TextSpeed};
+use core::cell::Cell;
+use kernel::hil::gpio::{
+    ActivationMode, ActivationState, Client, ClientWithValue, Configure, InterruptEdge,
+    InterruptPin,
+};
+use kernel::hil::time::{ConvertTicks, Ticks, Time};
+use kernel::utilities::cells::OptionalCell;
+
+/// The time in milliseconds during which the bounces
+/// that follow a press are ignored
+const DEBOUNCE_MS: u32 = 50;
+
+/// The speeds that the button controls can set
+#[derive(Copy, Clone)]
+pub struct SpeedRange {
+    /// The change of the speed for each press, in milliseconds
+    pub step_ms: u32,
+    /// The fastest speed, the shortest delay between two characters
+    pub min_ms: u32,
+    /// The slowest speed, the longest delay between two characters
+    pub max_ms: u32,
+}
+
+/// A button and the state used to detect its presses
+struct Button<'a, P: InterruptPin<'a> + Configure, K: Ticks> {
+    pin: &'a P,
+    /// Stores if the button was pressed at the last interrupt
+    pressed: Cell<bool>,
+    /// The time of the last accepted press
+    last_press: Cell<Option<K>>,
+}
+
+/// Structure representing the button controls of a text display
+///
+/// The first button slows the text down, the second one speeds it up
+/// and the third one pauses or resumes it. The controls are the
+/// interrupt client of the three pins.
+///
+/// The controls may also sit between the pins' value wrappers and
+/// another driver, usually the buttons driver of the processes,
+/// to which they forward the interrupts.
+pub struct ButtonControls<'a, P: InterruptPin<'a> + Configure, T: TextSpeed + TextPause, A: Time> {
+    slower: Button<'a, P, A::Ticks>,
+    faster: Button<'a, P, A::Ticks>,
+    pause: Button<'a, P, A::Ticks>,
+    mode: ActivationMode,
+    text: &'a T,
+    time: &'a A,
+    range: SpeedRange,
+    /// Stores if the buttons control the text
+    enabled: bool,
+    /// The client that receives the interrupts of the pins
+    client: OptionalCell<&'a dyn ClientWithValue>,
+}
+
+impl<'a, P: InterruptPin<'a> + Configure, T: TextSpeed + TextPause, A: Time>
+    ButtonControls<'a, P, T, A>
+{
+    /// Initializes a new controls structure
+    ///
+    /// All the buttons use the same activation `mode`. The buttons
+    /// control the text only if `enabled` is true, otherwise the
+    /// controls only forward the interrupts to their client.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        slower: &'a P,
+        faster: &'a P,
+        pause: &'a P,
+        mode: ActivationMode,
+        text: &'a T,
+        time: &'a A,
+        range: SpeedRange,
+        enabled: bool,
+    ) -> Self {
+        let button = |pin| Button {
+            pin,
+            pressed: Cell::new(false),
+            last_press: Cell::new(None),
+        };
+        ButtonControls {
+            slower: button(slower),
+            faster: button(faster),
+            pause: button(pause),
+            mode,
+            text,
+            time,
+            range,
+            enabled,
+            client: OptionalCell::empty(),
+        }
+    }
+
+    /// Sets the client that receives the interrupts of the pins
+    pub fn set_client(&self, client: &'a dyn ClientWithValue) {
+        self.client.set(client);
+    }
+
+    /// Configures the pins and enables their interrupts,
+    /// if the buttons control the text
+    ///
+    /// The controls have to be set as the pins' client.
+    pub fn setup(&self) {
+        if !self.enabled {
+            return;
+        }
+        for button in [&self.slower, &self.faster, &self.pause].iter() {
+            button.pin.make_input();
+            button.pressed.set(self.is_pressed(button));
+            // Both edges are needed to notice the releases.
+            button.pin.enable_interrupts(InterruptEdge::EitherEdge);
+        }
+    }
+
+    /// Verifies if `button` is pressed
+    fn is_pressed(&self, button: &Button<'a, P, A::Ticks>) -> bool {
+        button.pin.read_activation(self.mode) == ActivationState::Active
+    }
+
+    /// Verifies if `button` has just been pressed
+    fn is_new_press(&self, button: &Button<'a, P, A::Ticks>) -> bool {
+        let pressed = self.is_pressed(button);
+        if pressed == button.pressed.replace(pressed) || !pressed {
+            return false;
+        }
+        let now = self.time.now();
+        let bounce = button.last_press.get().map_or(false, |last| {
+            now.wrapping_sub(last) < self.time.ticks_from_ms(DEBOUNCE_MS)
+        });
+        if !bounce {
+            button.last_press.set(Some(now));
+        }
+        !bounce
+    }
+
+    /// Updates the text after one of the buttons
+    /// has been pressed or released
+    fn update(&self) {
+        // The interrupt does not tell which pin has changed,
+        // all the buttons are verified.
+        let speed = self.text.speed();
+        if self.is_new_press(&self.slower) {
+            self.text
+                .set_speed((speed.saturating_add(self.range.step_ms)).min(self.range.max_ms));
+        }
+        if self.is_new_press(&self.faster) {
+            self.text
+                .set_speed((speed.saturating_sub(self.range.step_ms)).max(self.range.min_ms));
+        }
+        if self.is_new_press(&self.pause) {
+            self.text.set_paused(!self.text.is_paused());
+        }
+    }
+}
+
+impl<'a, P: InterruptPin<'a> + Configure, T: TextSpeed + TextPause, A: Time> Client
+    for ButtonControls<'a, P, T, A>
+{
+    /// Called when one of the buttons is pressed or released
+    fn fired(&self) {
+        if self.enabled {
+            self.update();
+        }
+    }
+}
+
+impl<'a, P: InterruptPin<'a> + Configure, T: TextSpeed + TextPause, A: Time> ClientWithValue
+    for ButtonControls<'a, P, T, A>
+{
+    /// Called by the value wrapper of the pin that
+    /// has been pressed or released
+    fn fired(&self, value: u32) {
+        if self.enabled {
+            self.update();
+        }
+        self.client.map(|client| client.fired(value));
+    }
+}
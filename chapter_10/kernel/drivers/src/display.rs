@@ -0,0 +1,65 @@
+use crate::matrix_transform::Transform;
+use kernel::ErrorCode;
+
+/// The display settings of a text screen saved with its boot message
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TextSettings {
+    /// The number of milliseconds that each character,
+    /// or each column when scrolling, is displayed
+    pub speed: u32,
+
+    /// Whether the text scrolls one column at a time instead
+    /// of being displayed one character at a time
+    pub scroll: bool,
+
+    /// The number of blank columns after the scrolling text
+    pub gap: usize,
+
+    /// The number of milliseconds that the LEDs are off
+    /// between two characters
+    pub blank_ms: u32,
+}
+
+/// The interface of a store that keeps the boot message
+/// of a text screen across resets
+pub trait BootMessageStore {
+    /// Copies the text of the boot message to `text`
+    ///
+    /// Returns the length of the text and the settings with which it
+    /// is displayed, or `None` if no boot message has been saved.
+    fn load(&self, text: &mut [u8]) -> Option<(usize, TextSettings)>;
+
+    /// Replaces the boot message with `text`, displayed with `settings`
+    ///
+    /// The store may keep only the beginning of a long text. If the
+    /// new message cannot be saved, the previous one is kept.
+    fn save(&self, text: &[u8], settings: TextSettings) -> Result<(), ErrorCode>;
+}
+
+/// The interface of a text display that has an adjustable speed
+pub trait TextSpeed {
+    /// Sets the delay in milliseconds between two characters
+    fn set_speed(&self, speed_ms: u32);
+
+    /// Returns the delay in milliseconds between two characters
+    fn speed(&self) -> u32;
+}
+
+/// The interface of a text display that can be paused
+pub trait TextPause {
+    /// Freezes or resumes the displayed text
+    fn set_paused(&self, paused: bool);
+
+    /// Returns whether the displayed text is frozen
+    fn is_paused(&self) -> bool;
+}
+
+/// The interface of the drivers that display an image
+/// on the LED matrix and are able to transform it
+pub trait MatrixOrientation {
+    /// Sets the transformation applied to the displayed image
+    fn set_transform(&self, transform: Transform);
+
+    /// Returns the transformation applied to the displayed image
+    fn transform(&self) -> Transform;
+}
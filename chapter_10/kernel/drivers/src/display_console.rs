@@ -0,0 +1,486 @@
+use crate::display::TextSpeed;
+use core::cell::Cell;
+use core::str;
+use kernel::hil::text_screen::{TextScreen, TextScreenClient};
+use kernel::hil::uart::{self, Receive, ReceiveClient, Transmit, TransmitClient, UartData};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+
+/// The names of the display console's commands
+const COMMANDS: [&[u8]; 3] = [b"display", b"speed", b"clear"];
+
+/// The console that a command line belongs to
+#[derive(Copy, Clone, PartialEq)]
+enum Line {
+    /// The line received so far starts like a display command
+    Undecided,
+    /// The line is a display command
+    Display,
+    /// The line is forwarded to the process console
+    Console,
+}
+
+/// The steps of the screen request in progress
+#[derive(Copy, Clone, PartialEq)]
+enum Step {
+    /// No request in progress
+    Idle,
+    /// Erase the previous text before displaying the new one
+    ShowClear,
+    /// Turn the display on before writing the new text
+    ShowDisplay,
+    /// Write the new text
+    ShowPrint,
+    /// A single command: *clear*, *display on* or *display off*
+    Command,
+}
+
+/// Structure representing the display console
+///
+/// The console reads command lines from a UART, usually a device of
+/// the UART mux, and drives a text screen:
+///   - `display <text>` displays the text
+///   - `display on` and `display off` turn the display on and off
+///   - `clear` erases the text
+///   - `speed <ms>` sets the delay between two characters
+///
+/// The console is also the UART of the process console, to which it
+/// forwards the other lines. The characters of a line are held back
+/// while the line may still be a display command, so the process
+/// console never receives the display commands. The console echoes
+/// the display commands, the process console echoes its own lines.
+pub struct DisplayConsole<'a> {
+    /// The UART that receives the commands
+    uart: &'a dyn UartData<'a>,
+
+    /// The UART through which the process console transmits
+    console_uart: &'a dyn UartData<'a>,
+
+    /// The process console, that receives the other lines
+    console: OptionalCell<&'a dyn ReceiveClient>,
+
+    /// The buffer in which the process console receives a character
+    console_rx_buffer: TakeCell<'static, [u8]>,
+
+    /// The screen that the console drives
+    screen: &'a dyn TextScreen<'a>,
+
+    /// The display whose speed the console sets
+    speed: &'a dyn TextSpeed,
+
+    /// The buffer used to send the echo and the replies
+    tx_buffer: TakeCell<'static, [u8]>,
+
+    /// The characters waiting for the transmission in progress
+    queue: TakeCell<'static, [u8]>,
+
+    /// The number of characters in the queue
+    queue_len: Cell<usize>,
+
+    /// The buffer used to receive the characters, one at a time
+    rx_buffer: TakeCell<'static, [u8]>,
+
+    /// The command line being received
+    command: TakeCell<'static, [u8]>,
+
+    /// The length of the command line being received
+    command_len: Cell<usize>,
+
+    /// The console that the command line belongs to
+    line: Cell<Line>,
+
+    /// The buffer that stores the text while it is printed
+    text: TakeCell<'static, [u8]>,
+
+    /// The length of the text to print
+    text_len: Cell<usize>,
+
+    /// The current step of the screen request
+    step: Cell<Step>,
+}
+
+impl<'a> DisplayConsole<'a> {
+    /// Initializes a new console structure
+    ///
+    /// The longest command line is as long as the `command` buffer
+    /// and the longest text as long as the `text` buffer. The echo
+    /// received while a reply is sent has to fit in the `queue` buffer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        uart: &'a dyn UartData<'a>,
+        console_uart: &'a dyn UartData<'a>,
+        screen: &'a dyn TextScreen<'a>,
+        speed: &'a dyn TextSpeed,
+        tx_buffer: &'static mut [u8],
+        queue: &'static mut [u8],
+        rx_buffer: &'static mut [u8],
+        command: &'static mut [u8],
+        text: &'static mut [u8],
+    ) -> Self {
+        DisplayConsole {
+            uart,
+            console_uart,
+            console: OptionalCell::empty(),
+            console_rx_buffer: TakeCell::empty(),
+            screen,
+            speed,
+            tx_buffer: TakeCell::new(tx_buffer),
+            queue: TakeCell::new(queue),
+            queue_len: Cell::new(0),
+            rx_buffer: TakeCell::new(rx_buffer),
+            command: TakeCell::new(command),
+            command_len: Cell::new(0),
+            line: Cell::new(Line::Undecided),
+            text: TakeCell::new(text),
+            text_len: Cell::new(0),
+            step: Cell::new(Step::Idle),
+        }
+    }
+
+    /// Starts receiving commands
+    pub fn start(&self) -> Result<(), ErrorCode> {
+        match self.rx_buffer.take() {
+            Some(rx_buffer) => {
+                self.uart
+                    .receive_buffer(rx_buffer, 1)
+                    .map_err(|(err, rx_buffer)| {
+                        self.rx_buffer.replace(rx_buffer);
+                        err
+                    })
+            }
+            None => Err(ErrorCode::ALREADY),
+        }
+    }
+
+    /// Adds a received character to the command line and
+    /// executes the line when it ends
+    fn receive(&self, character: u8) {
+        if self.line.get() == Line::Console {
+            if character == b'\r' || character == b'\n' {
+                self.line.set(Line::Undecided);
+            }
+            self.forward(character);
+            return;
+        }
+        match character {
+            b'\r' | b'\n' => {
+                let len = self.command_len.replace(0);
+                let line = self.line.replace(Line::Undecided);
+                self.command.map(|command| {
+                    let command = &command[..len];
+                    if line == Line::Display || COMMANDS.iter().any(|name| *name == command) {
+                        if line == Line::Undecided {
+                            self.write(command);
+                        }
+                        self.write(b"\r\n");
+                        self.execute(command);
+                    } else {
+                        // Empty lines and lines that stopped
+                        // short of a command are not ours.
+                        for character in command.iter() {
+                            self.forward(*character);
+                        }
+                        self.forward(character);
+                    }
+                });
+            }
+            // Backspace and delete
+            0x08 | 0x7f => {
+                let len = self.command_len.get();
+                if len > 0 {
+                    self.command_len.set(len - 1);
+                    // The characters held back were not echoed.
+                    if self.line.get() == Line::Display {
+                        self.write(b"\x08 \x08");
+                    }
+                }
+            }
+            _ => {
+                let len = self.command_len.get();
+                if self.command.map_or(0, |command| command.len()) > len {
+                    self.command.map(|command| command[len] = character);
+                    self.command_len.set(len + 1);
+                    match self.line.get() {
+                        Line::Display => self.write(&[character]),
+                        _ => self.classify(),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decides whether the command line received so far
+    /// belongs to the display console or to the process console
+    fn classify(&self) {
+        self.command.map(|command| {
+            let command = &command[..self.command_len.get()];
+            if COMMANDS.iter().any(|name| {
+                command.len() > name.len()
+                    && command.starts_with(name)
+                    && command[name.len()] == b' '
+            }) {
+                self.line.set(Line::Display);
+                self.write(command);
+            } else if !COMMANDS.iter().any(|name| name.starts_with(command)) {
+                self.line.set(Line::Console);
+                self.command_len.set(0);
+                for character in command.iter() {
+                    self.forward(*character);
+                }
+            }
+        });
+    }
+
+    /// Delivers a character to the process console
+    ///
+    /// The character is lost if the process console is not receiving.
+    fn forward(&self, character: u8) {
+        if let Some(rx_buffer) = self.console_rx_buffer.take() {
+            rx_buffer[0] = character;
+            match self.console.get() {
+                Some(console) => console.received_buffer(rx_buffer, 1, Ok(()), uart::Error::None),
+                None => {
+                    self.console_rx_buffer.replace(rx_buffer);
+                }
+            }
+        }
+    }
+
+    /// Executes a command line
+    fn execute(&self, line: &[u8]) {
+        let line = trim(line);
+        let (name, argument) = match line.iter().position(|character| *character == b' ') {
+            Some(space) => (&line[..space], trim(&line[space + 1..])),
+            None => (line, &line[line.len()..]),
+        };
+        match name {
+            b"display" => match argument {
+                b"" => self.reply(b"usage: display <text>|on|off"),
+                b"on" => self.request(Step::Command, || self.screen.display_on()),
+                b"off" => self.request(Step::Command, || self.screen.display_off()),
+                text => self.show(text),
+            },
+            b"speed" => match str::from_utf8(argument)
+                .ok()
+                .and_then(|speed| speed.parse::<u32>().ok())
+            {
+                Some(speed) if speed > 0 => {
+                    self.speed.set_speed(speed);
+                    self.reply(b"display: ok");
+                }
+                _ => self.reply(b"usage: speed <ms>"),
+            },
+            b"clear" => self.request(Step::Command, || self.screen.clear()),
+            // The other lines were forwarded to the process console.
+            _ => {}
+        }
+    }
+
+    /// Displays `text`, truncated to the size of the text buffer
+    fn show(&self, text: &[u8]) {
+        if self.step.get() == Step::Idle {
+            self.text.map(|buffer| {
+                let len = text.len().min(buffer.len());
+                buffer[..len].copy_from_slice(&text[..len]);
+                self.text_len.set(len);
+            });
+        }
+        self.request(Step::ShowClear, || self.screen.clear());
+    }
+
+    /// Sends a screen request, unless another one is in progress
+    fn request(&self, step: Step, request: impl FnOnce() -> Result<(), ErrorCode>) {
+        if self.step.get() == Step::Idle {
+            self.step.set(step);
+            if let Err(err) = request() {
+                self.done(Err(err));
+            }
+        } else {
+            self.reply(b"display: busy");
+        }
+    }
+
+    /// Ends the screen request and replies with its result
+    fn done(&self, res: Result<(), ErrorCode>) {
+        self.step.set(Step::Idle);
+        match res {
+            Ok(()) => self.reply(b"display: ok"),
+            Err(ErrorCode::BUSY) => self.reply(b"display: busy"),
+            Err(_) => self.reply(b"display: error"),
+        }
+    }
+
+    /// Sends a reply line
+    fn reply(&self, message: &[u8]) {
+        self.write(message);
+        self.write(b"\r\n");
+    }
+
+    /// Adds `characters` to the queue and sends them, unless
+    /// another transmission is in progress
+    ///
+    /// The characters that do not fit in the queue are lost.
+    fn write(&self, characters: &[u8]) {
+        self.queue.map(|queue| {
+            let len = self.queue_len.get();
+            let count = characters.len().min(queue.len() - len);
+            queue[len..len + count].copy_from_slice(&characters[..count]);
+            self.queue_len.set(len + count);
+        });
+        self.send();
+    }
+
+    /// Sends the queued characters, unless another
+    /// transmission is in progress
+    fn send(&self) {
+        let len = self.queue_len.get();
+        if len == 0 {
+            return;
+        }
+        if let Some(tx_buffer) = self.tx_buffer.take() {
+            let len = len.min(tx_buffer.len());
+            self.queue.map(|queue| {
+                tx_buffer[..len].copy_from_slice(&queue[..len]);
+                queue.copy_within(len..self.queue_len.get(), 0);
+            });
+            self.queue_len.set(self.queue_len.get() - len);
+            if let Err((_, tx_buffer)) = self.uart.transmit_buffer(tx_buffer, len) {
+                self.tx_buffer.replace(tx_buffer);
+            }
+        }
+    }
+}
+
+/// Removes the spaces from the start and the end of `text`
+fn trim(text: &[u8]) -> &[u8] {
+    let start = text
+        .iter()
+        .position(|character| *character != b' ')
+        .unwrap_or(text.len());
+    let end = text
+        .iter()
+        .rposition(|character| *character != b' ')
+        .map_or(start, |last| last + 1);
+    &text[start..end]
+}
+
+/// This implementation allows `DisplayConsole` to receive the screen's callbacks.
+impl<'a> TextScreenClient for DisplayConsole<'a> {
+    fn command_complete(&self, r: Result<(), ErrorCode>) {
+        match (self.step.get(), r) {
+            (Step::ShowClear, Ok(())) => {
+                // Turn the display on first, so that the screen
+                // displays the text as soon as it is written.
+                self.step.set(Step::ShowDisplay);
+                if let Err(err) = self.screen.display_on() {
+                    self.done(Err(err));
+                }
+            }
+            (Step::ShowDisplay, Ok(())) => match self.text.take() {
+                Some(text) => {
+                    self.step.set(Step::ShowPrint);
+                    if let Err((err, text)) = self.screen.print(text, self.text_len.get()) {
+                        self.text.replace(text);
+                        self.done(Err(err));
+                    }
+                }
+                None => self.done(Err(ErrorCode::NOMEM)),
+            },
+            (Step::Idle, _) | (Step::ShowPrint, _) => {}
+            (_, r) => self.done(r),
+        }
+    }
+
+    fn write_complete(&self, buffer: &'static mut [u8], _len: usize, r: Result<(), ErrorCode>) {
+        self.text.replace(buffer);
+        self.done(r);
+    }
+}
+
+/// This implementation allows `DisplayConsole` to receive the commands.
+impl<'a> ReceiveClient for DisplayConsole<'a> {
+    fn received_buffer(
+        &self,
+        rx_buffer: &'static mut [u8],
+        rx_len: usize,
+        rval: Result<(), ErrorCode>,
+        _error: uart::Error,
+    ) {
+        if rval.is_ok() && rx_len > 0 {
+            self.receive(rx_buffer[0]);
+        }
+        // Wait for the next character.
+        if let Err((_, rx_buffer)) = self.uart.receive_buffer(rx_buffer, 1) {
+            self.rx_buffer.replace(rx_buffer);
+        }
+    }
+}
+
+/// This implementation allows `DisplayConsole` to get its replies' buffer back.
+impl<'a> TransmitClient for DisplayConsole<'a> {
+    fn transmitted_buffer(
+        &self,
+        tx_buffer: &'static mut [u8],
+        _tx_len: usize,
+        _rval: Result<(), ErrorCode>,
+    ) {
+        self.tx_buffer.replace(tx_buffer);
+        self.send();
+    }
+}
+
+/// The process console transmits through its own UART.
+impl<'a> Transmit<'a> for DisplayConsole<'a> {
+    fn set_transmit_client(&self, client: &'a dyn TransmitClient) {
+        self.console_uart.set_transmit_client(client);
+    }
+
+    fn transmit_buffer(
+        &self,
+        tx_buffer: &'static mut [u8],
+        tx_len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        self.console_uart.transmit_buffer(tx_buffer, tx_len)
+    }
+
+    fn transmit_word(&self, word: u32) -> Result<(), ErrorCode> {
+        self.console_uart.transmit_word(word)
+    }
+
+    fn transmit_abort(&self) -> Result<(), ErrorCode> {
+        self.console_uart.transmit_abort()
+    }
+}
+
+/// The process console receives the lines that are not display
+/// commands, one character at a time.
+impl<'a> Receive<'a> for DisplayConsole<'a> {
+    fn set_receive_client(&self, client: &'a dyn ReceiveClient) {
+        self.console.set(client);
+    }
+
+    fn receive_buffer(
+        &self,
+        rx_buffer: &'static mut [u8],
+        rx_len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if self.console_rx_buffer.is_some() {
+            Err((ErrorCode::BUSY, rx_buffer))
+        } else if rx_len == 0 || rx_len > rx_buffer.len() {
+            Err((ErrorCode::SIZE, rx_buffer))
+        } else {
+            self.console_rx_buffer.replace(rx_buffer);
+            Ok(())
+        }
+    }
+
+    fn receive_word(&self) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+
+    fn receive_abort(&self) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+}
+
+impl<'a> UartData<'a> for DisplayConsole<'a> {}
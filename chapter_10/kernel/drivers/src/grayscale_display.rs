@@ -0,0 +1,289 @@
+use crate::display::MatrixOrientation;
+use crate::matrix_transform::{Rotation, Transform};
+use core::cell::Cell;
+use core::mem;
+use kernel::grant::Grant;
+use kernel::hil::led::Led;
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::process::{Error, ProcessId};
+use kernel::processbuffer::{ReadOnlyProcessBuffer, ReadableProcessBuffer};
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::utilities::cells::TakeCell;
+use kernel::ErrorCode;
+
+/// The driver number
+///
+/// As this is not one of Tock's standard drivers,
+/// its number has to be higher or equal to 0xa0000.
+///
+/// Our previous driver was 0xa0003 so we use the
+/// number available.
+pub const DRIVER_NUM: usize = 0xa0004;
+
+/// The number of LEDs of the matrix, which is also
+/// the size of the framebuffer
+pub const FRAMEBUFFER_LEN: usize = 25;
+
+/// The number of brightness levels that the driver
+/// is able to display
+const LEVELS: usize = 8;
+
+/// The time in microseconds of a PWM slice
+///
+/// A full PWM period has `LEVELS` slices.
+const SLICE_US: u32 = 1000;
+
+/// The data type that will be stored in each
+/// process' grant.
+#[derive(Default)]
+pub struct AppData {
+    /// The buffer shared by the process with the driver
+    /// that contains the brightness of each LED.
+    buffer: ReadOnlyProcessBuffer,
+}
+
+/// Structure representing the driver
+///
+/// The driver keeps the brightness of each LED in a framebuffer,
+/// from 0 (off) to 255 (fully on). Intermediate values are displayed
+/// using software PWM: the PWM period is split into `LEVELS` slices
+/// and, in each slice, an LED is on only if its brightness is above
+/// the slice's threshold.
+///
+/// The driver expects to be the only user of the LEDs. It does not
+/// know about the other drivers that the board gives the same LEDs
+/// to, such as `LedMatrixText`, so if they display at the same time,
+/// each one overwrites the LEDs set by the other. Processes should
+/// turn off one display (command 2 here) before using the other.
+pub struct GrayscaleDisplay<'a, L: Led, A: Alarm<'a>> {
+    /// the a slice of Matrix LEDs
+    /// LED 0 is upper left, LED 24 is lower right
+    leds: &'a [&'a L],
+
+    /// The alarm used to switch between PWM slices
+    alarm: &'a A,
+
+    /// The grant entrypoint
+    ///
+    /// The data type stored by the grant is `AppData` and
+    /// the driver does not use upcalls.
+    grant: Grant<AppData, 0>,
+
+    /// The framebuffer, storing the brightness of each LED
+    framebuffer: TakeCell<'a, [u8]>,
+
+    /// The PWM slice that is displayed
+    slice: Cell<usize>,
+
+    /// The rotation and mirroring applied to the framebuffer
+    transform: Cell<Transform>,
+}
+
+impl<'a, L: Led, A: Alarm<'a>> GrayscaleDisplay<'a, L, A> {
+    /// Initializes a new driver structure
+    pub fn new(
+        leds: &'a [&'a L],
+        alarm: &'a A,
+        framebuffer: &'a mut [u8; FRAMEBUFFER_LEN],
+        grant: Grant<AppData, 0>,
+    ) -> Self {
+        if leds.len() != FRAMEBUFFER_LEN {
+            panic!(
+                "Expecting {} LEDs, {} supplied",
+                FRAMEBUFFER_LEN,
+                leds.len()
+            );
+        }
+        GrayscaleDisplay {
+            leds,
+            alarm,
+            grant,
+            framebuffer: TakeCell::new(framebuffer),
+            slice: Cell::new(0),
+            transform: Cell::new(Transform::IDENTITY),
+        }
+    }
+
+    /// Returns the minimum brightness that an LED must have
+    /// to be on during the PWM `slice`
+    fn threshold(slice: usize) -> usize {
+        slice * (256 / LEVELS)
+    }
+
+    /// Displays the current PWM slice of the framebuffer
+    ///
+    /// Returns whether the framebuffer has intermediate brightness
+    /// levels, meaning that the driver has to keep switching slices.
+    fn display_slice(&self) -> bool {
+        let threshold = Self::threshold(self.slice.get());
+        let transform = self.transform.get();
+        self.framebuffer.map_or(false, |framebuffer| {
+            let mut needs_pwm = false;
+            for (index, brightness) in framebuffer.iter().enumerate() {
+                let led = self.leds[transform.led(index)];
+                if *brightness as usize > threshold {
+                    led.on();
+                } else {
+                    led.off();
+                }
+                needs_pwm = needs_pwm || (*brightness > 0 && *brightness < u8::MAX);
+            }
+            needs_pwm
+        })
+    }
+
+    /// Displays the next PWM slice and sets up the alarm for
+    /// the following one
+    fn display_next(&self) {
+        self.slice.set((self.slice.get() + 1) % LEVELS);
+        if self.display_slice() {
+            self.alarm
+                .set_alarm(self.alarm.now(), self.alarm.ticks_from_us(SLICE_US));
+        }
+    }
+
+    /// Displays the framebuffer starting with the first PWM slice
+    fn refresh(&self) {
+        let _ = self.alarm.disarm();
+        self.slice.set(0);
+        // If all the LEDs are either fully on or off, the first slice
+        // displays the framebuffer and there is no need for an alarm.
+        // This allows the MCU to enter low power modes.
+        if self.display_slice() {
+            self.alarm
+                .set_alarm(self.alarm.now(), self.alarm.ticks_from_us(SLICE_US));
+        }
+    }
+
+    /// Copies the framebuffer from the buffer shared by the process
+    fn commit(&self, process_id: ProcessId) -> Result<(), ErrorCode> {
+        let res = self.grant.enter(process_id, |app, _| {
+            // Verify is the process has previously shared a buffer.
+            if app.buffer.len() > 0 {
+                // Verify that the buffer stores the brightness of all the LEDs.
+                if app.buffer.len() >= FRAMEBUFFER_LEN {
+                    app.buffer
+                        .enter(|buffer| {
+                            self.framebuffer.map(|framebuffer| {
+                                for index in 0..FRAMEBUFFER_LEN {
+                                    framebuffer[index] = buffer[index].get();
+                                }
+                            });
+                        })
+                        .map_err(|err| err.into())
+                } else {
+                    Err(ErrorCode::SIZE)
+                }
+            } else {
+                // The process has not shared with us a buffer.
+                Err(ErrorCode::NOMEM)
+            }
+        });
+        match res {
+            Ok(Ok(())) => {
+                self.refresh();
+                Ok(())
+            }
+            Ok(Err(err)) => Err(err),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Turns off all the LEDs
+    fn clear(&self) {
+        self.framebuffer.map(|framebuffer| {
+            for brightness in framebuffer.iter_mut() {
+                *brightness = 0;
+            }
+        });
+        self.refresh();
+    }
+}
+
+/// The implementation of `SyscallDriver` makes `GrayscaleDisplay` a syscall driver
+impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for GrayscaleDisplay<'a, L, A> {
+    fn allow_readonly(
+        &self,
+        process_id: ProcessId,
+        allow_number: usize,
+        mut buffer: ReadOnlyProcessBuffer,
+    ) -> Result<ReadOnlyProcessBuffer, (ReadOnlyProcessBuffer, ErrorCode)> {
+        match allow_number {
+            // The process has shared or unshared a framebuffer with us
+            0 => {
+                let res = self.grant.enter(process_id, |app, _| {
+                    mem::swap(&mut app.buffer, &mut buffer);
+                });
+                match res {
+                    Ok(()) => Ok(buffer),
+                    Err(err) => Err((buffer, err.into())),
+                }
+            }
+            _ => Err((buffer, ErrorCode::NOSUPPORT)),
+        }
+    }
+
+    fn allocate_grant(&self, process_id: ProcessId) -> Result<(), Error> {
+        self.grant.enter(process_id, |_, _| {})
+    }
+
+    fn command(
+        &self,
+        command_number: usize,
+        r2: usize,
+        r3: usize,
+        process_id: ProcessId,
+    ) -> CommandReturn {
+        match command_number {
+            // Tock's convention states that all syscall drivers must return *success* or *success_...* for
+            // command number 0. This allows processes to verify if a driver is present.
+            0 => CommandReturn::success(),
+            // Display the framebuffer shared by the process
+            1 => match self.commit(process_id) {
+                Ok(()) => CommandReturn::success(),
+                Err(err) => CommandReturn::failure(err),
+            },
+            // Turn off all the LEDs
+            2 => {
+                self.clear();
+                CommandReturn::success()
+            }
+            // Rotate the framebuffer clockwise by the number of degrees stored
+            // in *r2* (0, 90, 180 or 270) and mirror it horizontally if *r3* is 1
+            3 => match (Rotation::from_degrees(r2), r3) {
+                (Some(rotation), 0 | 1) => {
+                    self.set_transform(Transform {
+                        rotation,
+                        mirror: r3 == 1,
+                    });
+                    CommandReturn::success()
+                }
+                _ => CommandReturn::failure(ErrorCode::INVAL),
+            },
+            // Inform the process that we do not understand the command
+            _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
+        }
+    }
+}
+
+/// This implementation allows the kernel to rotate and mirror the framebuffer.
+impl<'a, L: Led, A: Alarm<'a>> MatrixOrientation for GrayscaleDisplay<'a, L, A> {
+    fn set_transform(&self, transform: Transform) {
+        self.transform.set(transform);
+        self.refresh();
+    }
+
+    fn transform(&self) -> Transform {
+        self.transform.get()
+    }
+}
+
+/// This implementation allows `GrayscaleDisplay` to use an alarm.
+impl<'a, L: Led, A: Alarm<'a>> AlarmClient for GrayscaleDisplay<'a, L, A> {
+    /// Called when the alarm expires
+    fn alarm(&self) {
+        // The current PWM slice has been displayed enough,
+        // display the next one
+        self.display_next();
+    }
+}
@@ -1,11 +1,14 @@
+use crate::display::{BootMessageStore, MatrixOrientation, TextPause, TextSettings, TextSpeed};
+use crate::matrix_transform::{Rotation, Transform};
 use core::cell::Cell;
 use core::cmp;
+use font::{Font, Font5x5, GLYPH_HEIGHT, GLYPH_WIDTH};
 use kernel::dynamic_deferred_call::{
     DeferredCallHandle, DynamicDeferredCall, DynamicDeferredCallClient,
 };
 use kernel::hil::led::Led;
 use kernel::hil::text_screen::{TextScreen, TextScreenClient};
-use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks, Ticks};
 use kernel::process::{Error, ProcessId};
 use kernel::syscall::{CommandReturn, SyscallDriver};
 use kernel::utilities::cells::{OptionalCell, TakeCell};
@@ -20,91 +23,16 @@ use kernel::ErrorCode;
 /// number available.
 pub const DRIVER_NUM: usize = 0xa0003;
 
-/// Font glyph definition for digits
-///
-/// A font glyph is a set of bits that represents that
-/// state of the LEDs
-const DIGITS: [u32; 10] = [
-    // 0
-    0b11111_10011_10101_11001_11111,
-    // 1
-    0b00100_01100_00100_00100_01110,
-    // 2
-    0b11110_00001_01110_10000_11111,
-    // 3
-    0b11110_00001_11110_00001_11110,
-    // 4
-    0b10000_10000_10100_11111_00100,
-    // 5
-    0b11111_10000_11110_00001_11110,
-    // 6
-    0b11111_10000_11111_10001_11111,
-    // 7
-    0b11111_00001_00010_00100_00100,
-    // 8
-    0b11111_10001_11111_10001_11111,
-    // 9
-    0b11111_10001_11111_00001_11111,
-];
-
-/// Font glyph definition for capital letters
-///
-/// A font glyph is a set of bits that represents that
-/// state of the LEDs
-const LETTERS: [u32; 26] = [
-    // A
-    0b01110_10001_11111_10001_10001,
-    // B
-    0b11111_10001_11110_10001_11111,
-    // C
-    0b11111_10000_10000_10000_11111,
-    // D
-    0b11110_10001_10001_10001_11110,
-    // E
-    0b11111_10000_11110_10000_11111,
-    // F
-    0b11111_10000_11110_10000_10000,
-    // G
-    0b11111_10000_10111_10001_11111,
-    // H
-    0b10001_10001_11111_10001_10001,
-    // I
-    0b11111_00100_00100_00100_11111,
-    // J
-    0b00011_00001_00001_10001_11111,
-    // K
-    0b10001_10010_11100_10010_10001,
-    // L
-    0b10000_10000_10000_10000_11111,
-    // M
-    0b10001_11011_10101_10001_10001,
-    // N
-    0b10001_11001_10101_10011_10001,
-    // O
-    0b01110_10001_10001_10001_01110,
-    // P
-    0b11110_10001_11110_10000_10000,
-    // Q
-    0b01110_10001_10001_01110_00011,
-    // R
-    0b11110_10001_11110_10001_10001,
-    // S
-    0b11111_10000_11111_00001_11111,
-    // T
-    0b11111_00100_00100_00100_00100,
-    // U
-    0b10001_10001_10001_10001_11111,
-    // V
-    0b10001_10001_01010_01010_00100,
-    // W
-    0b10001_10001_10101_10101_01010,
-    // X
-    0b10001_01010_00100_01010_10001,
-    // Y
-    0b10001_10001_01010_00100_00100,
-    // Z
-    0b11111_00010_00100_01000_11111,
-];
+/// The default number of blank columns displayed in scroll mode
+/// after the text and before it repeats (a full blank screen)
+const DEFAULT_SCROLL_GAP: usize = GLYPH_WIDTH;
+
+/// The glyph bits of all the LEDs, used to invert a glyph
+const ALL_LEDS: u32 = (1 << 25) - 1;
+
+/// The number of milliseconds that the highlight of a
+/// blinking cursor stays on, and then off
+const CURSOR_BLINK_MS: u32 = 500;
 
 /// The possible states
 #[derive(Copy, Clone, PartialEq)]
@@ -117,6 +45,24 @@ enum Status {
     ExecutesPrint,
 }
 
+/// The ways in which the driver displays the text
+#[derive(Copy, Clone, PartialEq)]
+enum Mode {
+    /// Displays one character at a time
+    Character,
+    /// Scrolls the text horizontally, one column at a time
+    Scroll,
+}
+
+/// What the LED matrix shows until the alarm fires
+#[derive(Copy, Clone, PartialEq)]
+enum Phase {
+    /// The LEDs are off, between two characters
+    Blank,
+    /// A character
+    Character,
+}
+
 /// Structure representing the driver
 pub struct LedMatrixText<'a, L: Led, A: Alarm<'a>> {
     /// the a slice of Matrix LEDs
@@ -131,13 +77,56 @@ pub struct LedMatrixText<'a, L: Led, A: Alarm<'a>> {
     client: OptionalCell<&'a dyn TextScreenClient>,
 
     /// The driver's buffer
+    ///
+    /// The buffer stores a virtual screen of `columns` characters
+    /// per row, row after row. Cells that have not been written
+    /// store 0.
     buffer: TakeCell<'a, [u8]>,
 
-    /// The position within the driver's buffer that will
+    /// The number of characters of a row of the virtual screen
+    columns: usize,
+
+    /// The position within the buffer where the next *print*
+    /// request writes the text
+    cursor: Cell<usize>,
+
+    /// Stores if a *print* request replaces the whole text
+    ///   - false means that the text is written at the cursor,
+    ///     over the characters that are already there
+    ///   - true means that the screen is erased and the text
+    ///     is written at the upper left corner
+    replace: Cell<bool>,
+
+    /// Stores if the character at the cursor is highlighted
+    /// by displaying its glyph inverted.
+    cursor_visible: Cell<bool>,
+
+    /// Stores if the cursor's highlight blinks.
+    cursor_blink: Cell<bool>,
+
+    /// Stores if the blinking highlight is currently on. It
+    /// toggles every `CURSOR_BLINK_MS` milliseconds.
+    cursor_lit: Cell<bool>,
+
+    /// The time at which the blinking highlight last toggled
+    blink_start: Cell<A::Ticks>,
+
+    /// The time at which the displayed frame started
+    /// and the number of ticks that it lasts
+    frame: Cell<(A::Ticks, A::Ticks)>,
+
+    /// The position within the displayed text that will
     /// be displayed next
+    ///
+    /// In scroll mode, this is the column of the text that
+    /// will be displayed next in the left column of the matrix.
     position: Cell<usize>,
 
-    /// The length of the text stored in the driver's buffer
+    /// The length of the displayed text
+    ///
+    /// The displayed text is made of the screen's rows, in order. Each
+    /// row ends after its last written character and is separated
+    /// from the next row by a space. Rows without text are skipped.
     len: Cell<usize>,
 
     /// A temporary buffer received by the driver from the client.
@@ -153,6 +142,29 @@ pub struct LedMatrixText<'a, L: Led, A: Alarm<'a>> {
     /// expressed in milliseconds delay between to letters or digits.
     speed: Cell<u32>,
 
+    /// The way in which the driver displays the text.
+    mode: Cell<Mode>,
+
+    /// The number of blank columns displayed in scroll mode
+    /// after the text and before it repeats.
+    gap: Cell<usize>,
+
+    /// The number of milliseconds that the LEDs are off
+    /// between two characters in character mode.
+    blank_ms: Cell<u32>,
+
+    /// What the LED matrix shows until the alarm fires.
+    phase: Cell<Phase>,
+
+    /// Stores if the text is paused, frozen on its current frame.
+    paused: Cell<bool>,
+
+    /// Stores if the alarm has expired while the text was paused.
+    stalled: Cell<bool>,
+
+    /// The rotation and mirroring applied to the glyphs.
+    transform: Cell<Transform>,
+
     /// The status of the driver.
     status: Cell<Status>,
 
@@ -168,6 +180,17 @@ pub struct LedMatrixText<'a, L: Led, A: Alarm<'a>> {
     /// The handle (position in the kernel's deferred callbacks array)
     /// to the driver's deferred callback function
     deferred_call_handle: OptionalCell<DeferredCallHandle>,
+
+    /// An optional store that keeps the text and the settings
+    /// that the driver displays after a reset
+    boot_message_store: OptionalCell<&'a dyn BootMessageStore>,
+
+    /// The minimum number of milliseconds between two saves
+    /// of the boot message
+    save_interval_ms: Cell<u32>,
+
+    /// The time at which the boot message was last saved
+    last_save: OptionalCell<A::Ticks>,
 }
 
 impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
@@ -176,27 +199,128 @@ impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
         leds: &'a [&'a L],
         alarm: &'a A,
         buffer: &'a mut [u8],
+        columns: usize,
         speed: u32,
         deferred_caller: &'a DynamicDeferredCall,
     ) -> Self {
         if leds.len() != 25 {
             panic!("Expecting 25 LEDs, {} supplied", leds.len());
         }
+        if columns == 0 || columns > buffer.len() {
+            panic!(
+                "Expecting between 1 and {} columns, {} supplied",
+                buffer.len(),
+                columns
+            );
+        }
+        // The screen has only full rows, the cells left after
+        // the last one are not used.
+        let len = buffer.len() - buffer.len() % columns;
+        let buffer = &mut buffer[..len];
         LedMatrixText {
             leds: leds,
             alarm: alarm,
             buffer: TakeCell::new(buffer),
+            columns: columns,
+            cursor: Cell::new(0),
+            replace: Cell::new(false),
+            cursor_visible: Cell::new(false),
+            cursor_blink: Cell::new(false),
+            cursor_lit: Cell::new(true),
+            blink_start: Cell::new(A::Ticks::from(0)),
+            frame: Cell::new((A::Ticks::from(0), A::Ticks::from(0))),
             client_buffer: TakeCell::empty(),
             client_len: Cell::new(0),
             position: Cell::new(0),
             speed: Cell::new(speed),
             len: Cell::new(0),
+            mode: Cell::new(Mode::Character),
+            gap: Cell::new(DEFAULT_SCROLL_GAP),
+            blank_ms: Cell::new(0),
+            phase: Cell::new(Phase::Blank),
+            paused: Cell::new(false),
+            stalled: Cell::new(false),
+            transform: Cell::new(Transform::IDENTITY),
             status: Cell::new(Status::Idle),
             is_enabled: Cell::new(false),
             deferred_caller: deferred_caller,
             deferred_call_handle: OptionalCell::empty(),
             client: OptionalCell::empty(),
+            boot_message_store: OptionalCell::empty(),
+            save_interval_ms: Cell::new(0),
+            last_save: OptionalCell::empty(),
+        }
+    }
+
+    /// Sets the store of the boot message and displays the
+    /// boot message, if one has been saved
+    ///
+    /// Saving writes and erases the flash, processes may save the boot
+    /// message at most once every `save_interval_ms` milliseconds.
+    /// This has to be called once, while the board boots.
+    pub fn set_boot_message_store(&self, store: &'a dyn BootMessageStore, save_interval_ms: u32) {
+        self.boot_message_store.set(store);
+        self.save_interval_ms.set(save_interval_ms);
+        let loaded = self.buffer.map_or(false, |buffer| {
+            if let Some((len, settings)) = store.load(buffer) {
+                // Erase whatever was left after the text.
+                for character in buffer[len..].iter_mut() {
+                    *character = 0;
+                }
+                self.cursor.set(len % buffer.len());
+                self.speed.set(settings.speed);
+                self.mode.set(if settings.scroll {
+                    Mode::Scroll
+                } else {
+                    Mode::Character
+                });
+                self.gap
+                    .set(settings.gap.min(buffer.len() * (GLYPH_WIDTH + 1)));
+                self.blank_ms.set(settings.blank_ms);
+                true
+            } else {
+                false
+            }
+        });
+        if loaded {
+            // Turn the display on, there is no process yet to do it.
+            self.is_enabled.set(true);
+            self.update_len();
+        }
+    }
+
+    /// Saves the driver's text and settings as the boot message
+    fn save_boot_message(&self) -> Result<(), ErrorCode> {
+        let store = self
+            .boot_message_store
+            .extract()
+            .ok_or(ErrorCode::NOSUPPORT)?;
+        let now = self.alarm.now();
+        let interval = self.alarm.ticks_from_ms(self.save_interval_ms.get());
+        if self
+            .last_save
+            .map_or(false, |last| now.wrapping_sub(*last) < interval)
+        {
+            return Err(ErrorCode::BUSY);
         }
+        self.buffer.map_or(Err(ErrorCode::NOMEM), |buffer| {
+            // Save the screen up to its last written character.
+            let len = buffer
+                .iter()
+                .rposition(|character| *character != 0)
+                .map_or(0, |last| last + 1);
+            // A failed save may have written the flash as well.
+            self.last_save.set(now);
+            store.save(
+                &buffer[..len],
+                TextSettings {
+                    speed: self.speed.get(),
+                    scroll: self.mode.get() == Mode::Scroll,
+                    gap: self.gap.get(),
+                    blank_ms: self.blank_ms.get(),
+                },
+            )
+        })
     }
 
     /// Set the driver's deferred callback function
@@ -210,8 +334,113 @@ impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
             .map(|handle| self.deferred_caller.set(*handle));
     }
 
-    /// Displays the next letter or digit from the driver's buffer
+    /// Displays the next frame of the text, depending on the display mode
     fn display_next(&self) {
+        // Verify if the LEDs have to be turned off before the next
+        // character, so that two identical consecutive characters
+        // are distinguishable.
+        if self.mode.get() == Mode::Character
+            && self.phase.get() == Phase::Character
+            && self.blank_ms.get() > 0
+        {
+            self.clear();
+            self.phase.set(Phase::Blank);
+            // Set up an alarm for the end of the gap.
+            self.schedule(self.blank_ms.get());
+            return;
+        }
+        match self.mode.get() {
+            Mode::Character => self.display_next_character(),
+            Mode::Scroll => self.display_next_column(),
+        }
+        // If the length the text is greater then 0, set the next alarm.
+        // If we have no letters or digits to display, the text's length
+        // is 0, it is pointless to schedule an alarm as next
+        // time the alarm fires there will still be no text to display.
+        // Not setting the alarm allows the MCU to enter low power
+        // modes (if there are no other taks pending).
+        if self.len.get() > 0 {
+            self.schedule(self.speed.get());
+        }
+    }
+
+    /// Displays the current frame for `ms` milliseconds
+    fn schedule(&self, ms: u32) {
+        self.frame
+            .set((self.alarm.now(), self.alarm.ticks_from_ms(ms)));
+        self.schedule_alarm();
+    }
+
+    /// Returns the number of ticks left until `dt` ticks after `reference`
+    fn remaining(&self, reference: A::Ticks, dt: A::Ticks) -> A::Ticks {
+        let elapsed = self.alarm.now().wrapping_sub(reference);
+        if elapsed < dt {
+            dt.wrapping_sub(elapsed)
+        } else {
+            A::Ticks::from(0)
+        }
+    }
+
+    /// Sets up the alarm for the end of the displayed frame, or
+    /// earlier if the highlight of a blinking cursor toggles before
+    fn schedule_alarm(&self) {
+        let (reference, dt) = self.frame.get();
+        let mut remaining = self.remaining(reference, dt);
+        if self.is_blinking() {
+            remaining = cmp::min(
+                remaining,
+                self.remaining(
+                    self.blink_start.get(),
+                    self.alarm.ticks_from_ms(CURSOR_BLINK_MS),
+                ),
+            );
+        }
+        self.alarm.set_alarm(self.alarm.now(), remaining);
+    }
+
+    /// Returns whether the cursor is displayed with a blinking highlight
+    fn is_blinking(&self) -> bool {
+        self.cursor_visible.get() && self.cursor_blink.get()
+    }
+
+    /// Toggles the highlight of a blinking cursor if its half period
+    /// has elapsed and displays the current frame again if it is not over
+    ///
+    /// Returns whether the frame is not over.
+    fn blink(&self) -> bool {
+        let half_period = self.alarm.ticks_from_ms(CURSOR_BLINK_MS);
+        if !self.is_blinking()
+            || self.remaining(self.blink_start.get(), half_period) != A::Ticks::from(0)
+        {
+            return false;
+        }
+        self.cursor_lit.set(!self.cursor_lit.get());
+        self.blink_start.set(self.alarm.now());
+        let (reference, dt) = self.frame.get();
+        if self.remaining(reference, dt) == A::Ticks::from(0) {
+            return false;
+        }
+        // Display the frame again, the position is moved back
+        // to the frame that is displayed.
+        if self.position.get() > 0 {
+            match self.mode.get() {
+                Mode::Character if self.phase.get() == Phase::Character => {
+                    self.position.set(self.position.get() - 1);
+                    self.display_next_character();
+                }
+                Mode::Character => {}
+                Mode::Scroll => {
+                    self.position.set(self.position.get() - 1);
+                    self.display_next_column();
+                }
+            }
+        }
+        self.schedule_alarm();
+        true
+    }
+
+    /// Displays the next letter or digit from the driver's buffer
+    fn display_next_character(&self) {
         // Verify if we are at the end of the buffer.
         if self.position.get() >= self.len.get() {
             // Reset the position to the start of the buffer.
@@ -222,16 +451,19 @@ impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
         // of the text is 0.
         if self.position.get() < self.len.get() {
             if !self.buffer.map_or(false, |buffer| {
-                // Make sure we are within the buffers length
-                if self.position.get() < buffer.len() {
+                // Make sure we are within the text's length
+                if let Some((character, highlight)) =
+                    self.text_character(buffer, self.position.get())
+                {
                     // Display the letter or digit.
-                    let _ = self.display(buffer[self.position.get()] as char);
+                    let _ = self.display(character, highlight);
+                    self.phase.set(Phase::Character);
                     // We successfully displayed a letter or a digit,
                     // so we increase the current position
                     self.position.set(self.position.get() + 1);
                     true
                 } else {
-                    // We are overflowing the buffer
+                    // We are overflowing the text
                     // This should never happen if our driver is correctly written.
                     false
                 }
@@ -246,24 +478,134 @@ impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
             // Clear that is displayed right now.
             self.clear();
         }
-        // If the length the text is greater then 0, set the next alarm.
-        // If we have no letters or digits to display, the text's length
-        // is 0, it is pointless to schedule an alarm as next
-        // time the alarm fires there will still be no text to display.
-        // Not setting the alarm allows the MCU to enter low power
-        // modes (if there are no other taks pending).
-        if self.len.get() > 0 {
-            self.alarm
-                .set_alarm(self.alarm.now(), self.alarm.ticks_from_ms(self.speed.get()));
+    }
+
+    /// Scrolls the text one column to the left
+    ///
+    /// The text is seen as a strip of columns: the columns of each
+    /// character's glyph, separated by one blank column. The strip
+    /// ends with `gap` blank columns, after which it starts again
+    /// with the first character. The matrix displays 5 consecutive
+    /// columns of the strip, starting with the column at `position`.
+    fn display_next_column(&self) {
+        if self.is_enabled.get() && self.len.get() > 0 {
+            // Compute the number of columns of the strip.
+            let strip_len = (self.len.get() * (GLYPH_WIDTH + 1) - 1).saturating_add(self.gap.get());
+            // Verify if we are at the end of the strip.
+            if self.position.get() >= strip_len {
+                // Reset the position to the start of the strip.
+                self.position.set(0);
+            }
+            // Build the glyph of the displayed frame, LED by LED.
+            let frame = self.buffer.map_or(0, |buffer| {
+                let mut frame = 0;
+                for y in 0..GLYPH_HEIGHT {
+                    for x in 0..GLYPH_WIDTH {
+                        let column = (self.position.get() + x) % strip_len;
+                        frame = (frame << 1) | self.is_strip_led_on(buffer, column, y) as u32;
+                    }
+                }
+                frame
+            });
+            self.print(frame);
+            // Move the text one column to the left
+            self.position.set(self.position.get() + 1);
+        } else {
+            // The display is disabled or there is nothing to display.
+            self.clear();
+        }
+    }
+
+    /// Returns whether the LED in row `y` of the strip's `column` is on
+    fn is_strip_led_on(&self, buffer: &[u8], column: usize, y: usize) -> bool {
+        // Each character uses the columns of its glyph and a blank column.
+        let index = column / (GLYPH_WIDTH + 1);
+        let x = column % (GLYPH_WIDTH + 1);
+        if x < GLYPH_WIDTH {
+            self.text_character(buffer, index)
+                .map_or(false, |(character, highlight)| {
+                    Font5x5
+                        .glyph(character)
+                        .map_or(false, |glyph| glyph.is_on(x, y))
+                        ^ highlight
+                })
+        } else {
+            // This is either the blank column between two characters
+            // or the gap after the text.
+            false
         }
     }
 
+    /// Returns the number of rows of the virtual screen
+    fn rows(&self, buffer: &[u8]) -> usize {
+        buffer.len() / self.columns
+    }
+
+    /// Returns the length of the text of `row`, up to its last
+    /// written character or to the cursor, if it is visible
+    fn row_len(&self, buffer: &[u8], row: usize) -> usize {
+        let start = row * self.columns;
+        let len = buffer[start..start + self.columns]
+            .iter()
+            .rposition(|character| *character != 0)
+            .map_or(0, |last| last + 1);
+        let cursor = self.cursor.get();
+        if self.cursor_visible.get() && cursor / self.columns == row {
+            cmp::max(len, cursor % self.columns + 1)
+        } else {
+            len
+        }
+    }
+
+    /// Returns the length of the displayed text
+    fn text_len(&self, buffer: &[u8]) -> usize {
+        let (len, rows) = (0..self.rows(buffer))
+            .map(|row| self.row_len(buffer, row))
+            .filter(|len| *len > 0)
+            .fold((0, 0), |(len, rows), row_len| (len + row_len, rows + 1));
+        // Rows are separated by a space
+        len + rows.saturating_sub(1)
+    }
+
+    /// Returns the character at `index` within the displayed text
+    /// and whether the cursor highlights it
+    fn text_character(&self, buffer: &[u8], mut index: usize) -> Option<(char, bool)> {
+        let mut first_row = true;
+        for row in 0..self.rows(buffer) {
+            let row_len = self.row_len(buffer, row);
+            if row_len > 0 {
+                // The space that separates the row from the previous one
+                if !first_row {
+                    if index == 0 {
+                        return Some((' ', false));
+                    }
+                    index = index - 1;
+                }
+                first_row = false;
+                if index < row_len {
+                    let cell = row * self.columns + index;
+                    let highlight = self.cursor_visible.get()
+                        && cell == self.cursor.get()
+                        && (self.cursor_lit.get() || !self.cursor_blink.get());
+                    // Cells that have not been written are displayed as spaces
+                    return match buffer[cell] {
+                        0 => Some((' ', highlight)),
+                        character => Some((character as char, highlight)),
+                    };
+                }
+                index = index - row_len;
+            }
+        }
+        None
+    }
+
     /// Prints the a font `glyph` by setting LEDs
     /// on and off depending on the glyph's bits
     ///
     /// A font glyph is a set of bits that represents that
-    /// state of the LEDs
+    /// state of the LEDs, it is transformed before being printed
     fn print(&self, glyph: u32) {
+        let glyph = self.transform.get().apply(glyph);
         for index in 0..25 {
             match (glyph >> (24 - index)) & 0x01 {
                 0 => self.leds[index].off(),
@@ -280,20 +622,55 @@ impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
         }
     }
 
-    /// Displays a character
-    fn display(&self, character: char) -> Result<(), ErrorCode> {
+    /// Recomputes the length of the displayed text and starts
+    /// displaying it if there was nothing to display before
+    fn update_len(&self) {
+        let previous_len = self.len.get();
+        self.len
+            .set(self.buffer.map_or(0, |buffer| self.text_len(buffer)));
+        if previous_len == 0 && self.len.get() != 0 {
+            self.display_next();
+        }
+    }
+
+    /// Changes the way the cursor is displayed
+    fn set_cursor_style(&self, visible: bool, blink: bool) -> Result<(), ErrorCode> {
+        // Verify that we do no have another action in progress.
+        if self.status.get() == Status::Idle {
+            // Start a new command action
+            self.status.set(Status::ExecutesCommand);
+            self.cursor_visible.set(visible);
+            self.cursor_blink.set(blink);
+            self.cursor_lit.set(true);
+            self.blink_start.set(self.alarm.now());
+            // A visible cursor might extend its row.
+            self.update_len();
+            // A blinking cursor may have to toggle before the frame ends.
+            if self.alarm.is_armed() {
+                self.schedule_alarm();
+            }
+            // Ask the kernel to send us a deferred callback (software interrupt)
+            // as we are not allowed to call TextScreen's *command_complete* function
+            // before we return from the current function.
+            self.schedule_deferred_callback();
+            Ok(())
+        } else {
+            // Inform the TextScreen that we have another action in progress
+            // and that it should try again later.
+            Err(ErrorCode::BUSY)
+        }
+    }
+
+    /// Displays a character, with its glyph inverted if `highlight` is set
+    fn display(&self, character: char, highlight: bool) -> Result<(), ErrorCode> {
         if self.is_enabled.get() {
-            let displayed_character = character.to_ascii_uppercase();
-            match displayed_character {
-                '0'..='9' => {
-                    self.print(DIGITS[displayed_character as usize - '0' as usize]);
+            match Font5x5.glyph(character) {
+                Some(glyph) => {
+                    let inverted = if highlight { ALL_LEDS } else { 0 };
+                    self.print(glyph.bits() ^ inverted);
                     Ok(())
                 }
-                'A'..='Z' => {
-                    self.print(LETTERS[displayed_character as usize - 'A' as usize]);
-                    Ok(())
-                }
-                _ => {
+                None => {
                     self.clear();
                     Err(ErrorCode::INVAL)
                 }
@@ -314,6 +691,17 @@ impl<'a, L: Led, A: Alarm<'a>> LedMatrixText<'a, L, A> {
 impl<'a, L: Led, A: Alarm<'a>> AlarmClient for LedMatrixText<'a, L, A> {
     /// Called when the alarm expires
     fn alarm(&self) {
+        // Keep the current frame while the text is paused,
+        // the next one is displayed when it resumes.
+        if self.paused.get() {
+            self.stalled.set(true);
+            return;
+        }
+        // The alarm may have expired only to toggle the highlight
+        // of a blinking cursor, in the middle of the frame.
+        if self.blink() {
+            return;
+        }
         // The alarm has expired, the current letter or digit has been displayed enugh,
         // display the next letter or digit
         self.display_next();
@@ -324,7 +712,9 @@ impl<'a, L: Led, A: Alarm<'a>> AlarmClient for LedMatrixText<'a, L, A> {
 impl<'a, L: Led, A: Alarm<'a>> DynamicDeferredCallClient for LedMatrixText<'a, L, A> {
     /// The deferred callback (software interrupt) handler
     fn call(&self, _handle: DeferredCallHandle) {
-        match self.status.get() {
+        // The driver is ready to take new requests, so that the client
+        // can send its next request from the callback.
+        match self.status.replace(Status::Idle) {
             // We should not get here, we ignore it.
             Status::Idle => {}
             // The driver has performed a command, inform the client
@@ -343,8 +733,6 @@ impl<'a, L: Led, A: Alarm<'a>> DynamicDeferredCallClient for LedMatrixText<'a, L
                 });
             }
         }
-        // The driver is ready to take new requests.
-        self.status.set(Status::Idle);
     }
 }
 
@@ -359,9 +747,9 @@ impl<'a, L: Led, A: Alarm<'a>> TextScreen<'a> for LedMatrixText<'a, L, A> {
     }
 
     fn get_size(&self) -> (usize, usize) {
-        // Our simulated screen has 1 row that can display as many characters
-        // as the capacity of the driver's buffer.
-        (self.get_buffer_len(), 1)
+        // Our simulated screen has rows of `columns` characters,
+        // as many as the driver's buffer can store.
+        (self.columns, self.get_buffer_len() / self.columns)
     }
 
     /// This is a *print* request from the `TextScreen` driver
@@ -378,15 +766,28 @@ impl<'a, L: Led, A: Alarm<'a>> TextScreen<'a> for LedMatrixText<'a, L, A> {
                 self.status.set(Status::ExecutesPrint);
                 // Store the previous length of the text we store in the driver's buffer.
                 let previous_len = self.len.get();
-                // Copy the text to the driver's buffer.
+                // Copy the text to the driver's buffer, starting at the cursor.
                 let printed_len = self.buffer.map_or(0, |buf| {
+                    if self.replace.get() {
+                        // Erase the previous text and start displaying
+                        // the new one from the beginning.
+                        for character in buf.iter_mut() {
+                            *character = 0;
+                        }
+                        self.cursor.set(0);
+                        self.position.set(0);
+                    }
+                    let cursor = self.cursor.get();
                     // Compute how many characters we can copy to the driver's buffer.
-                    let max_len = cmp::min(len, buf.len());
+                    let max_len = cmp::min(len, buf.len() - cursor);
                     for position in 0..max_len {
-                        buf[position] = buffer[position];
+                        buf[cursor + position] = buffer[position];
                     }
-                    // Compute the new length of the text sored in the driver's buffer
-                    self.len.set(cmp::max(max_len, self.len.get()));
+                    // Move the cursor after the text, back to the
+                    // start of the screen if it reaches its end.
+                    self.cursor.set((cursor + max_len) % buf.len());
+                    // Compute the new length of the displayed text
+                    self.len.set(self.text_len(buf));
                     // Make printed_length = max_len, the number of characters that
                     // we have copied to thed driver's buffer.
                     max_len
@@ -406,7 +807,7 @@ impl<'a, L: Led, A: Alarm<'a>> TextScreen<'a> for LedMatrixText<'a, L, A> {
                 // not be automatically called. If the new length of the text
                 // is different from 0, we can immedialty print the next
                 // letter or digit.
-                if previous_len == 0 && printed_len != 0 {
+                if previous_len == 0 && self.len.get() != 0 {
                     self.display_next();
                 }
                 Ok(())
@@ -422,26 +823,47 @@ impl<'a, L: Led, A: Alarm<'a>> TextScreen<'a> for LedMatrixText<'a, L, A> {
         }
     }
 
-    /* Actions that are not supported */
-
-    fn set_cursor(&self, _x_position: usize, _y_position: usize) -> Result<(), ErrorCode> {
-        Err(ErrorCode::NOSUPPORT)
+    fn set_cursor(&self, x_position: usize, y_position: usize) -> Result<(), ErrorCode> {
+        // Verify that we do no have another action in progress.
+        if self.status.get() == Status::Idle {
+            // Verify that the position is on the screen.
+            let (columns, rows) = self.get_size();
+            if x_position < columns && y_position < rows {
+                // Start a new command action
+                self.status.set(Status::ExecutesCommand);
+                // Move the write position
+                self.cursor.set(y_position * columns + x_position);
+                // A visible cursor might extend its row.
+                self.update_len();
+                // Ask the kernel to send us a deferred callback (software interrupt)
+                // as we are not allowed to call TextScreen's *command_complete* function
+                // before we return from the current function.
+                self.schedule_deferred_callback();
+                Ok(())
+            } else {
+                Err(ErrorCode::INVAL)
+            }
+        } else {
+            // Inform the TextScreen that we have another action in progress
+            // and that it should try again later.
+            Err(ErrorCode::BUSY)
+        }
     }
 
     fn hide_cursor(&self) -> Result<(), ErrorCode> {
-        Err(ErrorCode::NOSUPPORT)
+        self.set_cursor_style(false, self.cursor_blink.get())
     }
 
     fn show_cursor(&self) -> Result<(), ErrorCode> {
-        Err(ErrorCode::NOSUPPORT)
+        self.set_cursor_style(true, self.cursor_blink.get())
     }
 
     fn blink_cursor_on(&self) -> Result<(), ErrorCode> {
-        Err(ErrorCode::NOSUPPORT)
+        self.set_cursor_style(self.cursor_visible.get(), true)
     }
 
     fn blink_cursor_off(&self) -> Result<(), ErrorCode> {
-        Err(ErrorCode::NOSUPPORT)
+        self.set_cursor_style(self.cursor_visible.get(), false)
     }
 
     /* Display commands */
@@ -489,12 +911,22 @@ impl<'a, L: Led, A: Alarm<'a>> TextScreen<'a> for LedMatrixText<'a, L, A> {
         if self.status.get() == Status::Idle {
             // Start a new command action
             self.status.set(Status::ExecutesCommand);
-            // Reset the position
+            // Reset the position and move the cursor to
+            // the upper left corner of the screen
             self.position.set(0);
-            // Set the text's length to 0
+            self.cursor.set(0);
+            // Erase the text from the screen and set its length to 0
+            self.buffer.map(|buffer| {
+                for character in buffer.iter_mut() {
+                    *character = 0;
+                }
+            });
             self.len.set(0);
+            self.phase.set(Phase::Blank);
             // Clear what is currently displayed on the LED matrix
             self.clear();
+            // A visible cursor is still displayed.
+            self.update_len();
             // Ask the kernel to send us a deferred callback (software interrupt)
             // as we are not allowed to call TextScreen's *command_complete* function
             // before we return from the current function.
@@ -508,6 +940,46 @@ impl<'a, L: Led, A: Alarm<'a>> TextScreen<'a> for LedMatrixText<'a, L, A> {
     }
 }
 
+/// This implementation allows the kernel's consoles to set the speed.
+impl<'a, L: Led, A: Alarm<'a>> TextSpeed for LedMatrixText<'a, L, A> {
+    fn set_speed(&self, speed_ms: u32) {
+        self.speed.set(speed_ms);
+    }
+
+    fn speed(&self) -> u32 {
+        self.speed.get()
+    }
+}
+
+/// This implementation allows the kernel's buttons to pause the text.
+impl<'a, L: Led, A: Alarm<'a>> TextPause for LedMatrixText<'a, L, A> {
+    fn set_paused(&self, paused: bool) {
+        self.paused.set(paused);
+        // Display the next frame if the alarm has expired
+        // while the text was paused.
+        if !paused && self.stalled.replace(false) {
+            self.display_next();
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.get()
+    }
+}
+
+/// This implementation allows the kernel to rotate and mirror the text.
+///
+/// The new transformation is applied starting with the next displayed frame.
+impl<'a, L: Led, A: Alarm<'a>> MatrixOrientation for LedMatrixText<'a, L, A> {
+    fn set_transform(&self, transform: Transform) {
+        self.transform.set(transform);
+    }
+
+    fn transform(&self) -> Transform {
+        self.transform.get()
+    }
+}
+
 /// This implementation allows `LedMatrixText` to expose a setup syscall API
 impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for LedMatrixText<'a, L, A> {
     fn allocate_grant(&self, _: ProcessId) -> Result<(), Error> {
@@ -520,7 +992,7 @@ impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for LedMatrixText<'a, L, A> {
         &self,
         command_number: usize,
         r2: usize,
-        _r3: usize,
+        r3: usize,
         _process_id: ProcessId,
     ) -> CommandReturn {
         match command_number {
@@ -532,6 +1004,70 @@ impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for LedMatrixText<'a, L, A> {
                 self.speed.set(r2 as u32);
                 CommandReturn::success()
             }
+            // Set the display mode to the value stored in *r2*
+            //  0 - display one character at a time
+            //  1 - scroll the text one column at a time, using
+            //      *r3* blank columns before the text repeats
+            // The gap is at most as long as the strip of a full screen.
+            2 => {
+                let mode = match r2 {
+                    0 => Mode::Character,
+                    1 => Mode::Scroll,
+                    _ => return CommandReturn::failure(ErrorCode::INVAL),
+                };
+                let max_gap = self
+                    .buffer
+                    .map_or(0, |buffer| buffer.len() * (GLYPH_WIDTH + 1));
+                if r3 > max_gap {
+                    return CommandReturn::failure(ErrorCode::INVAL);
+                }
+                self.mode.set(mode);
+                self.gap.set(r3);
+                // Start displaying the text from the beginning.
+                self.position.set(0);
+                self.phase.set(Phase::Blank);
+                CommandReturn::success()
+            }
+            // Set the time in milliseconds that the LEDs are off between two
+            // characters in character mode to the value stored in *r2*.
+            3 => {
+                self.blank_ms.set(r2 as u32);
+                CommandReturn::success()
+            }
+            // Set the way *print* requests write the text to the value stored in *r2*
+            //  0 - write the text at the cursor, over the previous text
+            //  1 - replace the previous text with the new one
+            4 => match r2 {
+                0 => {
+                    self.replace.set(false);
+                    CommandReturn::success()
+                }
+                1 => {
+                    self.replace.set(true);
+                    CommandReturn::success()
+                }
+                _ => CommandReturn::failure(ErrorCode::INVAL),
+            },
+            // Save the displayed text, the speed and the display mode as the
+            // boot message, displayed after the next reset. Saving an empty
+            // screen removes the boot message. Fails with BUSY if the boot
+            // message was saved less than the board's save interval ago.
+            5 => match self.save_boot_message() {
+                Ok(()) => CommandReturn::success(),
+                Err(err) => CommandReturn::failure(err),
+            },
+            // Rotate the text clockwise by the number of degrees stored in *r2*
+            // (0, 90, 180 or 270) and mirror it horizontally if *r3* is 1
+            6 => match (Rotation::from_degrees(r2), r3) {
+                (Some(rotation), 0 | 1) => {
+                    self.set_transform(Transform {
+                        rotation,
+                        mirror: r3 == 1,
+                    });
+                    CommandReturn::success()
+                }
+                _ => CommandReturn::failure(ErrorCode::INVAL),
+            },
             _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
         }
     }
@@ -3,3 +3,42 @@
 
 /// The driver that offers the text screen service.
 pub mod led_matrix_text;
+
+/// The traits of the display drivers used by the features built on them.
+pub mod display;
+
+/// The driver that displays grayscale images.
+pub mod grayscale_display;
+
+/// The mux that shares the text screen between kernel clients.
+pub mod virtual_text_screen;
+
+/// The fault policy that restarts processes and displays their faults.
+pub mod restart_fault_policy;
+
+/// The LED matrix that displays the panic message.
+pub mod panic_matrix;
+
+/// The messages that the kernel displays on a text screen.
+pub mod screen_message;
+
+/// The boot message of the LED matrix, stored in flash with TicKV.
+pub mod boot_message;
+
+/// The console commands that drive the text display over serial.
+pub mod display_console;
+
+/// The buttons that control the speed of the text and pause it.
+pub mod button_controls;
+
+/// The rotation and mirroring of the image displayed by the LED matrix.
+pub mod matrix_transform;
+
+/// The tracker that rotates the LED matrix using an accelerometer.
+pub mod orientation_tracker;
+
+/// The driver that measures the ambient light with the LED matrix and adapts its brightness.
+pub mod ambient_light;
+
+/// The syscall filter that restricts the display drivers to allowed applications.
+pub mod syscall_filter;
@@ -0,0 +1,82 @@
+use font::{GLYPH_HEIGHT, GLYPH_LEDS, GLYPH_WIDTH};
+
+/// The clockwise rotations of the displayed image
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Rotation {
+    /// The image is displayed upright
+    Deg0,
+    /// The image is rotated a quarter turn clockwise,
+    /// its top row being displayed in the right column
+    Deg90,
+    /// The image is displayed upside down
+    Deg180,
+    /// The image is rotated a quarter turn counterclockwise,
+    /// its top row being displayed in the left column
+    Deg270,
+}
+
+impl Rotation {
+    /// Returns the rotation of `degrees` degrees, if it is
+    /// a multiple of 90 smaller than 360
+    pub fn from_degrees(degrees: usize) -> Option<Rotation> {
+        match degrees {
+            0 => Some(Rotation::Deg0),
+            90 => Some(Rotation::Deg90),
+            180 => Some(Rotation::Deg180),
+            270 => Some(Rotation::Deg270),
+            _ => None,
+        }
+    }
+}
+
+/// The transformation that the drivers apply to the image
+/// before displaying it on the LED matrix
+///
+/// The image is first mirrored, if required, then rotated.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Transform {
+    /// The clockwise rotation of the image
+    pub rotation: Rotation,
+    /// Stores if the image is mirrored horizontally,
+    /// its left column being displayed on the right
+    pub mirror: bool,
+}
+
+impl Transform {
+    /// The transformation that displays the image as it is,
+    /// LED 0 being the upper left one
+    pub const IDENTITY: Transform = Transform {
+        rotation: Rotation::Deg0,
+        mirror: false,
+    };
+
+    /// Returns the index of the LED that displays the pixel
+    /// `index` of the image
+    ///
+    /// The matrix has the size of a glyph, which is square,
+    /// so the rotated image fits the matrix.
+    pub fn led(&self, index: usize) -> usize {
+        let (x, y) = (index % GLYPH_WIDTH, index / GLYPH_WIDTH);
+        let x = if self.mirror { GLYPH_WIDTH - 1 - x } else { x };
+        let (x, y) = match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (GLYPH_HEIGHT - 1 - y, x),
+            Rotation::Deg180 => (GLYPH_WIDTH - 1 - x, GLYPH_HEIGHT - 1 - y),
+            Rotation::Deg270 => (y, GLYPH_WIDTH - 1 - x),
+        };
+        y * GLYPH_WIDTH + x
+    }
+
+    /// Transforms a 25 bits image, where the pixel `index`
+    /// is stored in the bit `24 - index`, like a font glyph
+    pub fn apply(&self, bitmap: u32) -> u32 {
+        if *self == Transform::IDENTITY {
+            return bitmap;
+        }
+        (0..GLYPH_LEDS)
+            .filter(|index| bitmap & (1 << (GLYPH_LEDS - 1 - index)) != 0)
+            .fold(0, |result, index| {
+                result | 1 << (GLYPH_LEDS - 1 - self.led(index))
+            })
+    }
+}
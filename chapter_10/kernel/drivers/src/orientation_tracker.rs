@@ -0,0 +1,163 @@
+use crate::display::MatrixOrientation;
+use crate::matrix_transform::{Rotation, Transform};
+use core::cell::Cell;
+use kernel::hil::sensors::{NineDof, NineDofClient};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::utilities::cells::OptionalCell;
+
+/// The minimum acceleration, in mg, along the matrix's plane
+/// for the tracker to decide an orientation
+const MIN_ACCELERATION_MG: i32 = 500;
+
+/// The directions of the accelerometer's axes relative to the matrix
+///
+/// The tracker expects the x axis to point to the right of the
+/// matrix and the y axis to point to its top. Sensors mounted
+/// otherwise have their axes inverted.
+#[derive(Copy, Clone)]
+pub struct Axes {
+    pub invert_x: bool,
+    pub invert_y: bool,
+}
+
+/// Returns the rotation that keeps the image upright given the
+/// acceleration, in mg, along the matrix's x (right) and y (up) axes
+///
+/// Returns `None` if the board lies flat or is held close to a
+/// diagonal, the previous rotation should be kept.
+pub fn upright_rotation(x: i32, y: i32) -> Option<Rotation> {
+    let (x_abs, y_abs) = (x.abs(), y.abs());
+    if x_abs.max(y_abs) < MIN_ACCELERATION_MG {
+        None
+    } else if y_abs > 2 * x_abs {
+        // The top (or the bottom) edge of the matrix is up.
+        Some(if y > 0 {
+            Rotation::Deg0
+        } else {
+            Rotation::Deg180
+        })
+    } else if x_abs > 2 * y_abs {
+        // The right (or the left) edge of the matrix is up, the top
+        // of the image has to be displayed on that edge.
+        Some(if x > 0 {
+            Rotation::Deg90
+        } else {
+            Rotation::Deg270
+        })
+    } else {
+        None
+    }
+}
+
+/// Structure representing the orientation tracker
+///
+/// The tracker periodically reads the accelerometer and rotates the
+/// displays so that the image stays upright, however the board is held.
+/// The mirroring of the displays is left unchanged.
+///
+/// The tracker is the sensor's client and forwards the readings it has
+/// not requested to the next client, usually the `NineDof` syscall driver.
+pub struct OrientationTracker<'a, A: Alarm<'a>> {
+    /// The sensor that provides the acceleration
+    sensor: &'a dyn NineDof<'a>,
+
+    /// The alarm used to read the sensor periodically
+    alarm: &'a A,
+
+    /// The displays that the tracker rotates
+    displays: &'a [&'a dyn MatrixOrientation],
+
+    /// The directions of the sensor's axes
+    axes: Axes,
+
+    /// The time in milliseconds between two readings
+    period_ms: u32,
+
+    /// Stores if the tracker waits for its reading
+    reading: Cell<bool>,
+
+    /// The client that receives the other readings of the sensor
+    client: OptionalCell<&'a dyn NineDofClient>,
+}
+
+impl<'a, A: Alarm<'a>> OrientationTracker<'a, A> {
+    /// Initializes a new tracker structure
+    pub fn new(
+        sensor: &'a dyn NineDof<'a>,
+        alarm: &'a A,
+        displays: &'a [&'a dyn MatrixOrientation],
+        axes: Axes,
+        period_ms: u32,
+    ) -> Self {
+        OrientationTracker {
+            sensor,
+            alarm,
+            displays,
+            axes,
+            period_ms,
+            reading: Cell::new(false),
+            client: OptionalCell::empty(),
+        }
+    }
+
+    /// Sets the client that receives the readings requested by others
+    pub fn set_client(&self, client: &'a dyn NineDofClient) {
+        self.client.set(client);
+    }
+
+    /// Starts tracking the orientation
+    pub fn start(&self) {
+        self.schedule();
+    }
+
+    /// Sets up the alarm for the next reading
+    fn schedule(&self) {
+        self.alarm
+            .set_alarm(self.alarm.now(), self.alarm.ticks_from_ms(self.period_ms));
+    }
+
+    /// Rotates the displays that are not upright
+    fn rotate(&self, rotation: Rotation) {
+        for display in self.displays.iter() {
+            let transform = display.transform();
+            if transform.rotation != rotation {
+                display.set_transform(Transform {
+                    rotation,
+                    ..transform
+                });
+            }
+        }
+    }
+}
+
+/// This implementation allows `OrientationTracker` to use an alarm.
+impl<'a, A: Alarm<'a>> AlarmClient for OrientationTracker<'a, A> {
+    /// Called when it is time to read the sensor
+    fn alarm(&self) {
+        match self.sensor.read_accelerometer() {
+            Ok(()) => self.reading.set(true),
+            // The sensor is busy with another client's request,
+            // try again later.
+            Err(_) => self.schedule(),
+        }
+    }
+}
+
+/// This implementation allows `OrientationTracker` to receive the readings.
+impl<'a, A: Alarm<'a>> NineDofClient for OrientationTracker<'a, A> {
+    fn callback(&self, arg1: usize, arg2: usize, arg3: usize) {
+        if self.reading.replace(false) {
+            // The readings are signed values stored in usize.
+            let x = arg1 as isize as i32;
+            let y = arg2 as isize as i32;
+            let x = if self.axes.invert_x { -x } else { x };
+            let y = if self.axes.invert_y { -y } else { y };
+            if let Some(rotation) = upright_rotation(x, y) {
+                self.rotate(rotation);
+            }
+            self.schedule();
+        } else {
+            self.client.map(|client| client.callback(arg1, arg2, arg3));
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use core::fmt;
+use font::{Font, Font5x5, GLYPH_HEIGHT, GLYPH_WIDTH};
+use kernel::hil::gpio::{ActivationMode, Configure, Output};
+use kernel::process::{Process, State};
+
+/// The number of blank columns displayed after the text
+/// and before it repeats (a full blank screen)
+const SCROLL_GAP: usize = GLYPH_WIDTH;
+
+/// The maximum length of the panic message
+pub const MESSAGE_LEN: usize = 64;
+
+/// The maximum length of the panic reason within the message,
+/// so that a long reason does not hide the process name
+pub const REASON_LEN: usize = 32;
+
+/// Returns the name of the process that has faulted or, if none
+/// has, of the process that was running when the kernel panicked
+///
+/// `processes` yields the state and the name of each process. A
+/// process that has faulted is the reason of the panic, even if
+/// another process is still marked as running.
+pub fn faulting_process<'a>(
+    processes: impl Iterator<Item = (State, &'a str)> + Clone,
+) -> Option<&'a str> {
+    let find = |state: State| {
+        processes
+            .clone()
+            .find(|(process_state, _)| *process_state == state)
+            .map(|(_, name)| name)
+    };
+    find(State::Faulted).or_else(|| find(State::Running))
+}
+
+/// The condensed panic message displayed by `PanicMatrix`
+///
+/// The message is formatted without allocation and is truncated
+/// if it is longer than `MESSAGE_LEN`.
+pub struct PanicMessage {
+    buffer: [u8; MESSAGE_LEN],
+    len: usize,
+}
+
+impl PanicMessage {
+    /// Builds the message from the panic location, the panic reason
+    /// and the name of the faulting process, if any
+    ///
+    /// The message has the form `file:line process: reason`, where file
+    /// is the file name without its path and reason is truncated to
+    /// `REASON_LEN` characters.
+    pub fn new(
+        location: Option<&core::panic::Location>,
+        reason: Option<&fmt::Arguments>,
+        processes: &[Option<&'static dyn Process>],
+    ) -> PanicMessage {
+        let processes = processes
+            .iter()
+            .filter_map(|process| *process)
+            .map(|process| (process.get_state(), process.get_process_name()));
+        Self::with_process(location, reason, faulting_process(processes))
+    }
+
+    /// Builds the message from the panic location, the panic
+    /// reason and the name of the faulting process
+    pub fn with_process(
+        location: Option<&core::panic::Location>,
+        reason: Option<&fmt::Arguments>,
+        process_name: Option<&str>,
+    ) -> PanicMessage {
+        let mut message = PanicMessage {
+            buffer: [0; MESSAGE_LEN],
+            len: 0,
+        };
+        if let Some(location) = location {
+            let file = location.file();
+            let file = file.rsplit('/').next().unwrap_or(file);
+            let _ =
+                fmt::Write::write_fmt(&mut message, format_args!("{}:{}", file, location.line()));
+        }
+        if let Some(name) = process_name {
+            let _ = fmt::Write::write_fmt(&mut message, format_args!(" {}", name));
+        }
+        if let Some(reason) = reason {
+            let _ = fmt::Write::write_str(&mut message, ": ");
+            let mut reason_writer = Truncated {
+                message: &mut message,
+                remaining: REASON_LEN,
+            };
+            let _ = fmt::Write::write_fmt(&mut reason_writer, *reason);
+        }
+        message
+    }
+
+    /// Returns the text of the message
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+impl fmt::Write for PanicMessage {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            if self.len < MESSAGE_LEN {
+                self.buffer[self.len] = byte;
+                self.len = self.len + 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes at most `remaining` characters into a panic message
+struct Truncated<'a> {
+    message: &'a mut PanicMessage,
+    remaining: usize,
+}
+
+impl fmt::Write for Truncated<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for character in s.chars().take(self.remaining) {
+            self.remaining -= 1;
+            fmt::Write::write_char(self.message, character)?;
+        }
+        Ok(())
+    }
+}
+
+/// Structure representing the LED matrix used while panicking
+///
+/// The kernel's LED matrix driver needs interrupts to refresh
+/// the LEDs, which are not available anymore after a panic. This
+/// structure drives the matrix GPIOs directly, one row at a time,
+/// and busy waits between rows.
+pub struct PanicMatrix<'a, P: Output + Configure> {
+    cols: [&'a P; 5],
+    rows: [&'a P; 5],
+    cols_mode: ActivationMode,
+    rows_mode: ActivationMode,
+}
+
+impl<'a, P: Output + Configure> PanicMatrix<'a, P> {
+    /// Initializes a new matrix and configures its pins as outputs
+    pub fn new(
+        cols: [&'a P; 5],
+        cols_mode: ActivationMode,
+        rows: [&'a P; 5],
+        rows_mode: ActivationMode,
+    ) -> Self {
+        let matrix = PanicMatrix {
+            cols,
+            rows,
+            cols_mode,
+            rows_mode,
+        };
+        for pin in matrix.cols.iter().chain(matrix.rows.iter()) {
+            pin.make_output();
+        }
+        matrix.clear();
+        matrix
+    }
+
+    /// Sets `pin` to its active or inactive level
+    fn write(pin: &P, mode: ActivationMode, active: bool) {
+        match (mode, active) {
+            (ActivationMode::ActiveHigh, true) | (ActivationMode::ActiveLow, false) => pin.set(),
+            _ => pin.clear(),
+        }
+    }
+
+    /// Turns off all the LEDs
+    fn clear(&self) {
+        for col in self.cols.iter() {
+            Self::write(col, self.cols_mode, false);
+        }
+        for row in self.rows.iter() {
+            Self::write(row, self.rows_mode, false);
+        }
+    }
+
+    /// Verifies if the LED at column `x` of the row `y` of the
+    /// scrolling strip is on
+    ///
+    /// The strip has each character followed by a blank column
+    /// and ends with `SCROLL_GAP` blank columns.
+    fn is_strip_led_on(text: &[u8], x: usize, y: usize) -> bool {
+        let character = x / (GLYPH_WIDTH + 1);
+        let column = x % (GLYPH_WIDTH + 1);
+        character < text.len()
+            && column < GLYPH_WIDTH
+            && Font5x5
+                .glyph(text[character] as char)
+                .map_or(false, |glyph| glyph.is_on(column, y))
+    }
+
+    /// Displays the 5 columns of the strip starting at `offset` once,
+    /// lighting each row for the duration of `delay`
+    fn refresh(&self, text: &[u8], offset: usize, delay: &dyn Fn()) {
+        for y in 0..GLYPH_HEIGHT {
+            for x in 0..GLYPH_WIDTH {
+                Self::write(
+                    self.cols[x],
+                    self.cols_mode,
+                    Self::is_strip_led_on(text, offset + x, y),
+                );
+            }
+            Self::write(self.rows[y], self.rows_mode, true);
+            delay();
+            Self::write(self.rows[y], self.rows_mode, false);
+        }
+    }
+
+    /// Scrolls `text` across the matrix forever
+    ///
+    /// Each column is displayed for `refreshes` refreshes of the matrix.
+    pub fn scroll_forever(&self, text: &[u8], refreshes: usize, delay: &dyn Fn()) -> ! {
+        let strip_len = text.len() * (GLYPH_WIDTH + 1) + SCROLL_GAP;
+        loop {
+            for offset in 0..strip_len {
+                for _ in 0..refreshes {
+                    self.refresh(text, offset, delay);
+                }
+            }
+        }
+    }
+}
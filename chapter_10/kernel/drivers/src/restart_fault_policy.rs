@@ -0,0 +1,329 @@
+use core::cell::Cell;
+use kernel::hil::text_screen::{TextScreen, TextScreenClient};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks, Ticks};
+use kernel::process::{FaultAction, Process, ProcessFaultPolicy};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+
+/// The maximum number of processes, so that their
+/// indexes have at most two digits
+const MAX_PROCS: usize = 100;
+
+/// The length of the longest fault code, "F99", which is
+/// the size that the message buffer needs
+pub const MESSAGE_LEN: usize = 3;
+
+/// The steps that the policy takes to update the fault message
+#[derive(Copy, Clone, PartialEq)]
+enum Step {
+    /// The screen is up to date
+    Idle,
+    /// Erase the previous message
+    Clear,
+    /// Write the fault message
+    Print,
+    /// Turn the display on or off
+    Display,
+}
+
+/// Structure representing the fault policy
+///
+/// Instead of panicking, the policy stops a faulted process and
+/// restarts it after a delay. The delay doubles with each restart
+/// of the same process. After `max_restarts` restarts, the process
+/// stays stopped. A restarted process that runs for `reset_ms`
+/// without faulting starts over with the first delay.
+///
+/// While processes wait to be restarted or have been stopped for good,
+/// the policy displays the fault code "F" followed by the index of the
+/// last faulted process on its text screen.
+///
+/// The index is the position of the process in the processes array,
+/// written in decimal, so "F0" to "F99". The kernel does not tell the
+/// policy why a process has faulted, so the code has no fault kind and
+/// is the same whether the process waits to be restarted or not.
+pub struct RestartFaultPolicy<'a, A: Alarm<'a>, const NUM_PROCS: usize> {
+    /// The array of processes that the kernel has loaded
+    processes: &'static [Option<&'static dyn Process>; NUM_PROCS],
+
+    /// The alarm used to delay the restarts
+    alarm: &'a A,
+
+    /// The screen that displays the fault message
+    screen: &'a dyn TextScreen<'a>,
+
+    /// The buffer that stores the fault message while it is printed
+    message: TakeCell<'static, [u8]>,
+
+    /// The delay in milliseconds before the first restart of a process
+    delay_ms: u32,
+
+    /// The number of times a process is restarted before
+    /// it is stopped for good
+    max_restarts: usize,
+
+    /// The time in milliseconds that a restarted process has to
+    /// run without faulting for its faults to be forgotten
+    reset_ms: u32,
+
+    /// The number of times each process has faulted, capped
+    /// at `max_restarts` + 1 once the process has been stopped for good
+    faults: [Cell<usize>; NUM_PROCS],
+
+    /// The number of milliseconds left until each process is restarted,
+    /// relative to `reference`
+    delays: [Cell<Option<u32>>; NUM_PROCS],
+
+    /// The number of milliseconds left until the faults of each
+    /// restarted process are forgotten, relative to `reference`
+    resets: [Cell<Option<u32>>; NUM_PROCS],
+
+    /// The time from which the delays and resets are counted
+    reference: Cell<A::Ticks>,
+
+    /// The index of the last faulted process
+    last_fault: OptionalCell<usize>,
+
+    /// The current step of the screen update
+    step: Cell<Step>,
+
+    /// Stores whether the fault message has changed
+    dirty: Cell<bool>,
+
+    /// Stores whether the screen displays the fault message
+    shown: Cell<bool>,
+}
+
+impl<'a, A: Alarm<'a>, const NUM_PROCS: usize> RestartFaultPolicy<'a, A, NUM_PROCS> {
+    /// Initializes a new policy structure
+    ///
+    /// The `message` buffer has to be able to store the fault code,
+    /// 3 bytes are enough for up to 100 processes, the most that
+    /// the policy supports.
+    pub fn new(
+        processes: &'static [Option<&'static dyn Process>; NUM_PROCS],
+        alarm: &'a A,
+        screen: &'a dyn TextScreen<'a>,
+        message: &'static mut [u8],
+        delay_ms: u32,
+        max_restarts: usize,
+        reset_ms: u32,
+    ) -> Self {
+        if NUM_PROCS > MAX_PROCS {
+            panic!(
+                "Expecting at most {} processes, {} supplied",
+                MAX_PROCS, NUM_PROCS
+            );
+        }
+        if message.len() < MESSAGE_LEN {
+            panic!(
+                "Expecting a message buffer of at least {} bytes, {} supplied",
+                MESSAGE_LEN,
+                message.len()
+            );
+        }
+        const NO_FAULTS: Cell<usize> = Cell::new(0);
+        const NO_DELAY: Cell<Option<u32>> = Cell::new(None);
+        RestartFaultPolicy {
+            processes,
+            alarm,
+            screen,
+            message: TakeCell::new(message),
+            delay_ms,
+            max_restarts,
+            reset_ms,
+            faults: [NO_FAULTS; NUM_PROCS],
+            delays: [NO_DELAY; NUM_PROCS],
+            resets: [NO_DELAY; NUM_PROCS],
+            reference: Cell::new(alarm.now()),
+            last_fault: OptionalCell::empty(),
+            step: Cell::new(Step::Idle),
+            dirty: Cell::new(false),
+            shown: Cell::new(false),
+        }
+    }
+
+    /// Returns the index of `process` in the processes array
+    fn index(&self, process: &dyn Process) -> Option<usize> {
+        self.processes
+            .iter()
+            .position(|slot| slot.map_or(false, |slot| slot.processid() == process.processid()))
+    }
+
+    /// Subtracts the time elapsed since `reference` from the delays of
+    /// the processes that wait to be restarted and from the resets of
+    /// the restarted processes, and moves `reference` to now
+    fn elapse(&self) {
+        let now = self.alarm.now();
+        let elapsed = self
+            .alarm
+            .ticks_to_ms(now.wrapping_sub(self.reference.get()));
+        for delay in self.delays.iter().chain(self.resets.iter()) {
+            delay.set(delay.get().map(|ms| ms.saturating_sub(elapsed)));
+        }
+        self.reference.set(now);
+    }
+
+    /// Sets up the alarm for the next restart or reset
+    fn rearm(&self) {
+        match self
+            .delays
+            .iter()
+            .chain(self.resets.iter())
+            .filter_map(|delay| delay.get())
+            .min()
+        {
+            Some(ms) => self
+                .alarm
+                .set_alarm(self.reference.get(), self.alarm.ticks_from_ms(ms)),
+            None => {
+                let _ = self.alarm.disarm();
+            }
+        }
+    }
+
+    /// Verifies if the fault message should be displayed
+    fn is_visible(&self) -> bool {
+        self.delays.iter().any(|delay| delay.get().is_some())
+            || self
+                .faults
+                .iter()
+                .any(|faults| faults.get() > self.max_restarts)
+    }
+
+    /// Takes the next step to bring the screen up to date
+    fn refresh(&self) {
+        if self.step.get() != Step::Idle {
+            // The screen is updated when the current step is done.
+            return;
+        }
+        let visible = self.is_visible();
+        if visible && self.dirty.get() {
+            self.dirty.set(false);
+            self.step.set(Step::Clear);
+            if self.screen.clear().is_err() {
+                self.step.set(Step::Idle);
+            }
+        } else if visible != self.shown.get() {
+            self.shown.set(visible);
+            self.step.set(Step::Display);
+            let res = if visible {
+                self.screen.display_on()
+            } else {
+                self.screen.display_off()
+            };
+            if res.is_err() {
+                self.step.set(Step::Idle);
+            }
+        }
+    }
+
+    /// Writes the fault code of the last faulted process and prints it
+    ///
+    /// The code is "F" followed by the index of the process, with
+    /// one digit below 10 and two digits otherwise.
+    fn print_message(&self) {
+        self.message.take().map(|message| {
+            let index = self.last_fault.extract().unwrap_or(0);
+            let mut len = 0;
+            message[len] = b'F';
+            len = len + 1;
+            if index >= 10 {
+                message[len] = b'0' + ((index / 10) % 10) as u8;
+                len = len + 1;
+            }
+            message[len] = b'0' + (index % 10) as u8;
+            len = len + 1;
+            self.step.set(Step::Print);
+            if let Err((_, message)) = self.screen.print(message, len) {
+                self.message.replace(message);
+                self.step.set(Step::Idle);
+            }
+        });
+    }
+
+    /// Records a fault of the process at `index` in the processes array
+    /// and schedules its restart, if it has not been restarted too often
+    pub fn fault(&self, index: usize) {
+        if index >= NUM_PROCS {
+            return;
+        }
+        let faults = self.faults[index].get();
+        self.elapse();
+        // The process has not run long enough for its faults to be forgotten.
+        self.resets[index].set(None);
+        if faults < self.max_restarts {
+            // Double the delay for each restart of the same process.
+            let delay_ms = self.delay_ms.saturating_mul(1 << faults.min(31));
+            self.delays[index].set(Some(delay_ms));
+        }
+        self.rearm();
+        self.faults[index].set((faults + 1).min(self.max_restarts + 1));
+        self.last_fault.set(index);
+        self.dirty.set(true);
+        self.refresh();
+    }
+}
+
+impl<'a, A: Alarm<'a>, const NUM_PROCS: usize> ProcessFaultPolicy
+    for RestartFaultPolicy<'a, A, NUM_PROCS>
+{
+    fn action(&self, process: &dyn Process) -> FaultAction {
+        if let Some(index) = self.index(process) {
+            self.fault(index);
+        }
+        // The process is restarted by the alarm.
+        FaultAction::Stop
+    }
+}
+
+/// This implementation allows `RestartFaultPolicy` to use an alarm.
+impl<'a, A: Alarm<'a>, const NUM_PROCS: usize> AlarmClient
+    for RestartFaultPolicy<'a, A, NUM_PROCS>
+{
+    /// Called when the alarm expires
+    fn alarm(&self) {
+        self.elapse();
+        for (index, reset) in self.resets.iter().enumerate() {
+            if reset.get() == Some(0) {
+                // The process has run long enough without faulting.
+                reset.set(None);
+                self.faults[index].set(0);
+            }
+        }
+        for (index, delay) in self.delays.iter().enumerate() {
+            if delay.get() == Some(0) {
+                delay.set(None);
+                self.resets[index].set(Some(self.reset_ms));
+                self.processes[index].map(|process| process.try_restart(None));
+            }
+        }
+        self.rearm();
+        self.refresh();
+    }
+}
+
+/// This implementation allows `RestartFaultPolicy` to receive the
+/// screen's callbacks.
+impl<'a, A: Alarm<'a>, const NUM_PROCS: usize> TextScreenClient
+    for RestartFaultPolicy<'a, A, NUM_PROCS>
+{
+    fn command_complete(&self, _r: Result<(), ErrorCode>) {
+        match self.step.get() {
+            Step::Clear => {
+                self.step.set(Step::Idle);
+                self.print_message();
+            }
+            _ => self.step.set(Step::Idle),
+        }
+        self.refresh();
+    }
+
+    fn write_complete(&self, buffer: &'static mut [u8], _len: usize, _r: Result<(), ErrorCode>) {
+        self.message.replace(buffer);
+        // Make sure that the message is displayed.
+        self.step.set(Step::Idle);
+        self.shown.set(false);
+        self.refresh();
+    }
+}
@@ -0,0 +1,128 @@
+use core::cell::Cell;
+use kernel::hil::text_screen::{TextScreen, TextScreenClient};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::utilities::cells::TakeCell;
+use kernel::ErrorCode;
+
+/// The steps that the message takes to display itself
+#[derive(Copy, Clone, PartialEq)]
+enum Step {
+    /// Nothing to do
+    Idle,
+    /// Erase the previous text
+    Clear,
+    /// Turn the display on
+    DisplayOn,
+    /// Write the message and wait for the alarm
+    Print,
+    /// Wait for the display to be turned off
+    DisplayOff,
+}
+
+/// Structure representing a message displayed by the kernel
+///
+/// The message is displayed on a text screen, usually a virtual
+/// screen of the text screen mux, for a limited time.
+pub struct ScreenMessage<'a, A: Alarm<'a>> {
+    /// The screen that displays the message
+    screen: &'a dyn TextScreen<'a>,
+
+    /// The alarm that turns the message off
+    alarm: &'a A,
+
+    /// The message's text
+    buffer: TakeCell<'static, [u8]>,
+
+    /// The length of the message's text
+    len: Cell<usize>,
+
+    /// The number of milliseconds that the message is displayed
+    duration_ms: Cell<u32>,
+
+    /// The current step
+    step: Cell<Step>,
+}
+
+impl<'a, A: Alarm<'a>> ScreenMessage<'a, A> {
+    /// Initializes a new message structure
+    pub fn new(screen: &'a dyn TextScreen<'a>, alarm: &'a A, buffer: &'static mut [u8]) -> Self {
+        ScreenMessage {
+            screen,
+            alarm,
+            buffer: TakeCell::new(buffer),
+            len: Cell::new(0),
+            duration_ms: Cell::new(0),
+            step: Cell::new(Step::Idle),
+        }
+    }
+
+    /// Displays `text` for `duration_ms` milliseconds
+    ///
+    /// The text is truncated to the size of the message's buffer.
+    pub fn show(&self, text: &str, duration_ms: u32) -> Result<(), ErrorCode> {
+        if self.step.get() != Step::Idle {
+            return Err(ErrorCode::BUSY);
+        }
+        self.buffer
+            .map(|buffer| {
+                let len = text.len().min(buffer.len());
+                buffer[..len].copy_from_slice(&text.as_bytes()[..len]);
+                self.len.set(len);
+            })
+            .ok_or(ErrorCode::NOMEM)?;
+        self.duration_ms.set(duration_ms);
+        self.step.set(Step::Clear);
+        self.screen.clear().map_err(|err| {
+            self.step.set(Step::Idle);
+            err
+        })
+    }
+}
+
+/// This implementation allows `ScreenMessage` to receive the screen's callbacks.
+impl<'a, A: Alarm<'a>> TextScreenClient for ScreenMessage<'a, A> {
+    fn command_complete(&self, _r: Result<(), ErrorCode>) {
+        match self.step.get() {
+            Step::Clear => {
+                // Turn the display on first, so that the screen
+                // displays the message as soon as it is written.
+                self.step.set(Step::DisplayOn);
+                if self.screen.display_on().is_err() {
+                    self.step.set(Step::Idle);
+                }
+            }
+            Step::DisplayOn => {
+                if let Some(buffer) = self.buffer.take() {
+                    self.step.set(Step::Print);
+                    if let Err((_, buffer)) = self.screen.print(buffer, self.len.get()) {
+                        self.buffer.replace(buffer);
+                        self.step.set(Step::Idle);
+                    }
+                } else {
+                    self.step.set(Step::Idle);
+                }
+            }
+            _ => self.step.set(Step::Idle),
+        }
+    }
+
+    fn write_complete(&self, buffer: &'static mut [u8], _len: usize, _r: Result<(), ErrorCode>) {
+        self.buffer.replace(buffer);
+        // Set up an alarm for the end of the message.
+        self.alarm.set_alarm(
+            self.alarm.now(),
+            self.alarm.ticks_from_ms(self.duration_ms.get()),
+        );
+    }
+}
+
+/// This implementation allows `ScreenMessage` to use an alarm.
+impl<'a, A: Alarm<'a>> AlarmClient for ScreenMessage<'a, A> {
+    /// Called when the message has been displayed long enough
+    fn alarm(&self) {
+        self.step.set(Step::DisplayOff);
+        if self.screen.display_off().is_err() {
+            self.step.set(Step::Idle);
+        }
+    }
+}
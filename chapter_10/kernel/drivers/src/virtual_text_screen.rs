@@ -0,0 +1,572 @@
+use core::cell::Cell;
+use core::cmp;
+use kernel::collections::list::{List, ListLink, ListNode};
+use kernel::dynamic_deferred_call::{
+    DeferredCallHandle, DynamicDeferredCall, DynamicDeferredCallClient,
+};
+use kernel::hil::text_screen::{TextScreen, TextScreenClient};
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+
+/// The steps that the mux takes to make the screen display
+/// the text of the owner
+#[derive(Copy, Clone, PartialEq)]
+enum Step {
+    /// The screen displays the owner's text
+    Idle,
+    /// Erase the previous text
+    Clear,
+    /// Turn the display on or off
+    Display,
+    /// Write the owner's text
+    Print,
+    /// Move the cursor to the owner's cursor position
+    SetCursor,
+    /// Show or hide the cursor
+    CursorVisibility,
+    /// Make the cursor blink or not
+    CursorBlink,
+}
+
+/// The request of a virtual screen that waits for its completion callback
+#[derive(Copy, Clone, PartialEq)]
+enum Completion {
+    /// There is no request in progress
+    None,
+    /// A command, the client's *command_complete* is called
+    Command,
+    /// A *print* request that wrote `len` characters, the client's
+    /// *write_complete* is called
+    Write(usize),
+}
+
+/// Shares one text screen between several kernel clients
+///
+/// Each client uses a `VirtualTextScreen` that keeps its own copy of the
+/// text. The screen displays the text of one client, the owner. The owner
+/// is the client with the highest priority among the ones that have turned
+/// their display on. Clients with the same priority take turns, each of them
+/// owning the screen for a time slice.
+pub struct MuxTextScreen<'a, A: Alarm<'a>> {
+    /// The shared screen
+    screen: &'a dyn TextScreen<'a>,
+
+    /// The alarm used to switch the owner when the time slice expires
+    alarm: &'a A,
+
+    /// The virtual screens of the clients
+    devices: List<'a, VirtualTextScreen<'a, A>>,
+
+    /// The client whose text the screen displays
+    owner: OptionalCell<&'a VirtualTextScreen<'a, A>>,
+
+    /// The buffer used to send the owner's text to the screen
+    buffer: TakeCell<'static, [u8]>,
+
+    /// The step the mux takes to display the owner's text
+    step: Cell<Step>,
+
+    /// Stores if the owner or its text has changed since
+    /// the mux started to display it
+    dirty: Cell<bool>,
+
+    /// The number of milliseconds that a client owns the screen before
+    /// a client with the same priority takes its turn, 0 meaning that
+    /// the owner keeps the screen until it turns its display off
+    slice_ms: u32,
+
+    /// A reference to the kernel's deferred caller used to schedule
+    /// deferred callbacks (software interrupts)
+    deferred_caller: &'a DynamicDeferredCall,
+
+    /// The handle (position in the kernel's deferred callbacks array)
+    /// to the mux's deferred callback function
+    deferred_call_handle: OptionalCell<DeferredCallHandle>,
+}
+
+impl<'a, A: Alarm<'a>> MuxTextScreen<'a, A> {
+    /// Initializes a new mux structure
+    ///
+    /// The `buffer` has to be able to store the whole text of the screen.
+    pub fn new(
+        screen: &'a dyn TextScreen<'a>,
+        alarm: &'a A,
+        buffer: &'static mut [u8],
+        slice_ms: u32,
+        deferred_caller: &'a DynamicDeferredCall,
+    ) -> Self {
+        let (columns, rows) = screen.get_size();
+        if buffer.len() < columns * rows {
+            panic!(
+                "Expecting a buffer of {} bytes, {} supplied",
+                columns * rows,
+                buffer.len()
+            );
+        }
+        MuxTextScreen {
+            screen,
+            alarm,
+            devices: List::new(),
+            owner: OptionalCell::empty(),
+            buffer: TakeCell::new(buffer),
+            step: Cell::new(Step::Idle),
+            dirty: Cell::new(false),
+            slice_ms,
+            deferred_caller,
+            deferred_call_handle: OptionalCell::empty(),
+        }
+    }
+
+    /// Set the mux's deferred callback function
+    pub fn initialize_callback_handle(&self, deferred_call_handle: DeferredCallHandle) {
+        self.deferred_call_handle.replace(deferred_call_handle);
+    }
+
+    /// schedule a deferred callback (sfotware interrupt)
+    fn schedule_deferred_callback(&self) {
+        self.deferred_call_handle
+            .map(|handle| self.deferred_caller.set(*handle));
+    }
+
+    /// Returns the number of characters of the screen
+    fn screen_len(&self) -> usize {
+        let (columns, rows) = self.screen.get_size();
+        columns * rows
+    }
+
+    /// Returns the highest priority of the clients that have
+    /// their display on
+    fn top_priority(&self) -> Option<usize> {
+        self.devices
+            .iter()
+            .filter(|device| device.enabled.get())
+            .map(|device| device.priority)
+            .max()
+    }
+
+    /// Returns the number of clients that may own the screen
+    fn candidates(&self, priority: usize) -> usize {
+        self.devices
+            .iter()
+            .filter(|device| device.enabled.get() && device.priority == priority)
+            .count()
+    }
+
+    /// Selects the owner of the screen after a client has
+    /// turned its display on or off
+    ///
+    /// The owner keeps the screen as long as no client
+    /// with a higher priority needs it.
+    fn select_owner(&self) {
+        let owner = self.top_priority().and_then(|priority| {
+            let keep = self.owner.map_or(false, |owner| {
+                owner.enabled.get() && owner.priority == priority
+            });
+            if keep {
+                self.owner.extract()
+            } else {
+                self.devices
+                    .iter()
+                    .find(|device| device.enabled.get() && device.priority == priority)
+            }
+        });
+        self.set_owner(owner);
+    }
+
+    /// Gives the screen to the next client with the same
+    /// priority when the owner's time slice expires
+    fn rotate_owner(&self) {
+        let owner = self.owner.extract().and_then(|owner| {
+            let candidate = |device: &&VirtualTextScreen<'a, A>| {
+                device.enabled.get() && device.priority == owner.priority
+            };
+            // The next candidate after the owner or, if there is
+            // none, the first candidate
+            self.devices
+                .iter()
+                .skip_while(|device| !core::ptr::eq(*device, owner))
+                .skip(1)
+                .find(candidate)
+                .or_else(|| self.devices.iter().find(candidate))
+        });
+        self.set_owner(owner);
+    }
+
+    /// Makes `owner` the owner of the screen
+    fn set_owner(&self, owner: Option<&'a VirtualTextScreen<'a, A>>) {
+        let changed = match (owner, self.owner.extract()) {
+            (Some(new), Some(previous)) => !core::ptr::eq(new, previous),
+            (None, None) => false,
+            _ => true,
+        };
+        match owner {
+            Some(owner) => self.owner.set(owner),
+            None => self.owner.clear(),
+        }
+        if changed {
+            self.refresh();
+        }
+        // Time slice the screen if other clients wait for their turn.
+        // An owner that keeps the screen also keeps the rest of its
+        // slice, so that clients changing their text do not delay
+        // the rotation.
+        let sliced =
+            self.slice_ms > 0 && owner.map_or(false, |owner| self.candidates(owner.priority) > 1);
+        if !sliced || changed {
+            let _ = self.alarm.disarm();
+        }
+        if sliced && !self.alarm.is_armed() {
+            self.alarm
+                .set_alarm(self.alarm.now(), self.alarm.ticks_from_ms(self.slice_ms));
+        }
+    }
+
+    /// Called by a virtual screen when its text or display changes
+    fn changed(&self, device: &VirtualTextScreen<'a, A>) {
+        if self
+            .owner
+            .map_or(false, |owner| core::ptr::eq(*owner, device))
+        {
+            self.refresh();
+        }
+    }
+
+    /// Makes the screen display the owner's text
+    fn refresh(&self) {
+        self.dirty.set(true);
+        // If the mux is in the middle of displaying a text, it
+        // starts again once it is done.
+        if self.step.get() == Step::Idle {
+            self.run(Step::Clear);
+        }
+    }
+
+    /// Sends the request of `step` to the screen
+    ///
+    /// If the screen refuses a request, the mux skips it
+    /// and continues with the next step.
+    fn run(&self, step: Step) {
+        self.step.set(step);
+        let res = match step {
+            Step::Idle => {
+                // Start again if the owner's text has changed
+                // while it was sent to the screen.
+                if self.dirty.get() {
+                    self.run(Step::Clear);
+                }
+                return;
+            }
+            Step::Clear => {
+                self.dirty.set(false);
+                self.screen.clear()
+            }
+            Step::Print => match self.owner.extract() {
+                Some(owner) => {
+                    let len = self.screen_len();
+                    match self.buffer.take() {
+                        Some(buffer) => {
+                            // Copy the owner's text, the cells that were not
+                            // written store 0 and stay empty.
+                            owner.text.map(|text| {
+                                let len = cmp::min(len, text.len());
+                                buffer[..len].copy_from_slice(&text[..len]);
+                            });
+                            self.screen.print(buffer, len).map_err(|(err, buffer)| {
+                                self.buffer.replace(buffer);
+                                err
+                            })
+                        }
+                        None => Err(ErrorCode::NOMEM),
+                    }
+                }
+                None => Err(ErrorCode::OFF),
+            },
+            Step::SetCursor => match self.owner.extract() {
+                Some(owner) => {
+                    let (columns, _) = self.screen.get_size();
+                    let cursor = owner.cursor.get();
+                    self.screen.set_cursor(cursor % columns, cursor / columns)
+                }
+                None => Err(ErrorCode::OFF),
+            },
+            Step::CursorVisibility => match self.owner.extract() {
+                Some(owner) if owner.cursor_visible.get() => self.screen.show_cursor(),
+                _ => self.screen.hide_cursor(),
+            },
+            Step::CursorBlink => match self.owner.extract() {
+                Some(owner) if owner.cursor_blink.get() => self.screen.blink_cursor_on(),
+                _ => self.screen.blink_cursor_off(),
+            },
+            Step::Display => match self.owner.extract() {
+                Some(_) => self.screen.display_on(),
+                None => self.screen.display_off(),
+            },
+        };
+        if res.is_err() {
+            self.run(self.next_step(step));
+        }
+    }
+
+    /// Returns the step that follows `step`
+    fn next_step(&self, step: Step) -> Step {
+        match step {
+            Step::Idle => Step::Idle,
+            Step::Clear => Step::Display,
+            Step::Display => Step::Print,
+            Step::Print => Step::SetCursor,
+            Step::SetCursor => Step::CursorVisibility,
+            Step::CursorVisibility => Step::CursorBlink,
+            Step::CursorBlink => Step::Idle,
+        }
+    }
+}
+
+/// This implementation allows `MuxTextScreen` to receive the screen's callbacks.
+impl<'a, A: Alarm<'a>> TextScreenClient for MuxTextScreen<'a, A> {
+    fn command_complete(&self, _r: Result<(), ErrorCode>) {
+        self.run(self.next_step(self.step.get()));
+    }
+
+    fn write_complete(&self, buffer: &'static mut [u8], _len: usize, _r: Result<(), ErrorCode>) {
+        self.buffer.replace(buffer);
+        self.run(self.next_step(self.step.get()));
+    }
+}
+
+/// This implementation allows `MuxTextScreen` to use an alarm.
+impl<'a, A: Alarm<'a>> AlarmClient for MuxTextScreen<'a, A> {
+    /// Called when the owner's time slice expires
+    fn alarm(&self) {
+        self.rotate_owner();
+    }
+}
+
+/// This implementation allows `MuxTextScreen` to receive deferred callbacks (software interrupts)
+impl<'a, A: Alarm<'a>> DynamicDeferredCallClient for MuxTextScreen<'a, A> {
+    /// The deferred callback (software interrupt) handler
+    ///
+    /// Informs the clients that their requests are done.
+    fn call(&self, _handle: DeferredCallHandle) {
+        for device in self.devices.iter() {
+            device.complete();
+        }
+    }
+}
+
+/// The text screen of a kernel client
+///
+/// It behaves like a screen of the same size as the shared one, but it
+/// displays its text only while it owns the shared screen. Writing a
+/// text at the cursor works like on the shared screen.
+pub struct VirtualTextScreen<'a, A: Alarm<'a>> {
+    /// The mux that shares the screen
+    mux: &'a MuxTextScreen<'a, A>,
+
+    /// The client's priority, the highest number wins
+    priority: usize,
+
+    /// The next virtual screen in the mux's list
+    next: ListLink<'a, VirtualTextScreen<'a, A>>,
+
+    /// An optional client that the virtual screen
+    /// will notify when a request is done.
+    client: OptionalCell<&'a dyn TextScreenClient>,
+
+    /// The client's text, row after row
+    ///
+    /// Cells that have not been written store 0.
+    text: TakeCell<'a, [u8]>,
+
+    /// The position within `text` where the next *print*
+    /// request writes the text
+    cursor: Cell<usize>,
+
+    /// Stores if the client shows the cursor.
+    cursor_visible: Cell<bool>,
+
+    /// Stores if the client's cursor blinks.
+    cursor_blink: Cell<bool>,
+
+    /// Stores if the client has turned its display on.
+    enabled: Cell<bool>,
+
+    /// The request that waits for its completion callback
+    completion: Cell<Completion>,
+
+    /// The buffer received from the client with a *print* request,
+    /// returned in the completion callback
+    client_buffer: TakeCell<'static, [u8]>,
+}
+
+impl<'a, A: Alarm<'a>> VirtualTextScreen<'a, A> {
+    /// Initializes a new virtual screen
+    ///
+    /// The `text` buffer has to be able to store the whole
+    /// text of the shared screen.
+    pub fn new(mux: &'a MuxTextScreen<'a, A>, text: &'a mut [u8], priority: usize) -> Self {
+        if text.len() < mux.screen_len() {
+            panic!(
+                "Expecting a buffer of {} bytes, {} supplied",
+                mux.screen_len(),
+                text.len()
+            );
+        }
+        VirtualTextScreen {
+            mux,
+            priority,
+            next: ListLink::empty(),
+            client: OptionalCell::empty(),
+            text: TakeCell::new(text),
+            cursor: Cell::new(0),
+            cursor_visible: Cell::new(false),
+            cursor_blink: Cell::new(false),
+            enabled: Cell::new(false),
+            completion: Cell::new(Completion::None),
+            client_buffer: TakeCell::empty(),
+        }
+    }
+
+    /// Adds the virtual screen to the mux's list
+    pub fn setup(&'a self) {
+        self.mux.devices.push_head(self);
+    }
+
+    /// Starts a request, the client is notified from the
+    /// mux's deferred callback
+    fn start(&self, completion: Completion) -> Result<(), ErrorCode> {
+        if self.completion.get() == Completion::None {
+            self.completion.set(completion);
+            self.mux.schedule_deferred_callback();
+            Ok(())
+        } else {
+            // Inform the client that it has another request in progress
+            // and that it should try again later.
+            Err(ErrorCode::BUSY)
+        }
+    }
+
+    /// Starts a command that changes the virtual screen
+    fn command(&self, update: impl FnOnce()) -> Result<(), ErrorCode> {
+        self.start(Completion::Command).map(|()| {
+            update();
+            self.mux.changed(self);
+        })
+    }
+
+    /// Informs the client that its request is done
+    fn complete(&self) {
+        match self.completion.replace(Completion::None) {
+            Completion::None => {}
+            Completion::Command => {
+                self.client.map(|client| client.command_complete(Ok(())));
+            }
+            Completion::Write(len) => {
+                self.client_buffer.take().map(|buffer| {
+                    self.client
+                        .map(|client| client.write_complete(buffer, len, Ok(())));
+                });
+            }
+        }
+    }
+}
+
+impl<'a, A: Alarm<'a>> ListNode<'a, VirtualTextScreen<'a, A>> for VirtualTextScreen<'a, A> {
+    fn next(&'a self) -> &'a ListLink<'a, VirtualTextScreen<'a, A>> {
+        &self.next
+    }
+}
+
+/// This implementation allows `VirtualTextScreen` to be used as a `TextScreen`.
+impl<'a, A: Alarm<'a>> TextScreen<'a> for VirtualTextScreen<'a, A> {
+    fn set_client(&self, client: Option<&'a dyn TextScreenClient>) {
+        if let Some(client) = client {
+            self.client.set(client);
+        } else {
+            self.client.clear();
+        }
+    }
+
+    fn get_size(&self) -> (usize, usize) {
+        self.mux.screen.get_size()
+    }
+
+    fn print(
+        &self,
+        buffer: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        // Verify if the length of the usefull text does not overflow the received buffer.
+        if len > buffer.len() {
+            return Err((ErrorCode::SIZE, buffer));
+        }
+        // Copy the text to the client's text, starting at the cursor.
+        let screen_len = self.mux.screen_len();
+        let cursor = self.cursor.get();
+        let printed_len = cmp::min(len, screen_len - cursor);
+        match self.start(Completion::Write(printed_len)) {
+            Ok(()) => {
+                self.text.map(|text| {
+                    text[cursor..cursor + printed_len].copy_from_slice(&buffer[..printed_len]);
+                });
+                // Move the cursor after the text, back to the
+                // start of the screen if it reaches its end.
+                self.cursor.set((cursor + printed_len) % screen_len);
+                self.client_buffer.replace(buffer);
+                self.mux.changed(self);
+                Ok(())
+            }
+            Err(err) => Err((err, buffer)),
+        }
+    }
+
+    fn set_cursor(&self, x_position: usize, y_position: usize) -> Result<(), ErrorCode> {
+        let (columns, rows) = self.get_size();
+        if x_position < columns && y_position < rows {
+            self.command(|| self.cursor.set(y_position * columns + x_position))
+        } else {
+            Err(ErrorCode::INVAL)
+        }
+    }
+
+    fn hide_cursor(&self) -> Result<(), ErrorCode> {
+        self.command(|| self.cursor_visible.set(false))
+    }
+
+    fn show_cursor(&self) -> Result<(), ErrorCode> {
+        self.command(|| self.cursor_visible.set(true))
+    }
+
+    fn blink_cursor_on(&self) -> Result<(), ErrorCode> {
+        self.command(|| self.cursor_blink.set(true))
+    }
+
+    fn blink_cursor_off(&self) -> Result<(), ErrorCode> {
+        self.command(|| self.cursor_blink.set(false))
+    }
+
+    fn display_on(&self) -> Result<(), ErrorCode> {
+        self.start(Completion::Command).map(|()| {
+            self.enabled.set(true);
+            self.mux.select_owner();
+        })
+    }
+
+    fn display_off(&self) -> Result<(), ErrorCode> {
+        self.start(Completion::Command).map(|()| {
+            self.enabled.set(false);
+            self.mux.select_owner();
+        })
+    }
+
+    fn clear(&self) -> Result<(), ErrorCode> {
+        self.command(|| {
+            self.text.map(|text| {
+                for character in text.iter_mut() {
+                    *character = 0;
+                }
+            });
+            self.cursor.set(0);
+        })
+    }
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use common::leak;
+use core::cell::Cell;
+use drivers::ambient_light::{AmbientLight, FULL_BRIGHTNESS};
+use kernel::hil::adc::AdcChannel;
+use kernel::hil::gpio::{Configuration, Configure, FloatingState, Input};
+use kernel::hil::time::{Alarm, AlarmClient, Ticks32, Time};
+use kernel::syscall::SyscallDriver;
+use kernel::utilities::cells::OptionalCell;
+use mock_hil::{MockAdcChannel, MockAlarm, MockKernel, MockPin};
+
+type Driver = AmbientLight<'static, MockPin<'static>, MockAdcChannel, MockAlarm<'static>>;
+
+/// The time during which the refresh displays a row, in milliseconds
+const SLOT_MS: u32 = 10;
+
+/// The time between two samples, in milliseconds
+const SAMPLE_PERIOD_MS: u32 = 40;
+
+/// The brightness used in complete darkness, 20%
+const MIN_BRIGHTNESS: u8 = 51;
+
+/// The time that the LEDs discharge, in milliseconds
+const DISCHARGE_MS: u32 = 1;
+
+/// The time after which an unanswered sample is dropped, in milliseconds
+const SAMPLE_TIMEOUT_MS: u32 = 10;
+
+/// Stands for the LED matrix driver, which displays
+/// the next row each time its alarm fires
+#[derive(Default)]
+struct Refresh {
+    alarm: OptionalCell<&'static Driver>,
+    rows: Cell<usize>,
+}
+
+impl Refresh {
+    /// Displays the next row and waits for the end of its time slot
+    fn next_row(&self) {
+        self.rows.set(self.rows.get() + 1);
+        self.alarm
+            .map(|alarm| alarm.set_alarm(alarm.now(), Ticks32::from(SLOT_MS)));
+    }
+}
+
+impl AlarmClient for Refresh {
+    fn alarm(&self) {
+        self.next_row();
+    }
+}
+
+/// The matrix pins, the ADC, the refresh and the driver
+struct Board {
+    rows: [&'static MockPin<'static>; 2],
+    columns: [&'static MockPin<'static>; 2],
+    channels: [&'static MockAdcChannel; 2],
+    alarm: &'static MockAlarm<'static>,
+    refresh: &'static Refresh,
+    driver: &'static Driver,
+}
+
+impl Board {
+    fn new() -> Board {
+        // The LED matrix driver configures its pins as outputs.
+        let pin = || {
+            let pin = leak(MockPin::new(false));
+            pin.make_output();
+            pin
+        };
+        let rows = [pin(), pin()];
+        let columns = [pin(), pin()];
+        let channels = [
+            leak(MockAdcChannel::default()),
+            leak(MockAdcChannel::default()),
+        ];
+        let alarm = leak(MockAlarm::default());
+        let driver: &'static Driver = leak(AmbientLight::new(
+            leak(rows),
+            leak(columns),
+            leak(channels),
+            alarm,
+            SAMPLE_PERIOD_MS,
+            MIN_BRIGHTNESS,
+        ));
+        alarm.set_alarm_client(driver);
+        for channel in channels.iter() {
+            channel.set_client(driver);
+        }
+        let refresh = leak(Refresh::default());
+        refresh.alarm.set(driver);
+        driver.set_alarm_client(refresh);
+        driver.start();
+        // The LED matrix driver displays the first row once initialized.
+        refresh.next_row();
+        Board {
+            rows,
+            columns,
+            channels,
+            alarm,
+            refresh,
+            driver,
+        }
+    }
+
+    /// Returns whether the rows power the LEDs
+    fn is_on(&self) -> bool {
+        self.rows
+            .iter()
+            .all(|row| row.configuration() == Configuration::Output)
+    }
+
+    /// Displays the rows at full brightness until the driver
+    /// blanks the matrix for the first sample
+    fn wait_for_sample(&self) {
+        for _ in 0..SAMPLE_PERIOD_MS / SLOT_MS {
+            assert!(self.alarm.fire());
+        }
+    }
+
+    /// Waits for the sample and lets the ADC return `value` for all columns
+    fn sample(&self, value: u16) {
+        self.wait_for_sample();
+        assert!(self.alarm.advance(DISCHARGE_MS));
+        for channel in self.channels.iter() {
+            channel.set_value(value);
+            assert!(channel.complete());
+        }
+    }
+}
+
+#[test]
+fn start_pulls_the_rows_down() {
+    let board = Board::new();
+    for row in board.rows.iter() {
+        assert_eq!(row.floating_state(), FloatingState::PullDown);
+    }
+    assert!(board.is_on());
+    assert_eq!(board.driver.brightness(), FULL_BRIGHTNESS);
+}
+
+#[test]
+fn full_brightness_keeps_the_rows_on() {
+    let board = Board::new();
+    for rows in 1..3 {
+        assert!(board.is_on());
+        assert_eq!(board.refresh.rows.get(), rows);
+        assert_eq!(board.alarm.remaining_ms(), Some(SLOT_MS));
+        assert!(board.alarm.fire());
+    }
+}
+
+#[test]
+fn sampling_holds_the_refresh_at_the_end_of_a_frame() {
+    let board = Board::new();
+    board.wait_for_sample();
+    // The next row waits for the sample.
+    assert_eq!(
+        board.refresh.rows.get(),
+        (SAMPLE_PERIOD_MS / SLOT_MS) as usize
+    );
+    assert!(!board.is_on());
+    for column in board.columns.iter() {
+        assert_eq!(column.configuration(), Configuration::Input);
+        assert!(column.read());
+    }
+    assert_eq!(board.alarm.remaining_ms(), Some(DISCHARGE_MS));
+    assert!(!board.channels[0].is_sampling());
+
+    // The columns are sampled one after the other.
+    assert!(board.alarm.fire());
+    assert!(board.channels[0].is_sampling());
+    assert!(board.channels[0].complete());
+    assert!(board.channels[1].is_sampling());
+    assert!(board.channels[1].complete());
+    for column in board.columns.iter() {
+        assert_eq!(column.configuration(), Configuration::Output);
+    }
+    assert_eq!(
+        board.refresh.rows.get(),
+        (SAMPLE_PERIOD_MS / SLOT_MS) as usize + 1
+    );
+    assert!(board.is_on());
+}
+
+#[test]
+fn bright_light_keeps_the_full_brightness() {
+    let board = Board::new();
+    board.sample(0);
+    assert_eq!(board.driver.level(), u8::MAX);
+    assert_eq!(board.driver.brightness(), FULL_BRIGHTNESS);
+    assert!(board.is_on());
+    assert_eq!(board.alarm.remaining_ms(), Some(SLOT_MS));
+}
+
+#[test]
+fn darkness_dims_each_row() {
+    let board = Board::new();
+    board.sample(u16::MAX);
+    assert_eq!(board.driver.level(), 0);
+    assert_eq!(board.driver.brightness(), MIN_BRIGHTNESS);
+
+    // Each row is on for 20% of its time slot.
+    for _ in 0..3 {
+        let rows = board.refresh.rows.get();
+        assert!(board.is_on());
+        assert_eq!(board.alarm.remaining_ms(), Some(2));
+        assert!(board.alarm.fire());
+        assert!(!board.is_on());
+        assert_eq!(board.alarm.remaining_ms(), Some(8));
+        assert!(board.alarm.fire());
+        assert_eq!(board.refresh.rows.get(), rows + 1);
+    }
+}
+
+#[test]
+fn unanswered_sample_times_out() {
+    let board = Board::new();
+    board.wait_for_sample();
+    assert!(board.alarm.advance(DISCHARGE_MS));
+    assert!(board.channels[0].is_sampling());
+    assert_eq!(board.alarm.remaining_ms(), Some(SAMPLE_TIMEOUT_MS));
+
+    // The previous light level is kept and the refresh goes on.
+    assert!(board.alarm.fire());
+    assert!(!board.channels[0].is_sampling());
+    for column in board.columns.iter() {
+        assert_eq!(column.configuration(), Configuration::Output);
+    }
+    assert_eq!(board.driver.level(), u8::MAX);
+    assert!(board.is_on());
+    assert_eq!(board.alarm.remaining_ms(), Some(SLOT_MS));
+}
+
+#[test]
+fn light_level_is_the_average_of_the_columns() {
+    let board = Board::new();
+    board.wait_for_sample();
+    assert!(board.alarm.advance(DISCHARGE_MS));
+    board.channels[0].set_value(0x8000);
+    assert!(board.channels[0].complete());
+    board.channels[1].set_value(0x0000);
+    assert!(board.channels[1].complete());
+    assert_eq!(board.driver.level(), u8::MAX - 0x40);
+}
+
+#[test]
+fn commands_return_the_level_and_the_brightness() {
+    let board = Board::new();
+    let kernel = MockKernel::new();
+    board.sample(u16::MAX);
+
+    let ret = board.driver.command(1, 0, 0, kernel.process_id(0));
+    assert_eq!(ret.get_success_u32(), Some(0));
+    let ret = board.driver.command(2, 0, 0, kernel.process_id(0));
+    assert_eq!(ret.get_success_u32(), Some(MIN_BRIGHTNESS as u32));
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use common::{glyph, leak, text, MatrixScreen};
+use drivers::boot_message::{TicKVStore, MAX_TEXT_LEN};
+use drivers::display::{BootMessageStore, TextSettings};
+use kernel::hil::text_screen::TextScreen;
+use kernel::syscall::SyscallDriver;
+use kernel::ErrorCode;
+use mock_hil::{MockFlash, MockKernel};
+
+/// The size of a region of the key-value store
+const REGION_SIZE: usize = 1024;
+
+/// The number of regions of the key-value store
+const REGIONS: usize = 4;
+
+/// The minimum time between two saves of the boot message, in milliseconds
+const SAVE_INTERVAL_MS: u32 = 10000;
+
+/// The settings of the saved messages
+const SETTINGS: TextSettings = TextSettings {
+    speed: 200,
+    scroll: true,
+    gap: 3,
+    blank_ms: 50,
+};
+
+type Flash = MockFlash<REGION_SIZE>;
+type Store = TicKVStore<'static, &'static Flash, REGION_SIZE>;
+
+/// Builds the store of the boot message on `flash`,
+/// the way the board does after a reset
+fn store(flash: &'static Flash) -> &'static Store {
+    let read_buffer: &'static mut [u8; REGION_SIZE] = Box::leak(Box::new([0; REGION_SIZE]));
+    let store = leak(TicKVStore::new(flash, read_buffer, flash.size()));
+    assert_eq!(store.initialise(), Ok(()));
+    store
+}
+
+/// The board's screen, that stores its boot message in `flash`
+/// and is built the way the board does after a reset
+fn boot(flash: Option<&'static Flash>) -> MatrixScreen {
+    let board = MatrixScreen::new(5, 2, 300, 1);
+    if let Some(flash) = flash {
+        board
+            .screen
+            .set_boot_message_store(store(flash), SAVE_INTERVAL_MS);
+    }
+    board
+}
+
+/// Prints `message` on the screen of `board`
+fn print(board: &MatrixScreen, message: &str) {
+    assert!(TextScreen::print(board.screen, text(message), message.len()).is_ok());
+    board.call();
+}
+
+/// Sends a command to the driver of `board`
+fn command(
+    board: &MatrixScreen,
+    command_number: usize,
+    r2: usize,
+    r3: usize,
+) -> Result<(), ErrorCode> {
+    let kernel = MockKernel::new();
+    let res = board
+        .screen
+        .command(command_number, r2, r3, kernel.process_id(0));
+    if res.is_success() {
+        Ok(())
+    } else {
+        Err(res.get_failure().unwrap())
+    }
+}
+
+#[test]
+fn empty_flash_has_no_boot_message() {
+    let flash = leak(Flash::new(REGIONS));
+    let mut text = [0; MAX_TEXT_LEN];
+    assert_eq!(store(flash).load(&mut text), None);
+}
+
+#[test]
+fn boot_message_survives_a_reset() {
+    let flash = leak(Flash::new(REGIONS));
+    assert_eq!(store(flash).save(b"HELLO", SETTINGS), Ok(()));
+
+    let mut text = [0; MAX_TEXT_LEN];
+    assert_eq!(store(flash).load(&mut text), Some((5, SETTINGS)));
+    assert_eq!(&text[..5], b"HELLO");
+}
+
+#[test]
+fn saving_replaces_the_boot_message() {
+    let flash = leak(Flash::new(REGIONS));
+    let store = store(flash);
+    // Save enough messages to fill the flash, so that the
+    // invalidated ones have to be collected.
+    for speed in 0..200 {
+        let settings = TextSettings { speed, ..SETTINGS };
+        assert_eq!(store.save(b"0123456789", settings), Ok(()));
+    }
+    assert_eq!(store.save(b"LAST", SETTINGS), Ok(()));
+
+    let mut text = [0; MAX_TEXT_LEN];
+    assert_eq!(store.load(&mut text), Some((4, SETTINGS)));
+    assert_eq!(&text[..4], b"LAST");
+}
+
+#[test]
+fn failed_save_keeps_the_previous_message() {
+    let flash = leak(Flash::new(REGIONS));
+    let store = store(flash);
+    assert_eq!(store.save(b"OLD", SETTINGS), Ok(()));
+    flash.set_failing(true);
+    assert!(store.save(b"NEW", SETTINGS).is_err());
+    flash.set_failing(false);
+
+    let mut text = [0; MAX_TEXT_LEN];
+    assert_eq!(store.load(&mut text), Some((3, SETTINGS)));
+    assert_eq!(&text[..3], b"OLD");
+}
+
+#[test]
+fn long_messages_are_truncated() {
+    let flash = leak(Flash::new(REGIONS));
+    assert_eq!(
+        store(flash).save(&[b'A'; MAX_TEXT_LEN + 10], SETTINGS),
+        Ok(())
+    );
+
+    let mut text = [0; MAX_TEXT_LEN + 10];
+    assert_eq!(store(flash).load(&mut text), Some((MAX_TEXT_LEN, SETTINGS)));
+}
+
+#[test]
+fn driver_displays_the_saved_message_after_a_reset() {
+    let flash = leak(Flash::new(REGIONS));
+    let board = boot(Some(flash));
+    assert_eq!(board.matrix.state(), 0);
+    print(&board, "HI");
+    assert_eq!(command(&board, 1, 700, 0), Ok(()));
+    assert_eq!(command(&board, 5, 0, 0), Ok(()));
+
+    let board = boot(Some(flash));
+    assert_eq!(board.matrix.state(), glyph('H'));
+    assert_eq!(board.alarm.remaining_ms(), Some(700));
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('I'));
+}
+
+#[test]
+fn driver_saves_the_display_mode() {
+    let flash = leak(Flash::new(REGIONS));
+    let board = boot(Some(flash));
+    print(&board, "HI");
+    assert_eq!(command(&board, 2, 1, 4), Ok(()));
+    assert_eq!(command(&board, 3, 20, 0), Ok(()));
+    assert_eq!(command(&board, 5, 0, 0), Ok(()));
+
+    let mut text = [0; MAX_TEXT_LEN];
+    let settings = TextSettings {
+        speed: 300,
+        scroll: true,
+        gap: 4,
+        blank_ms: 20,
+    };
+    assert_eq!(store(flash).load(&mut text), Some((2, settings)));
+}
+
+#[test]
+fn saving_too_often_is_busy() {
+    let flash = leak(Flash::new(REGIONS));
+    let board = boot(Some(flash));
+    print(&board, "HI");
+    assert_eq!(command(&board, 5, 0, 0), Ok(()));
+    assert_eq!(command(&board, 5, 0, 0), Err(ErrorCode::BUSY));
+
+    board.alarm.advance(SAVE_INTERVAL_MS);
+    assert_eq!(command(&board, 5, 0, 0), Ok(()));
+}
+
+#[test]
+fn saving_an_empty_screen_removes_the_boot_message() {
+    let flash = leak(Flash::new(REGIONS));
+    let board = boot(Some(flash));
+    print(&board, "HI");
+    assert_eq!(command(&board, 5, 0, 0), Ok(()));
+
+    let board = boot(Some(flash));
+    assert!(TextScreen::clear(board.screen).is_ok());
+    board.call();
+    assert_eq!(command(&board, 5, 0, 0), Ok(()));
+
+    let board = boot(Some(flash));
+    assert_eq!(board.matrix.state(), 0);
+    assert_eq!(board.alarm.remaining_ms(), None);
+}
+
+#[test]
+fn saving_without_a_store_is_not_supported() {
+    let board = boot(None);
+    print(&board, "HI");
+    assert_eq!(command(&board, 5, 0, 0), Err(ErrorCode::NOSUPPORT));
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use common::{glyph, leak, MatrixScreen, Screen};
+use core::cell::Cell;
+use drivers::button_controls::{ButtonControls, SpeedRange};
+use drivers::display::{TextPause, TextSpeed};
+use kernel::hil::gpio::{ActivationMode, ClientWithValue, Interrupt};
+use mock_hil::{MockAlarm, MockPin};
+
+type Controls = ButtonControls<'static, MockPin<'static>, Screen, MockAlarm<'static>>;
+
+/// The speeds that the buttons set
+const RANGE: SpeedRange = SpeedRange {
+    step_ms: 100,
+    min_ms: 100,
+    max_ms: 500,
+};
+
+/// A driver that records the last interrupt it received
+#[derive(Default)]
+struct RecordingClient {
+    value: Cell<Option<u32>>,
+}
+
+impl ClientWithValue for RecordingClient {
+    fn fired(&self, value: u32) {
+        self.value.set(Some(value));
+    }
+}
+
+/// The board's hardware, the screen and the controls
+struct Board {
+    screen: MatrixScreen,
+    slower: &'static MockPin<'static>,
+    faster: &'static MockPin<'static>,
+    pause: &'static MockPin<'static>,
+    controls: &'static Controls,
+    client: &'static RecordingClient,
+}
+
+impl Board {
+    fn new(speed: u32) -> Board {
+        Board::with_controls(speed, true)
+    }
+
+    /// Builds a board whose buttons control the text if `enabled` is true
+    fn with_controls(speed: u32, enabled: bool) -> Board {
+        let screen = MatrixScreen::new(5, 2, speed, 1);
+
+        // The buttons are active low, they are released.
+        let slower = leak(MockPin::new(true));
+        let faster = leak(MockPin::new(true));
+        let pause = leak(MockPin::new(true));
+        let controls: &'static Controls = leak(ButtonControls::new(
+            slower,
+            faster,
+            pause,
+            ActivationMode::ActiveLow,
+            screen.screen,
+            screen.alarm,
+            RANGE,
+            enabled,
+        ));
+        let client = leak(RecordingClient::default());
+        for pin in [slower, faster, pause].iter() {
+            pin.set_client(controls);
+        }
+        controls.set_client(client);
+        controls.setup();
+        Board {
+            screen,
+            slower,
+            faster,
+            pause,
+            controls,
+            client,
+        }
+    }
+
+    /// Presses and releases a button, then waits
+    /// for longer than the bounces
+    fn click(&self, pin: &MockPin) {
+        pin.set_level(false);
+        pin.set_level(true);
+        self.screen.alarm.advance(100);
+    }
+}
+
+#[test]
+fn setup_enables_the_interrupts() {
+    let board = Board::new(300);
+    assert!(board.slower.interrupts_enabled());
+    assert!(board.faster.interrupts_enabled());
+    assert!(board.pause.interrupts_enabled());
+}
+
+#[test]
+fn button_a_slows_the_text_down() {
+    let board = Board::new(300);
+    board.click(board.slower);
+    assert_eq!(board.screen.screen.speed(), 400);
+    board.click(board.slower);
+    board.click(board.slower);
+    assert_eq!(board.screen.screen.speed(), RANGE.max_ms);
+}
+
+#[test]
+fn button_b_speeds_the_text_up() {
+    let board = Board::new(300);
+    board.click(board.faster);
+    assert_eq!(board.screen.screen.speed(), 200);
+    board.click(board.faster);
+    board.click(board.faster);
+    assert_eq!(board.screen.screen.speed(), RANGE.min_ms);
+}
+
+#[test]
+fn releasing_a_button_does_nothing() {
+    let board = Board::new(300);
+    board.slower.set_level(false);
+    assert_eq!(board.screen.screen.speed(), 400);
+    board.screen.alarm.advance(100);
+    board.slower.set_level(true);
+    assert_eq!(board.screen.screen.speed(), 400);
+}
+
+#[test]
+fn bounces_are_ignored() {
+    let board = Board::new(300);
+    board.slower.set_level(false);
+    board.slower.set_level(true);
+    board.slower.set_level(false);
+    board.slower.set_level(true);
+    assert_eq!(board.screen.screen.speed(), 400);
+
+    board.screen.alarm.advance(100);
+    board.click(board.slower);
+    assert_eq!(board.screen.screen.speed(), 500);
+}
+
+#[test]
+fn touch_logo_pauses_and_resumes_the_text() {
+    let board = Board::new(300);
+    board.screen.display("HI");
+    assert_eq!(board.screen.matrix.state(), glyph('H'));
+
+    board.click(board.pause);
+    assert!(board.screen.screen.is_paused());
+    assert!(board.screen.alarm.fire());
+    assert_eq!(board.screen.matrix.state(), glyph('H'));
+
+    board.click(board.pause);
+    assert!(!board.screen.screen.is_paused());
+    assert_eq!(board.screen.matrix.state(), glyph('I'));
+}
+
+#[test]
+fn disabled_controls_leave_the_text_alone() {
+    let board = Board::with_controls(300, false);
+    assert!(!board.slower.interrupts_enabled());
+    board.slower.set_level(false);
+    ClientWithValue::fired(board.controls, 0);
+    assert_eq!(board.screen.screen.speed(), 300);
+}
+
+#[test]
+fn interrupts_are_forwarded_to_the_client() {
+    let board = Board::new(300);
+    board.slower.set_level(false);
+    ClientWithValue::fired(board.controls, 0);
+    assert_eq!(board.client.value.get(), Some(0));
+    // The press is counted once, whichever interrupts report it.
+    assert_eq!(board.screen.screen.speed(), 400);
+
+    let board = Board::with_controls(300, false);
+    ClientWithValue::fired(board.controls, 2);
+    assert_eq!(board.client.value.get(), Some(2));
+}
@@ -0,0 +1,97 @@
+//! The fixtures shared by the tests of the drivers.
+//!
+//! Each test file uses only some of them.
+#![allow(dead_code)]
+
+use drivers::led_matrix_text::LedMatrixText;
+use font::{Font, Font5x5};
+use kernel::dynamic_deferred_call::{
+    DeferredCallHandle, DynamicDeferredCall, DynamicDeferredCallClient,
+    DynamicDeferredCallClientState,
+};
+use kernel::hil::text_screen::TextScreen;
+use kernel::hil::time::Alarm;
+use mock_hil::{MockAlarm, MockLed, MockMatrix};
+
+pub type Screen = LedMatrixText<'static, MockLed, MockAlarm<'static>>;
+
+/// Leaks `value` so that it lives forever
+pub fn leak<T>(value: T) -> &'static T {
+    Box::leak(Box::new(value))
+}
+
+/// Allocates a buffer that lives forever
+pub fn buffer(len: usize) -> &'static mut [u8] {
+    Box::leak(vec![0; len].into_boxed_slice())
+}
+
+/// Copies `text` into a buffer that lives forever
+pub fn text(text: &str) -> &'static mut [u8] {
+    Box::leak(text.as_bytes().to_vec().into_boxed_slice())
+}
+
+/// Returns the glyph of `character` the way the matrix displays it
+pub fn glyph(character: char) -> u32 {
+    Font5x5.glyph(character).unwrap().bits()
+}
+
+/// An LED matrix text screen and the hardware that it uses
+pub struct MatrixScreen {
+    pub matrix: &'static MockMatrix,
+    pub alarm: &'static MockAlarm<'static>,
+    pub screen: &'static Screen,
+    pub handle: DeferredCallHandle,
+    pub deferred_caller: &'static DynamicDeferredCall,
+}
+
+impl MatrixScreen {
+    /// Creates a screen of `columns` by `rows` characters, each of them
+    /// displayed for `speed` milliseconds
+    ///
+    /// The deferred caller has room for `clients` clients, the
+    /// screen being the first one.
+    pub fn new(columns: usize, rows: usize, speed: u32, clients: usize) -> MatrixScreen {
+        let matrix = leak(MockMatrix::default());
+        let leds: &'static [&'static MockLed; 25] = leak(matrix.leds());
+        let alarm = leak(MockAlarm::default());
+        let client_states: &'static [DynamicDeferredCallClientState] = Box::leak(
+            (0..clients)
+                .map(|_| DynamicDeferredCallClientState::default())
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        );
+        let deferred_caller = leak(DynamicDeferredCall::new(client_states));
+        let screen: &'static Screen = leak(LedMatrixText::new(
+            leds,
+            alarm,
+            buffer(columns * rows),
+            columns,
+            speed,
+            deferred_caller,
+        ));
+        let handle = deferred_caller.register(screen).unwrap();
+        screen.initialize_callback_handle(handle);
+        alarm.set_alarm_client(screen);
+        MatrixScreen {
+            matrix,
+            alarm,
+            screen,
+            handle,
+            deferred_caller,
+        }
+    }
+
+    /// Delivers the deferred callback that the screen has scheduled,
+    /// the way the kernel does after the current request returns
+    pub fn call(&self) {
+        self.screen.call(self.handle);
+    }
+
+    /// Turns the display on and prints `message`
+    pub fn display(&self, message: &str) {
+        assert_eq!(TextScreen::display_on(self.screen), Ok(()));
+        self.call();
+        assert!(TextScreen::print(self.screen, text(message), message.len()).is_ok());
+        self.call();
+    }
+}
@@ -0,0 +1,227 @@
+mod common;
+
+use common::{buffer, glyph, leak, MatrixScreen};
+use core::cell::RefCell;
+use drivers::display_console::DisplayConsole;
+use kernel::hil::text_screen::TextScreen;
+use kernel::hil::uart::{self, Receive, ReceiveClient, Transmit};
+use kernel::utilities::cells::OptionalCell;
+use kernel::ErrorCode;
+use mock_hil::MockUart;
+
+type Console = DisplayConsole<'static>;
+
+/// The delay between two characters, in milliseconds
+const SPEED: u32 = 300;
+
+/// A process console that records the characters it receives
+#[derive(Default)]
+struct ProcessConsole {
+    uart: OptionalCell<&'static Console>,
+    received: RefCell<Vec<u8>>,
+}
+
+impl ProcessConsole {
+    /// Returns the characters received since the previous call
+    fn received(&self) -> String {
+        String::from_utf8(self.received.replace(Vec::new())).unwrap()
+    }
+}
+
+impl ReceiveClient for ProcessConsole {
+    fn received_buffer(
+        &self,
+        rx_buffer: &'static mut [u8],
+        rx_len: usize,
+        _rval: Result<(), ErrorCode>,
+        _error: uart::Error,
+    ) {
+        self.received
+            .borrow_mut()
+            .extend_from_slice(&rx_buffer[..rx_len]);
+        self.uart.map(|uart| uart.receive_buffer(rx_buffer, 1));
+    }
+}
+
+/// The board's hardware, the screen and the consoles
+struct Board {
+    screen: MatrixScreen,
+    uart: &'static MockUart<'static>,
+    console_uart: &'static MockUart<'static>,
+    console: &'static Console,
+    process_console: &'static ProcessConsole,
+}
+
+impl Board {
+    fn new() -> Board {
+        let screen = MatrixScreen::new(5, 2, SPEED, 1);
+        let uart = leak(MockUart::default());
+        let console_uart = leak(MockUart::default());
+        let console: &'static Console = leak(DisplayConsole::new(
+            uart,
+            console_uart,
+            screen.screen,
+            screen.screen,
+            buffer(32),
+            buffer(64),
+            buffer(1),
+            buffer(32),
+            buffer(10),
+        ));
+        TextScreen::set_client(screen.screen, Some(console));
+        uart.set_transmit_client(console);
+        uart.set_receive_client(console);
+        assert_eq!(console.start(), Ok(()));
+
+        let process_console = leak(ProcessConsole::default());
+        process_console.uart.set(console);
+        console.set_receive_client(process_console);
+        assert!(console.receive_buffer(buffer(1), 1).is_ok());
+        Board {
+            screen,
+            uart,
+            console_uart,
+            console,
+            process_console,
+        }
+    }
+
+    /// Types a command line and delivers the screen's deferred
+    /// callbacks until the command is done
+    fn run(&self, line: &str) -> String {
+        self.uart.type_text(line);
+        for _ in 0..5 {
+            self.screen.call();
+        }
+        self.uart.output()
+    }
+}
+
+#[test]
+fn display_shows_the_text() {
+    let board = Board::new();
+    assert_eq!(board.run("display HI\r"), "display HI\r\ndisplay: ok\r\n");
+    assert_eq!(board.screen.matrix.state(), glyph('H'));
+    assert!(board.screen.alarm.fire());
+    assert_eq!(board.screen.matrix.state(), glyph('I'));
+}
+
+#[test]
+fn display_replaces_the_previous_text() {
+    let board = Board::new();
+    assert_eq!(board.run("display HI\r"), "display HI\r\ndisplay: ok\r\n");
+    assert_eq!(board.run("display O\r"), "display O\r\ndisplay: ok\r\n");
+    assert_eq!(board.screen.matrix.state(), glyph('O'));
+    assert!(board.screen.alarm.fire());
+    assert_eq!(board.screen.matrix.state(), glyph('O'));
+}
+
+#[test]
+fn display_off_and_on() {
+    let board = Board::new();
+    assert_eq!(board.run("display HI\r"), "display HI\r\ndisplay: ok\r\n");
+    assert_eq!(board.run("display off\r"), "display off\r\ndisplay: ok\r\n");
+    assert!(board.screen.alarm.fire());
+    assert_eq!(board.screen.matrix.state(), 0);
+
+    assert_eq!(board.run("display on\r"), "display on\r\ndisplay: ok\r\n");
+    assert!(board.screen.alarm.fire());
+    assert_ne!(board.screen.matrix.state(), 0);
+}
+
+#[test]
+fn clear_erases_the_text() {
+    let board = Board::new();
+    assert_eq!(board.run("display HI\r"), "display HI\r\ndisplay: ok\r\n");
+    assert_eq!(board.run("clear\n"), "clear\r\ndisplay: ok\r\n");
+    assert_eq!(board.screen.matrix.state(), 0);
+    board.screen.alarm.fire();
+    assert_eq!(board.screen.matrix.state(), 0);
+}
+
+#[test]
+fn speed_sets_the_delay_between_characters() {
+    let board = Board::new();
+    assert_eq!(board.run("speed 700\r"), "speed 700\r\ndisplay: ok\r\n");
+    assert_eq!(board.run("display HI\r"), "display HI\r\ndisplay: ok\r\n");
+    assert_eq!(board.screen.alarm.remaining_ms(), Some(700));
+}
+
+#[test]
+fn invalid_arguments_print_the_usage() {
+    let board = Board::new();
+    assert_eq!(
+        board.run("speed fast\r"),
+        "speed fast\r\nusage: speed <ms>\r\n"
+    );
+    assert_eq!(board.run("speed 0\r"), "speed 0\r\nusage: speed <ms>\r\n");
+    assert_eq!(
+        board.run("display\r"),
+        "display\r\nusage: display <text>|on|off\r\n"
+    );
+}
+
+#[test]
+fn process_console_commands_are_forwarded() {
+    let board = Board::new();
+    assert_eq!(board.run("list\r"), "");
+    assert_eq!(board.process_console.received(), "list\r");
+    assert_eq!(board.run("stop blink\r"), "");
+    assert_eq!(board.process_console.received(), "stop blink\r");
+    assert_eq!(board.run("\r"), "");
+    assert_eq!(board.process_console.received(), "\r");
+    assert_eq!(board.screen.matrix.state(), 0);
+}
+
+#[test]
+fn lines_that_only_start_like_a_command_are_forwarded() {
+    let board = Board::new();
+    assert_eq!(board.run("status\r"), "");
+    assert_eq!(board.process_console.received(), "status\r");
+    assert_eq!(board.run("displays\r"), "");
+    assert_eq!(board.process_console.received(), "displays\r");
+    assert_eq!(board.run("cl\r"), "");
+    assert_eq!(board.process_console.received(), "cl\r");
+}
+
+#[test]
+fn display_commands_are_not_forwarded() {
+    let board = Board::new();
+    board.run("display HI\r");
+    board.run("clear\r");
+    assert_eq!(board.process_console.received(), "");
+}
+
+#[test]
+fn process_console_transmits_through_its_uart() {
+    let board = Board::new();
+    let text: &'static mut [u8] = Box::leak(b"tock$ ".to_vec().into_boxed_slice());
+    assert!(board.console.transmit_buffer(text, 6).is_ok());
+    assert_eq!(board.console_uart.output(), "tock$ ");
+    assert_eq!(board.uart.output(), "");
+}
+
+#[test]
+fn commands_wait_for_the_previous_one() {
+    let board = Board::new();
+    board.uart.type_text("display A\r");
+    board.uart.type_text("display B\r");
+    assert_eq!(
+        board.uart.output(),
+        "display A\r\ndisplay B\r\ndisplay: busy\r\n"
+    );
+    assert_eq!(board.run(""), "display: ok\r\n");
+    assert_eq!(board.screen.matrix.state(), glyph('A'));
+}
+
+#[test]
+fn backspace_erases_the_last_character() {
+    let board = Board::new();
+    assert_eq!(
+        board.run("display XZ\x7f\x08Y\r"),
+        "display XZ\x08 \x08\x08 \x08Y\r\ndisplay: ok\r\n"
+    );
+    assert_eq!(board.screen.matrix.state(), glyph('Y'));
+    assert!(board.screen.alarm.fire());
+    assert_eq!(board.screen.matrix.state(), glyph('Y'));
+}
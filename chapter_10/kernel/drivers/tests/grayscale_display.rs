@@ -0,0 +1,216 @@
+mod common;
+
+use common::leak;
+use drivers::grayscale_display::{GrayscaleDisplay, DRIVER_NUM, FRAMEBUFFER_LEN};
+use kernel::hil::time::Alarm;
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::ErrorCode;
+use mock_hil::{MockAlarm, MockKernel, MockLed, MockMatrix};
+
+type Display = GrayscaleDisplay<'static, MockLed, MockAlarm<'static>>;
+
+/// The number of PWM slices in a PWM period
+const LEVELS: usize = 8;
+
+/// The duration of a PWM slice, in milliseconds
+const SLICE_MS: u32 = 1;
+
+/// The glyph bits of all the LEDs
+const ALL_LEDS: u32 = (1 << 25) - 1;
+
+/// The index of a process that the kernel has not loaded
+const NOT_LOADED: usize = 1;
+
+/// Returns the bitmap that lights only the LED `index`
+fn pixel(index: usize) -> u32 {
+    1 << (24 - index)
+}
+
+/// The kernel, the LED matrix, the alarm and the driver
+///
+/// The kernel has loaded a single process.
+struct Board {
+    kernel: MockKernel,
+    matrix: &'static MockMatrix,
+    alarm: &'static MockAlarm<'static>,
+    driver: &'static Display,
+}
+
+impl Board {
+    fn new() -> Board {
+        let kernel = MockKernel::with_processes(&["example_app"]);
+        let matrix = leak(MockMatrix::default());
+        let leds: &'static [&'static MockLed; 25] = leak(matrix.leds());
+        let alarm = leak(MockAlarm::default());
+        let framebuffer = Box::leak(Box::new([0; FRAMEBUFFER_LEN]));
+        let driver = leak(GrayscaleDisplay::new(
+            leds,
+            alarm,
+            framebuffer,
+            kernel.create_grant(DRIVER_NUM),
+        ));
+        alarm.set_alarm_client(driver);
+        Board {
+            kernel,
+            matrix,
+            alarm,
+            driver,
+        }
+    }
+
+    /// Issues the command `command_number` for the process at `index`
+    fn command(&self, index: usize, command_number: usize, r2: usize, r3: usize) -> CommandReturn {
+        self.driver
+            .command(command_number, r2, r3, self.kernel.process_id(index))
+    }
+
+    /// Shares `frame` with the driver and displays it
+    fn commit(&self, frame: &[u8]) -> CommandReturn {
+        let buffer = self.kernel.process(0).readonly_buffer(frame);
+        assert!(self
+            .driver
+            .allow_readonly(self.kernel.process_id(0), 0, buffer)
+            .is_ok());
+        self.command(0, 1, 0, 0)
+    }
+
+    /// Returns the state of the LEDs during each slice of a PWM period
+    fn period(&self) -> Vec<u32> {
+        let mut states = vec![self.matrix.state()];
+        for _ in 1..LEVELS {
+            assert!(self.alarm.advance(SLICE_MS));
+            states.push(self.matrix.state());
+        }
+        states
+    }
+}
+
+/// Returns a framebuffer where only the LED `index` has `brightness`
+fn frame_with(index: usize, brightness: u8) -> [u8; FRAMEBUFFER_LEN] {
+    let mut frame = [0; FRAMEBUFFER_LEN];
+    frame[index] = brightness;
+    frame
+}
+
+#[test]
+fn command_0_reports_the_driver_as_present() {
+    let board = Board::new();
+
+    assert!(board.command(0, 0, 0, 0).is_success());
+}
+
+#[test]
+fn command_1_needs_a_buffer_with_all_the_leds() {
+    let board = Board::new();
+
+    assert_eq!(
+        board.command(0, 1, 0, 0).get_failure(),
+        Some(ErrorCode::NOMEM)
+    );
+    assert_eq!(
+        board.commit(&[u8::MAX; FRAMEBUFFER_LEN - 1]).get_failure(),
+        Some(ErrorCode::SIZE)
+    );
+    assert!(board.command(NOT_LOADED, 1, 0, 0).is_failure());
+    assert_eq!(board.matrix.changes(), 0);
+}
+
+#[test]
+fn fully_on_and_off_leds_need_no_alarm() {
+    let board = Board::new();
+    let mut frame = [0; FRAMEBUFFER_LEN];
+    frame[0] = u8::MAX;
+    frame[24] = u8::MAX;
+
+    assert!(board.commit(&frame).is_success());
+
+    assert_eq!(board.matrix.state(), pixel(0) | pixel(24));
+    assert!(!board.alarm.is_armed());
+}
+
+#[test]
+fn intermediate_brightness_starts_the_alarm() {
+    let board = Board::new();
+
+    assert!(board.commit(&frame_with(12, 100)).is_success());
+
+    assert_eq!(board.alarm.remaining_ms(), Some(SLICE_MS));
+}
+
+#[test]
+fn a_led_is_on_while_its_brightness_is_above_the_slice_threshold() {
+    let board = Board::new();
+    let on = pixel(12);
+    let off = 0;
+
+    // The thresholds of the slices are 0, 32, 64, ... 224.
+    assert!(board.commit(&frame_with(12, 1)).is_success());
+    assert_eq!(board.period(), [on, off, off, off, off, off, off, off]);
+
+    assert!(board.commit(&frame_with(12, 32)).is_success());
+    assert_eq!(board.period(), [on, off, off, off, off, off, off, off]);
+
+    assert!(board.commit(&frame_with(12, 33)).is_success());
+    assert_eq!(board.period(), [on, on, off, off, off, off, off, off]);
+
+    assert!(board.commit(&frame_with(12, 100)).is_success());
+    assert_eq!(board.period(), [on, on, on, on, off, off, off, off]);
+
+    assert!(board.commit(&frame_with(12, 254)).is_success());
+    assert_eq!(board.period(), [on, on, on, on, on, on, on, on]);
+}
+
+#[test]
+fn the_slices_repeat_after_a_period() {
+    let board = Board::new();
+
+    assert!(board.commit(&frame_with(0, 100)).is_success());
+    let first = board.period();
+    assert!(board.alarm.advance(SLICE_MS));
+
+    assert_eq!(board.period(), first);
+}
+
+#[test]
+fn a_frame_without_intermediate_brightness_stops_the_alarm() {
+    let board = Board::new();
+    assert!(board.commit(&frame_with(0, 100)).is_success());
+    assert!(board.alarm.advance(SLICE_MS));
+
+    assert!(board.commit(&[u8::MAX; FRAMEBUFFER_LEN]).is_success());
+
+    assert_eq!(board.matrix.state(), ALL_LEDS);
+    assert!(!board.alarm.is_armed());
+}
+
+#[test]
+fn command_2_turns_off_the_leds_and_stops_the_alarm() {
+    let board = Board::new();
+    assert!(board.commit(&[100; FRAMEBUFFER_LEN]).is_success());
+
+    assert!(board.command(0, 2, 0, 0).is_success());
+
+    assert_eq!(board.matrix.state(), 0);
+    assert!(!board.alarm.is_armed());
+}
+
+#[test]
+fn command_3_rotates_the_framebuffer() {
+    let board = Board::new();
+    assert!(board.commit(&frame_with(0, u8::MAX)).is_success());
+
+    assert!(board.command(0, 3, 90, 0).is_success());
+    assert_eq!(board.matrix.state(), pixel(4));
+
+    assert!(board.command(0, 3, 0, 1).is_success());
+    assert_eq!(board.matrix.state(), pixel(4));
+
+    assert_eq!(
+        board.command(0, 3, 45, 0).get_failure(),
+        Some(ErrorCode::INVAL)
+    );
+    assert_eq!(
+        board.command(0, 3, 0, 2).get_failure(),
+        Some(ErrorCode::INVAL)
+    );
+}
@@ -0,0 +1,487 @@
+use drivers::led_matrix_text::LedMatrixText;
+use drivers::matrix_transform::{Rotation, Transform};
+use font::{Font, Font5x5, GLYPH_HEIGHT, GLYPH_WIDTH};
+use kernel::dynamic_deferred_call::{
+    DeferredCallHandle, DynamicDeferredCall, DynamicDeferredCallClient,
+    DynamicDeferredCallClientState,
+};
+use kernel::hil::text_screen::TextScreen;
+use kernel::hil::time::Alarm;
+use kernel::syscall::SyscallDriver;
+use kernel::ErrorCode;
+use mock_hil::{MockAlarm, MockKernel, MockLed, MockMatrix, MockTextScreenClient};
+
+type Driver = LedMatrixText<'static, MockLed, MockAlarm<'static>>;
+
+/// The glyph bits of all the LEDs
+const ALL_LEDS: u32 = (1 << 25) - 1;
+
+/// The delay between two characters, in milliseconds
+const SPEED: u32 = 300;
+
+/// The board's hardware and the driver
+///
+/// The driver and its deferred caller have to live forever,
+/// so they are leaked.
+struct Board {
+    matrix: &'static MockMatrix,
+    alarm: &'static MockAlarm<'static>,
+    driver: &'static Driver,
+    client: &'static MockTextScreenClient,
+    handle: DeferredCallHandle,
+}
+
+impl Board {
+    fn new(columns: usize, rows: usize) -> Board {
+        Board::with_buffer_len(columns, columns * rows)
+    }
+
+    /// Creates a driver with a buffer of `len` characters,
+    /// which might not be a multiple of `columns`
+    fn with_buffer_len(columns: usize, len: usize) -> Board {
+        let matrix: &'static MockMatrix = Box::leak(Box::new(MockMatrix::default()));
+        let leds: &'static [&'static MockLed; 25] = Box::leak(Box::new(matrix.leds()));
+        let alarm: &'static MockAlarm<'static> = Box::leak(Box::new(MockAlarm::default()));
+        let client_states: &'static [DynamicDeferredCallClientState] =
+            Box::leak(Box::new([DynamicDeferredCallClientState::default()]));
+        let deferred_caller: &'static DynamicDeferredCall =
+            Box::leak(Box::new(DynamicDeferredCall::new(client_states)));
+        let buffer: &'static mut [u8] = Box::leak(vec![0; len].into_boxed_slice());
+        let driver: &'static Driver = Box::leak(Box::new(LedMatrixText::new(
+            leds,
+            alarm,
+            buffer,
+            columns,
+            SPEED,
+            deferred_caller,
+        )));
+        let handle = deferred_caller.register(driver).unwrap();
+        driver.initialize_callback_handle(handle);
+        alarm.set_alarm_client(driver);
+        let client: &'static MockTextScreenClient =
+            Box::leak(Box::new(MockTextScreenClient::default()));
+        TextScreen::set_client(driver, Some(client));
+        Board {
+            matrix,
+            alarm,
+            driver,
+            client,
+            handle,
+        }
+    }
+
+    /// Delivers the deferred callback that the driver has scheduled,
+    /// the way the kernel does after the current request returns
+    fn deferred_call(&self) {
+        self.driver.call(self.handle);
+    }
+
+    fn display_on(&self) {
+        assert_eq!(TextScreen::display_on(self.driver), Ok(()));
+        self.deferred_call();
+    }
+
+    fn set_cursor(&self, x: usize, y: usize) {
+        assert_eq!(TextScreen::set_cursor(self.driver, x, y), Ok(()));
+        self.deferred_call();
+    }
+
+    fn print(&self, text: &str) {
+        let buffer: &'static mut [u8] = Box::leak(text.as_bytes().to_vec().into_boxed_slice());
+        assert!(TextScreen::print(self.driver, buffer, text.len()).is_ok());
+        self.deferred_call();
+    }
+
+    fn clear(&self) {
+        assert_eq!(TextScreen::clear(self.driver), Ok(()));
+        self.deferred_call();
+    }
+
+    /// Verifies that the driver displays `text` and then starts over
+    fn assert_displays(&self, text: &str) {
+        let kernel = MockKernel::new();
+        // Setting the character mode restarts the display
+        // with the first character.
+        assert!(self
+            .driver
+            .command(2, 0, 0, kernel.process_id(0))
+            .is_success());
+        for character in text.chars().chain(text.chars().take(1)) {
+            assert!(self.alarm.fire());
+            assert_eq!(
+                self.matrix.state(),
+                glyph(character),
+                "{:?} in {:?}",
+                character,
+                text
+            );
+        }
+    }
+}
+
+/// Returns the glyph of `character` the way the matrix displays it
+fn glyph(character: char) -> u32 {
+    Font5x5.glyph(character).unwrap().bits()
+}
+
+#[test]
+fn print_displays_the_text_one_character_at_a_time() {
+    let board = Board::new(10, 1);
+    board.display_on();
+    board.print("AB");
+
+    assert_eq!(board.matrix.state(), glyph('A'));
+    assert_eq!(board.alarm.remaining_ms(), Some(SPEED));
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('B'));
+    // The text repeats
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('A'));
+}
+
+#[test]
+fn rows_are_displayed_in_order() {
+    let board = Board::new(5, 3);
+    board.display_on();
+
+    assert_eq!(TextScreen::get_size(board.driver), (5, 3));
+    board.set_cursor(0, 2);
+    board.print("C");
+    board.set_cursor(0, 0);
+    board.print("A");
+
+    // The display started with the text "C" and continues
+    // with "A C": the rows are separated by a space and the
+    // empty row is skipped.
+    assert_eq!(board.matrix.state(), glyph('C'));
+    for character in [' ', 'C', 'A', ' ', 'C'] {
+        assert!(board.alarm.fire());
+        assert_eq!(board.matrix.state(), glyph(character), "{:?}", character);
+    }
+}
+
+#[test]
+fn print_writes_at_the_cursor() {
+    let board = Board::new(5, 1);
+    board.display_on();
+
+    board.print("A");
+    board.print("B");
+    assert_eq!(board.matrix.state(), glyph('A'));
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('B'));
+}
+
+#[test]
+fn set_cursor_rejects_positions_outside_the_screen() {
+    let board = Board::new(5, 2);
+
+    assert_eq!(
+        TextScreen::set_cursor(board.driver, 5, 0),
+        Err(ErrorCode::INVAL)
+    );
+    assert_eq!(
+        TextScreen::set_cursor(board.driver, 0, 2),
+        Err(ErrorCode::INVAL)
+    );
+}
+
+#[test]
+fn show_cursor_inverts_the_character_at_the_cursor() {
+    let board = Board::new(5, 1);
+    board.display_on();
+    board.print("AB");
+
+    assert_eq!(TextScreen::show_cursor(board.driver), Ok(()));
+    board.deferred_call();
+    // The cursor is after the text, on an empty cell
+    for expected in [glyph('B'), !glyph(' ') & ALL_LEDS, glyph('A')] {
+        assert!(board.alarm.fire());
+        assert_eq!(board.matrix.state(), expected);
+    }
+}
+
+#[test]
+fn blink_cursor_toggles_the_highlight_on_its_own_period() {
+    let board = Board::new(5, 1);
+    board.display_on();
+    board.print("AB");
+    board.set_cursor(0, 0);
+
+    assert_eq!(TextScreen::show_cursor(board.driver), Ok(()));
+    board.deferred_call();
+    assert_eq!(TextScreen::blink_cursor_on(board.driver), Ok(()));
+    board.deferred_call();
+    // The highlight toggles every 500 ms, whatever the number of
+    // characters, so "A" is highlighted every other pass.
+    for (remaining, expected) in [
+        (SPEED, glyph('B')),
+        (200, glyph('B')),
+        (100, glyph('A')),
+        (SPEED, glyph('B')),
+        (100, glyph('B')),
+        (200, !glyph('A') & ALL_LEDS),
+        (SPEED, glyph('B')),
+        (SPEED, glyph('A')),
+    ] {
+        assert_eq!(board.alarm.remaining_ms(), Some(remaining));
+        assert!(board.alarm.fire());
+        assert_eq!(board.matrix.state(), expected);
+    }
+}
+
+#[test]
+fn print_completes_with_the_written_length() {
+    let board = Board::new(5, 1);
+
+    board.print("HELLO");
+    assert_eq!(board.client.writes(), 1);
+    assert_eq!(board.client.written_len(), 5);
+    assert_eq!(board.client.result(), Some(Ok(())));
+    assert_eq!(board.client.take_buffer().unwrap(), b"HELLO");
+
+    // The text is truncated at the end of the screen
+    board.set_cursor(3, 0);
+    board.print("ABC");
+    assert_eq!(board.client.writes(), 2);
+    assert_eq!(board.client.written_len(), 2);
+}
+
+#[test]
+fn print_stops_at_the_end_of_the_last_full_row() {
+    let board = Board::with_buffer_len(3, 7);
+    assert_eq!(TextScreen::get_size(board.driver), (3, 2));
+
+    board.print("ABCDEFG");
+    assert_eq!(board.client.written_len(), 6);
+
+    // The cursor went back to the start of the screen.
+    board.print("X");
+    assert_eq!(board.client.written_len(), 1);
+    board.display_on();
+    board.assert_displays("XBC DEF");
+}
+
+#[test]
+fn print_continues_the_text_at_the_cursor() {
+    let board = Board::new(10, 1);
+    board.display_on();
+
+    board.print("HELLO");
+    board.print("HI");
+    board.assert_displays("HELLOHI");
+}
+
+#[test]
+fn print_overwrites_the_text_after_set_cursor() {
+    let board = Board::new(10, 1);
+    board.display_on();
+
+    board.print("HELLO");
+    board.set_cursor(0, 0);
+    board.print("HI");
+    board.assert_displays("HILLO");
+}
+
+#[test]
+fn clear_resets_the_cursor() {
+    let board = Board::new(10, 1);
+    board.display_on();
+
+    board.print("HELLO");
+    board.clear();
+    assert_eq!(board.client.result(), Some(Ok(())));
+    board.print("HI");
+    board.assert_displays("HI");
+}
+
+#[test]
+fn command_4_replaces_the_text() {
+    let board = Board::new(10, 2);
+    let kernel = MockKernel::new();
+    board.display_on();
+
+    assert!(board
+        .driver
+        .command(4, 1, 0, kernel.process_id(0))
+        .is_success());
+    board.print("HELLO");
+    board.set_cursor(0, 1);
+    board.print("HI");
+    board.assert_displays("HI");
+
+    // Back to writing at the cursor
+    assert!(board
+        .driver
+        .command(4, 0, 0, kernel.process_id(0))
+        .is_success());
+    board.print("!");
+    board.assert_displays("HI!");
+
+    let ret = board.driver.command(4, 2, 0, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+}
+
+#[test]
+fn print_keeps_the_leds_off_while_the_display_is_off() {
+    let board = Board::new(10, 1);
+    board.print("AB");
+
+    assert_eq!(board.matrix.state(), 0);
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), 0);
+}
+
+#[test]
+fn clear_stops_the_display() {
+    let board = Board::new(10, 1);
+    board.display_on();
+    board.print("AB");
+
+    assert_eq!(TextScreen::clear(board.driver), Ok(()));
+    board.deferred_call();
+    assert_eq!(board.matrix.state(), 0);
+    // The pending alarm finds no text and is not set again
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), 0);
+    assert!(!board.alarm.is_armed());
+}
+
+#[test]
+fn requests_are_rejected_while_another_one_is_in_progress() {
+    let board = Board::new(10, 1);
+
+    assert_eq!(TextScreen::display_on(board.driver), Ok(()));
+    assert_eq!(TextScreen::display_off(board.driver), Err(ErrorCode::BUSY));
+    let buffer: &'static mut [u8] = Box::leak(Box::new([b'A']));
+    match TextScreen::print(board.driver, buffer, 1) {
+        Err((error, _)) => assert_eq!(error, ErrorCode::BUSY),
+        Ok(()) => panic!("print accepted while busy"),
+    }
+
+    board.deferred_call();
+    assert_eq!(TextScreen::display_off(board.driver), Ok(()));
+}
+
+#[test]
+fn command_1_sets_the_speed() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+    board.display_on();
+
+    assert!(board
+        .driver
+        .command(1, 100, 0, kernel.process_id(0))
+        .is_success());
+    board.print("AB");
+    assert_eq!(board.alarm.remaining_ms(), Some(100));
+}
+
+#[test]
+fn command_2_scrolls_the_text_one_column_at_a_time() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+    board.display_on();
+
+    assert!(board
+        .driver
+        .command(2, 1, GLYPH_WIDTH, kernel.process_id(0))
+        .is_success());
+    board.print("A");
+    assert_eq!(board.matrix.state(), glyph('A'));
+
+    // Each alarm moves the glyph one column to the left
+    let a = Font5x5.glyph('A').unwrap();
+    for shift in 1..=GLYPH_WIDTH {
+        assert!(board.alarm.fire());
+        let mut expected = 0;
+        for y in 0..GLYPH_HEIGHT {
+            let row = ((a.row(y) as u32) << shift) & 0b11111;
+            expected = (expected << GLYPH_WIDTH) | row;
+        }
+        assert_eq!(board.matrix.state(), expected, "shift {}", shift);
+    }
+}
+
+#[test]
+fn command_3_turns_the_leds_off_between_characters() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+    board.display_on();
+
+    assert!(board
+        .driver
+        .command(3, 50, 0, kernel.process_id(0))
+        .is_success());
+    board.print("LL");
+    assert_eq!(board.matrix.state(), glyph('L'));
+    assert_eq!(board.alarm.remaining_ms(), Some(SPEED));
+
+    // The gap between the two letters
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), 0);
+    assert_eq!(board.alarm.remaining_ms(), Some(50));
+
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('L'));
+    assert_eq!(board.alarm.remaining_ms(), Some(SPEED));
+}
+
+#[test]
+fn command_2_rejects_unknown_modes() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+
+    let ret = board.driver.command(2, 2, 0, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+}
+
+#[test]
+fn command_6_rotates_and_mirrors_the_text() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+    board.display_on();
+
+    assert!(board
+        .driver
+        .command(6, 90, 1, kernel.process_id(0))
+        .is_success());
+    board.print("LF");
+    let transform = Transform {
+        rotation: Rotation::Deg90,
+        mirror: true,
+    };
+    assert_eq!(board.matrix.state(), transform.apply(glyph('L')));
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), transform.apply(glyph('F')));
+}
+
+#[test]
+fn command_6_rejects_other_angles() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+
+    let ret = board.driver.command(6, 45, 0, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+    let ret = board.driver.command(6, 90, 2, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+}
+
+#[test]
+fn command_2_rejects_gaps_longer_than_the_screen() {
+    let board = Board::new(10, 1);
+    let kernel = MockKernel::new();
+
+    let ret = board
+        .driver
+        .command(2, 1, usize::MAX - 4, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+    let ret = board
+        .driver
+        .command(2, 1, 10 * (GLYPH_WIDTH + 1) + 1, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+    assert!(board
+        .driver
+        .command(2, 1, 10 * (GLYPH_WIDTH + 1), kernel.process_id(0))
+        .is_success());
+}
@@ -0,0 +1,73 @@
+use drivers::matrix_transform::{Rotation, Transform};
+
+/// Returns the bitmap that lights only the LED `index`
+fn pixel(index: usize) -> u32 {
+    1 << (24 - index)
+}
+
+fn transform(rotation: Rotation, mirror: bool) -> Transform {
+    Transform { rotation, mirror }
+}
+
+#[test]
+fn from_degrees_accepts_only_right_angles() {
+    assert_eq!(Rotation::from_degrees(0), Some(Rotation::Deg0));
+    assert_eq!(Rotation::from_degrees(90), Some(Rotation::Deg90));
+    assert_eq!(Rotation::from_degrees(180), Some(Rotation::Deg180));
+    assert_eq!(Rotation::from_degrees(270), Some(Rotation::Deg270));
+    assert_eq!(Rotation::from_degrees(45), None);
+    assert_eq!(Rotation::from_degrees(360), None);
+}
+
+#[test]
+fn identity_keeps_the_leds() {
+    for index in 0..25 {
+        assert_eq!(Transform::IDENTITY.led(index), index);
+    }
+    assert_eq!(Transform::IDENTITY.apply(0x1abcdef), 0x1abcdef);
+}
+
+#[test]
+fn rotations_are_clockwise() {
+    // The upper left LED moves around the corners.
+    assert_eq!(transform(Rotation::Deg90, false).led(0), 4);
+    assert_eq!(transform(Rotation::Deg180, false).led(0), 24);
+    assert_eq!(transform(Rotation::Deg270, false).led(0), 20);
+    // The center stays in place.
+    assert_eq!(transform(Rotation::Deg90, false).led(12), 12);
+    // The second LED of the first row moves to the second row
+    // of the last column.
+    assert_eq!(transform(Rotation::Deg90, false).led(1), 9);
+}
+
+#[test]
+fn mirroring_happens_before_rotating() {
+    assert_eq!(transform(Rotation::Deg0, true).led(0), 4);
+    assert_eq!(transform(Rotation::Deg0, true).led(7), 7);
+    assert_eq!(transform(Rotation::Deg90, true).led(0), 24);
+}
+
+#[test]
+fn apply_moves_the_bits() {
+    let bitmap = pixel(0) | pixel(1);
+    assert_eq!(
+        transform(Rotation::Deg90, false).apply(bitmap),
+        pixel(4) | pixel(9)
+    );
+    assert_eq!(
+        transform(Rotation::Deg180, false).apply(bitmap),
+        pixel(24) | pixel(23)
+    );
+    assert_eq!(
+        transform(Rotation::Deg0, true).apply(bitmap),
+        pixel(4) | pixel(3)
+    );
+}
+
+#[test]
+fn four_rotations_restore_the_image() {
+    let rotate = transform(Rotation::Deg90, false);
+    let bitmap = 0x1234567;
+    let rotated = (0..4).fold(bitmap, |bitmap, _| rotate.apply(bitmap));
+    assert_eq!(rotated, bitmap);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::{glyph, leak, MatrixScreen};
+use core::cell::Cell;
+use drivers::display::MatrixOrientation;
+use drivers::matrix_transform::{Rotation, Transform};
+use drivers::orientation_tracker::{upright_rotation, Axes, OrientationTracker};
+use kernel::hil::sensors::{NineDof, NineDofClient};
+use kernel::hil::time::Alarm;
+use mock_hil::{MockAccelerometer, MockAlarm};
+
+type Tracker = OrientationTracker<'static, MockAlarm<'static>>;
+
+/// The time in milliseconds between two readings
+const PERIOD_MS: u32 = 200;
+
+/// The axes of a sensor mounted like the matrix
+const AXES: Axes = Axes {
+    invert_x: false,
+    invert_y: false,
+};
+
+/// A client that records the last reading it received
+#[derive(Default)]
+struct RecordingClient {
+    reading: Cell<Option<(usize, usize, usize)>>,
+}
+
+impl NineDofClient for RecordingClient {
+    fn callback(&self, arg1: usize, arg2: usize, arg3: usize) {
+        self.reading.set(Some((arg1, arg2, arg3)));
+    }
+}
+
+/// The board's hardware, the screen and the tracker
+struct Board {
+    screen: MatrixScreen,
+    alarm: &'static MockAlarm<'static>,
+    sensor: &'static MockAccelerometer<'static>,
+    client: &'static RecordingClient,
+}
+
+impl Board {
+    fn new(axes: Axes) -> Board {
+        let screen = MatrixScreen::new(5, 2, 300, 1);
+
+        let alarm = leak(MockAlarm::default());
+        let sensor = leak(MockAccelerometer::default());
+        let displays: &'static [&'static dyn MatrixOrientation] = leak([screen.screen as _]);
+        let tracker: &'static Tracker = leak(OrientationTracker::new(
+            sensor, alarm, displays, axes, PERIOD_MS,
+        ));
+        let client = leak(RecordingClient::default());
+        alarm.set_alarm_client(tracker);
+        sensor.set_client(tracker);
+        tracker.set_client(client);
+        tracker.start();
+        Board {
+            screen,
+            alarm,
+            sensor,
+            client,
+        }
+    }
+
+    /// Holds the board with the acceleration `x`, `y` along the
+    /// matrix and lets the tracker read it
+    fn hold(&self, x: i32, y: i32) {
+        self.sensor.set_acceleration(x, y, 0);
+        assert!(self.alarm.advance(PERIOD_MS));
+        assert!(self.sensor.complete());
+    }
+}
+
+#[test]
+fn upright_rotation_follows_the_raised_edge() {
+    assert_eq!(upright_rotation(0, 1000), Some(Rotation::Deg0));
+    assert_eq!(upright_rotation(1000, 0), Some(Rotation::Deg90));
+    assert_eq!(upright_rotation(0, -1000), Some(Rotation::Deg180));
+    assert_eq!(upright_rotation(-1000, 0), Some(Rotation::Deg270));
+}
+
+#[test]
+fn upright_rotation_ignores_flat_and_diagonal_positions() {
+    assert_eq!(upright_rotation(100, -200), None);
+    assert_eq!(upright_rotation(700, 700), None);
+}
+
+#[test]
+fn tracker_reads_the_sensor_periodically() {
+    let board = Board::new(AXES);
+    assert_eq!(board.alarm.remaining_ms(), Some(PERIOD_MS));
+    board.hold(0, 1000);
+    assert_eq!(board.sensor.readings(), 1);
+    assert_eq!(board.alarm.remaining_ms(), Some(PERIOD_MS));
+    board.hold(0, 1000);
+    assert_eq!(board.sensor.readings(), 2);
+}
+
+#[test]
+fn tracker_rotates_the_text() {
+    let board = Board::new(AXES);
+    board.screen.display("HI");
+    assert_eq!(board.screen.matrix.state(), glyph('H'));
+
+    board.hold(1000, 0);
+    assert_eq!(board.screen.screen.transform().rotation, Rotation::Deg90);
+    assert!(board.screen.alarm.fire());
+    let rotated = Transform {
+        rotation: Rotation::Deg90,
+        mirror: false,
+    };
+    assert_eq!(board.screen.matrix.state(), rotated.apply(glyph('I')));
+}
+
+#[test]
+fn tracker_keeps_the_mirroring() {
+    let board = Board::new(AXES);
+    board.screen.screen.set_transform(Transform {
+        rotation: Rotation::Deg0,
+        mirror: true,
+    });
+    board.hold(0, -1000);
+    assert_eq!(
+        board.screen.screen.transform(),
+        Transform {
+            rotation: Rotation::Deg180,
+            mirror: true,
+        }
+    );
+}
+
+#[test]
+fn tracker_keeps_the_rotation_when_flat() {
+    let board = Board::new(AXES);
+    board.hold(-1000, 0);
+    board.hold(0, 0);
+    assert_eq!(board.screen.screen.transform().rotation, Rotation::Deg270);
+}
+
+#[test]
+fn tracker_inverts_the_axes() {
+    let board = Board::new(Axes {
+        invert_x: true,
+        invert_y: false,
+    });
+    board.hold(1000, 0);
+    assert_eq!(board.screen.screen.transform().rotation, Rotation::Deg270);
+}
+
+#[test]
+fn tracker_retries_when_the_sensor_is_busy() {
+    let board = Board::new(AXES);
+    assert_eq!(board.sensor.read_accelerometer(), Ok(()));
+    assert!(board.alarm.advance(PERIOD_MS));
+    assert_eq!(board.alarm.remaining_ms(), Some(PERIOD_MS));
+    assert_eq!(board.sensor.readings(), 1);
+}
+
+#[test]
+fn tracker_forwards_the_other_readings() {
+    let board = Board::new(AXES);
+    assert_eq!(board.sensor.read_accelerometer(), Ok(()));
+    board.sensor.set_acceleration(1000, -5, 3);
+    assert!(board.sensor.complete());
+    assert_eq!(
+        board.client.reading.get(),
+        Some((1000, -5isize as usize, 3))
+    );
+    // The reading was not the tracker's.
+    assert_eq!(board.screen.screen.transform(), Transform::IDENTITY);
+}
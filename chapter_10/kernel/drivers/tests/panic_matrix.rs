@@ -0,0 +1,84 @@
+use drivers::panic_matrix::{faulting_process, PanicMessage, MESSAGE_LEN, REASON_LEN};
+use kernel::process::{Process, State};
+
+#[test]
+fn message_has_the_file_name_and_line() {
+    let processes: [Option<&'static dyn Process>; 4] = [None; 4];
+    let location = core::panic::Location::caller();
+
+    let message = PanicMessage::new(Some(location), None, &processes);
+    let expected = format!("panic_matrix.rs:{}", location.line());
+    assert_eq!(message.as_bytes(), expected.as_bytes());
+}
+
+#[test]
+fn message_without_location_is_empty() {
+    let processes: [Option<&'static dyn Process>; 4] = [None; 4];
+
+    let message = PanicMessage::new(None, None, &processes);
+    assert!(message.as_bytes().is_empty());
+}
+
+#[test]
+fn message_ends_with_the_process_name() {
+    let location = core::panic::Location::caller();
+
+    let message = PanicMessage::with_process(Some(location), None, Some("blink"));
+    let expected = format!("panic_matrix.rs:{} blink", location.line());
+    assert_eq!(message.as_bytes(), expected.as_bytes());
+
+    let message = PanicMessage::with_process(None, None, Some("blink"));
+    assert_eq!(message.as_bytes(), b" blink");
+}
+
+#[test]
+fn long_messages_are_truncated() {
+    let name = "p".repeat(2 * MESSAGE_LEN);
+
+    let message = PanicMessage::with_process(None, None, Some(&name));
+    assert_eq!(message.as_bytes().len(), MESSAGE_LEN);
+    assert_eq!(message.as_bytes()[..2], *b" p");
+}
+
+#[test]
+fn message_ends_with_the_panic_reason() {
+    let location = core::panic::Location::caller();
+
+    let message = PanicMessage::with_process(
+        Some(location),
+        Some(&format_args!("index {} out of bounds", 7)),
+        Some("blink"),
+    );
+    let expected = format!(
+        "panic_matrix.rs:{} blink: index 7 out of bounds",
+        location.line()
+    );
+    assert_eq!(message.as_bytes(), expected.as_bytes());
+
+    let message = PanicMessage::with_process(None, Some(&format_args!("oops")), None);
+    assert_eq!(message.as_bytes(), b": oops");
+}
+
+#[test]
+fn long_panic_reasons_are_truncated() {
+    let reason = "r".repeat(2 * REASON_LEN);
+
+    let message = PanicMessage::with_process(None, Some(&format_args!("{}", reason)), None);
+    assert_eq!(message.as_bytes().len(), 2 + REASON_LEN);
+}
+
+#[test]
+fn faulted_process_is_preferred_to_the_running_one() {
+    let processes = [
+        (State::Yielded, "idle"),
+        (State::Running, "running"),
+        (State::Faulted, "faulted"),
+    ];
+
+    assert_eq!(faulting_process(processes.iter().copied()), Some("faulted"));
+    assert_eq!(
+        faulting_process(processes[..2].iter().copied()),
+        Some("running")
+    );
+    assert_eq!(faulting_process(processes[..1].iter().copied()), None);
+}
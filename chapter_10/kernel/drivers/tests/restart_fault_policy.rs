@@ -0,0 +1,163 @@
+mod common;
+
+use common::{buffer, leak};
+use drivers::restart_fault_policy::RestartFaultPolicy;
+use kernel::hil::text_screen::TextScreen;
+use kernel::hil::time::Alarm;
+use kernel::process::Process;
+use mock_hil::{MockAlarm, MockTextScreen};
+
+/// The number of processes of the board
+const NUM_PROCS: usize = 4;
+
+/// The delay before the first restart of a process, in milliseconds
+const DELAY_MS: u32 = 1000;
+
+/// The number of restarts before a process is stopped for good
+const MAX_RESTARTS: usize = 2;
+
+/// The time after which the faults of a process are forgotten, in milliseconds
+const RESET_MS: u32 = 5000;
+
+type Policy = RestartFaultPolicy<'static, MockAlarm<'static>, NUM_PROCS>;
+
+/// The board's alarm, screen and the policy
+///
+/// No process is loaded, the tests report the faults
+/// with the index of the process.
+struct Board {
+    alarm: &'static MockAlarm<'static>,
+    screen: &'static MockTextScreen<'static>,
+    policy: &'static Policy,
+}
+
+impl Board {
+    fn new() -> Board {
+        let processes: &'static [Option<&'static dyn Process>; NUM_PROCS] = leak([None; NUM_PROCS]);
+        let alarm = leak(MockAlarm::default());
+        let screen = leak(MockTextScreen::default());
+        let policy: &'static Policy = leak(RestartFaultPolicy::new(
+            processes,
+            alarm,
+            screen,
+            buffer(3),
+            DELAY_MS,
+            MAX_RESTARTS,
+            RESET_MS,
+        ));
+        alarm.set_alarm_client(policy);
+        TextScreen::set_client(screen, Some(policy));
+        Board {
+            alarm,
+            screen,
+            policy,
+        }
+    }
+
+    /// Completes the screen's requests until the policy is done
+    fn run(&self) {
+        while self.screen.complete() {}
+    }
+}
+
+#[test]
+fn fault_schedules_the_restart_and_shows_the_code() {
+    let board = Board::new();
+
+    board.policy.fault(1);
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS));
+    board.run();
+    assert_eq!(board.screen.text(), "F1");
+    assert!(board.screen.is_on());
+
+    // The message is hidden once the process is restarted.
+    assert!(board.alarm.fire());
+    board.run();
+    assert!(!board.screen.is_on());
+}
+
+#[test]
+fn delay_doubles_with_each_restart() {
+    let board = Board::new();
+
+    board.policy.fault(2);
+    assert!(board.alarm.fire());
+    // The alarm now waits for the faults to be forgotten.
+    assert_eq!(board.alarm.remaining_ms(), Some(RESET_MS));
+
+    board.policy.fault(2);
+    assert_eq!(board.alarm.remaining_ms(), Some(2 * DELAY_MS));
+}
+
+#[test]
+fn process_stays_stopped_after_the_last_restart() {
+    let board = Board::new();
+
+    for _ in 0..MAX_RESTARTS {
+        board.policy.fault(0);
+        assert!(board.alarm.fire());
+    }
+    board.policy.fault(0);
+    assert!(!board.alarm.is_armed());
+    board.run();
+    assert_eq!(board.screen.text(), "F0");
+    assert!(board.screen.is_on());
+}
+
+#[test]
+fn faults_are_forgotten_after_running_without_faulting() {
+    let board = Board::new();
+
+    board.policy.fault(3);
+    assert!(board.alarm.fire());
+    board.policy.fault(3);
+    assert!(board.alarm.fire());
+    assert!(board.alarm.advance(RESET_MS));
+    assert!(!board.alarm.is_armed());
+
+    // The process starts over with the first delay.
+    board.policy.fault(3);
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS));
+}
+
+#[test]
+fn delays_of_several_processes_are_counted_from_the_same_time() {
+    let board = Board::new();
+
+    board.policy.fault(0);
+    assert!(!board.alarm.advance(400));
+    board.policy.fault(1);
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS - 400));
+
+    // Process 0 is restarted, process 1 still waits.
+    assert!(board.alarm.fire());
+    assert_eq!(board.alarm.remaining_ms(), Some(400));
+    assert!(board.alarm.fire());
+    assert_eq!(board.alarm.remaining_ms(), Some(RESET_MS - 400));
+}
+
+#[test]
+fn faults_of_unknown_processes_are_ignored() {
+    let board = Board::new();
+
+    board.policy.fault(NUM_PROCS);
+    assert!(!board.alarm.is_armed());
+    assert!(!board.screen.complete());
+}
+
+#[test]
+#[should_panic(expected = "Expecting at most 100 processes, 101 supplied")]
+fn more_than_100_processes_are_rejected() {
+    let processes: &'static [Option<&'static dyn Process>; 101] = leak([None; 101]);
+    let alarm = leak(MockAlarm::default());
+    let screen = leak(MockTextScreen::default());
+    let _policy = RestartFaultPolicy::new(
+        processes,
+        alarm,
+        screen,
+        buffer(3),
+        DELAY_MS,
+        MAX_RESTARTS,
+        RESET_MS,
+    );
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{buffer, glyph, leak, MatrixScreen};
+use drivers::screen_message::ScreenMessage;
+use kernel::hil::text_screen::TextScreen;
+use kernel::hil::time::Alarm;
+use kernel::ErrorCode;
+use mock_hil::MockAlarm;
+
+type Message = ScreenMessage<'static, MockAlarm<'static>>;
+
+/// How long the message is displayed, in milliseconds
+const DURATION_MS: u32 = 10000;
+
+/// The board's hardware, the screen and the message
+struct Board {
+    screen: MatrixScreen,
+    alarm: &'static MockAlarm<'static>,
+    message: &'static Message,
+}
+
+impl Board {
+    fn new() -> Board {
+        let screen = MatrixScreen::new(5, 1, 300, 1);
+        let alarm = leak(MockAlarm::default());
+        let message: &'static Message = leak(ScreenMessage::new(screen.screen, alarm, buffer(3)));
+        alarm.set_alarm_client(message);
+        TextScreen::set_client(screen.screen, Some(message));
+        Board {
+            screen,
+            alarm,
+            message,
+        }
+    }
+
+    /// Delivers the deferred callbacks until the screen is idle
+    fn deferred_calls(&self) {
+        for _ in 0..10 {
+            self.screen.call();
+        }
+    }
+}
+
+#[test]
+fn displays_the_message_for_its_duration() {
+    let board = Board::new();
+    assert_eq!(board.message.show("W", DURATION_MS), Ok(()));
+    board.deferred_calls();
+    assert_eq!(board.screen.matrix.state(), glyph('W'));
+    assert_eq!(board.alarm.remaining_ms(), Some(DURATION_MS));
+
+    assert!(board.alarm.advance(DURATION_MS));
+    board.deferred_calls();
+    assert_eq!(board.screen.matrix.state(), 0);
+}
+
+#[test]
+fn truncates_the_message_to_the_buffer() {
+    let board = Board::new();
+    assert_eq!(board.message.show("WDTX", DURATION_MS), Ok(()));
+    board.deferred_calls();
+    // Only the first 3 characters are displayed, then the text starts over.
+    assert_eq!(board.screen.matrix.state(), glyph('W'));
+    for character in "DTW".chars() {
+        assert!(board.screen.alarm.fire());
+        assert_eq!(board.screen.matrix.state(), glyph(character));
+    }
+}
+
+#[test]
+fn rejects_a_message_while_displaying() {
+    let board = Board::new();
+    assert_eq!(board.message.show("W", DURATION_MS), Ok(()));
+    assert_eq!(board.message.show("D", DURATION_MS), Err(ErrorCode::BUSY));
+    board.deferred_calls();
+    assert_eq!(board.message.show("D", DURATION_MS), Err(ErrorCode::BUSY));
+
+    assert!(board.alarm.advance(DURATION_MS));
+    board.deferred_calls();
+    assert_eq!(board.message.show("D", DURATION_MS), Ok(()));
+}
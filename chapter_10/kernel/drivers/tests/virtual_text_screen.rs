@@ -0,0 +1,179 @@
+mod common;
+
+use common::{buffer, glyph, leak, text, MatrixScreen, Screen};
+use drivers::virtual_text_screen::{MuxTextScreen, VirtualTextScreen};
+use kernel::dynamic_deferred_call::{DeferredCallHandle, DynamicDeferredCallClient};
+use kernel::hil::text_screen::TextScreen;
+use kernel::hil::time::Alarm;
+use mock_hil::{MockAlarm, MockMatrix, MockTextScreenClient};
+
+type Mux = MuxTextScreen<'static, MockAlarm<'static>>;
+type Virtual = VirtualTextScreen<'static, MockAlarm<'static>>;
+
+/// The size of the screen
+const COLUMNS: usize = 5;
+const ROWS: usize = 2;
+
+/// The time slice of the clients with the same priority, in milliseconds
+const SLICE_MS: u32 = 1000;
+
+/// The board's hardware, the screen and the mux
+struct Board {
+    matrix: &'static MockMatrix,
+    alarm: &'static MockAlarm<'static>,
+    screen: &'static Screen,
+    screen_handle: DeferredCallHandle,
+    mux: &'static Mux,
+    mux_handle: DeferredCallHandle,
+}
+
+impl Board {
+    fn new() -> Board {
+        let MatrixScreen {
+            matrix,
+            screen,
+            handle: screen_handle,
+            deferred_caller,
+            ..
+        } = MatrixScreen::new(COLUMNS, ROWS, 300, 2);
+
+        let mux_alarm = leak(MockAlarm::default());
+        let mux: &'static Mux = leak(MuxTextScreen::new(
+            screen,
+            mux_alarm,
+            buffer(COLUMNS * ROWS),
+            SLICE_MS,
+            deferred_caller,
+        ));
+        let mux_handle = deferred_caller.register(mux).unwrap();
+        mux.initialize_callback_handle(mux_handle);
+        mux_alarm.set_alarm_client(mux);
+        TextScreen::set_client(screen, Some(mux));
+        Board {
+            matrix,
+            alarm: mux_alarm,
+            screen,
+            screen_handle,
+            mux,
+            mux_handle,
+        }
+    }
+
+    /// Adds a client with `priority`
+    fn client(&self, priority: usize) -> (&'static Virtual, &'static MockTextScreenClient) {
+        let device: &'static Virtual = leak(VirtualTextScreen::new(
+            self.mux,
+            buffer(COLUMNS * ROWS),
+            priority,
+        ));
+        device.setup();
+        let client = leak(MockTextScreenClient::default());
+        TextScreen::set_client(device, Some(client));
+        (device, client)
+    }
+
+    /// Delivers the deferred callbacks until the screen and the mux are done
+    fn run(&self) {
+        for _ in 0..20 {
+            self.screen.call(self.screen_handle);
+            self.mux.call(self.mux_handle);
+        }
+    }
+}
+
+/// Prints `message` on the virtual screen and turns its display on
+fn show(board: &Board, device: &'static Virtual, message: &str) {
+    assert!(TextScreen::print(device, text(message), message.len()).is_ok());
+    board.run();
+    assert_eq!(TextScreen::display_on(device), Ok(()));
+    board.run();
+}
+
+#[test]
+fn requests_complete_on_every_virtual_screen() {
+    let board = Board::new();
+    let (device, client) = board.client(0);
+
+    assert_eq!(TextScreen::get_size(device), (COLUMNS, ROWS));
+    show(&board, device, "AB");
+    assert_eq!(client.writes(), 1);
+    assert_eq!(client.written_len(), 2);
+    assert_eq!(client.commands(), 1);
+    assert_eq!(board.matrix.state(), glyph('A'));
+}
+
+#[test]
+fn higher_priority_clients_take_the_screen() {
+    let board = Board::new();
+    let (low, _) = board.client(0);
+    let (high, _) = board.client(1);
+
+    show(&board, low, "A");
+    assert_eq!(board.matrix.state(), glyph('A'));
+
+    show(&board, high, "B");
+    assert_eq!(board.matrix.state(), glyph('B'));
+
+    // The low priority client does not take the screen back
+    // while the high priority one uses it.
+    assert!(TextScreen::clear(low).is_ok());
+    board.run();
+    assert_eq!(board.matrix.state(), glyph('B'));
+
+    show(&board, low, "C");
+    assert_eq!(TextScreen::display_off(high), Ok(()));
+    board.run();
+    assert_eq!(board.matrix.state(), glyph('C'));
+}
+
+#[test]
+fn clients_with_the_same_priority_take_turns() {
+    let board = Board::new();
+    let (first, _) = board.client(0);
+    let (second, _) = board.client(0);
+
+    show(&board, first, "A");
+    assert!(!board.alarm.is_armed());
+    show(&board, second, "B");
+    let owner = board.matrix.state();
+    assert_eq!(board.alarm.remaining_ms(), Some(SLICE_MS));
+
+    assert!(board.alarm.fire());
+    board.run();
+    let next = if owner == glyph('A') { 'B' } else { 'A' };
+    assert_eq!(board.matrix.state(), glyph(next));
+
+    assert!(board.alarm.fire());
+    board.run();
+    assert_eq!(board.matrix.state(), owner);
+}
+
+#[test]
+fn the_owner_keeps_its_slice_when_other_clients_change() {
+    let board = Board::new();
+    let (first, _) = board.client(1);
+    let (second, _) = board.client(1);
+    let (low, _) = board.client(0);
+    show(&board, first, "A");
+    show(&board, second, "B");
+    let owner = board.matrix.state();
+    assert!(!board.alarm.advance(SLICE_MS - 400));
+
+    // The owner does not change, so its slice does not restart.
+    show(&board, low, "C");
+    assert_eq!(TextScreen::display_off(low), Ok(()));
+    board.run();
+    let (third, _) = board.client(1);
+    show(&board, third, "D");
+    assert_eq!(board.matrix.state(), owner);
+    assert_eq!(board.alarm.remaining_ms(), Some(400));
+
+    // Once the owner is the only client left, the slice stops.
+    let waiting = if owner == glyph('A') { second } else { first };
+    for device in [third, waiting].iter() {
+        assert_eq!(TextScreen::display_off(*device), Ok(()));
+        board.run();
+    }
+    assert_eq!(board.matrix.state(), owner);
+    assert!(!board.alarm.is_armed());
+}
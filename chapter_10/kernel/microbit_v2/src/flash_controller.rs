@@ -0,0 +1,114 @@
+use kernel::utilities::registers::interfaces::{Readable, Writeable};
+use kernel::utilities::registers::{register_bitfields, register_structs, ReadOnly, ReadWrite};
+use kernel::utilities::StaticRef;
+use tickv::error_codes::ErrorCode;
+use tickv::flash_controller::FlashController;
+
+register_structs! {
+    NvmcRegisters {
+        (0x000 => _reserved0),
+        (0x400 => ready: ReadOnly<u32, Ready::Register>),
+        (0x404 => _reserved1),
+        (0x504 => config: ReadWrite<u32, Config::Register>),
+        (0x508 => erasepage: ReadWrite<u32>),
+        (0x50C => @END),
+    }
+}
+
+register_bitfields! [u32,
+    Ready [
+        READY OFFSET(0) NUMBITS(1) []
+    ],
+    Config [
+        WEN OFFSET(0) NUMBITS(2) [
+            Ren = 0,
+            Wen = 1,
+            Een = 2
+        ]
+    ]
+];
+
+const NVMC_BASE: StaticRef<NvmcRegisters> =
+    unsafe { StaticRef::new(0x4001_E000 as *const NvmcRegisters) };
+
+/// The size of a flash page, the smallest area that can be erased
+pub const PAGE_SIZE: usize = 4096;
+
+/// The nRF52833 flash, used by the boot message's key-value store
+///
+/// The NVMC writes and erases the flash synchronously, the CPU
+/// is halted until each write or erase is done.
+pub struct NvmcFlash {
+    registers: StaticRef<NvmcRegisters>,
+    /// The address of the key-value region
+    start: usize,
+}
+
+impl NvmcFlash {
+    /// Initializes a new flash structure for the key-value
+    /// region that starts at the page address `start`
+    pub const fn new(start: usize) -> NvmcFlash {
+        NvmcFlash {
+            registers: NVMC_BASE,
+            start,
+        }
+    }
+
+    /// Waits for the NVMC to finish the current write or erase
+    fn wait_ready(&self) {
+        while !self.registers.ready.is_set(Ready::READY) {}
+    }
+}
+
+impl FlashController<PAGE_SIZE> for NvmcFlash {
+    fn read_region(
+        &self,
+        region_number: usize,
+        offset: usize,
+        buf: &mut [u8; PAGE_SIZE],
+    ) -> Result<(), ErrorCode> {
+        let address = self.start + region_number * PAGE_SIZE + offset;
+        for (index, byte) in buf.iter_mut().enumerate() {
+            // The flash is memory mapped.
+            *byte = unsafe { core::ptr::read_volatile((address + index) as *const u8) };
+        }
+        Ok(())
+    }
+
+    fn write(&self, address: usize, buf: &[u8]) -> Result<(), ErrorCode> {
+        let start = self.start + address;
+        let end = start + buf.len();
+        self.registers.config.write(Config::WEN::Wen);
+        // The flash is written one aligned word at a time, the bytes
+        // of the word that are not part of `buf` are written again
+        // with their current value, which leaves them unchanged.
+        let mut word_address = start & !0x3;
+        while word_address < end {
+            let mut word =
+                unsafe { core::ptr::read_volatile(word_address as *const u32) }.to_le_bytes();
+            for (index, byte) in word.iter_mut().enumerate() {
+                let byte_address = word_address + index;
+                if byte_address >= start && byte_address < end {
+                    *byte = buf[byte_address - start];
+                }
+            }
+            unsafe {
+                core::ptr::write_volatile(word_address as *mut u32, u32::from_le_bytes(word))
+            };
+            self.wait_ready();
+            word_address = word_address + 4;
+        }
+        self.registers.config.write(Config::WEN::Ren);
+        Ok(())
+    }
+
+    fn erase_region(&self, region_number: usize) -> Result<(), ErrorCode> {
+        self.registers.config.write(Config::WEN::Een);
+        self.registers
+            .erasepage
+            .set((self.start + region_number * PAGE_SIZE) as u32);
+        self.wait_ready();
+        self.registers.config.write(Config::WEN::Ren);
+        Ok(())
+    }
+}
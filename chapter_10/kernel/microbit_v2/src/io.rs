@@ -4,11 +4,13 @@ use core::panic::PanicInfo;
 use cortexm4;
 use kernel::debug;
 use kernel::debug::IoWrite;
-use kernel::hil::led;
+use kernel::hil::led::{self, Led};
 use kernel::hil::uart;
+use kernel::platform::watchdog::WatchDog;
 use nrf52833::gpio::{self, Pin};
 
-use kernel::hil::gpio::{Configure, Input, Output};
+use drivers::panic_matrix::{PanicMatrix, PanicMessage};
+use kernel::hil::gpio::{ActivationMode, Configure, Input, Output};
 
 use crate::CHIP;
 use crate::PROCESSES;
@@ -86,27 +88,51 @@ impl led::Led for MatrixLed {
     }
 }
 
-/// Default panic handler for the microbit board.
+/// The number of *nop* loop iterations that a row of the
+/// matrix stays lit while panicking
+const PANIC_ROW_DELAY: usize = 8000;
+
+/// The number of matrix refreshes that a column of the
+/// panic message is displayed for
+const PANIC_COLUMN_REFRESHES: usize = 40;
+
+/// Panic handler for the microbit board.
 ///
-/// We just use the standard default provided by the debug module in the kernel.
+/// We print the panic information to the serial port using the debug
+/// module in the kernel. As there is usually no serial cable in the field,
+/// we then scroll the panic location and the faulting process name on the
+/// LED matrix forever.
 #[cfg(not(test))]
 #[no_mangle]
 #[panic_handler]
 pub unsafe extern "C" fn panic_fmt(pi: &PanicInfo) -> ! {
-    // MicroBit v2 has an LED matrix, use the upper left LED
-    // let mut led = Led (&gpio::PORT[Pin::P0_28], );
-
-    // MicroBit v2 has a microphone LED, use it for panic
+    // The watchdog cannot be stopped, keep tickling it so that
+    // it does not reset the board while the panic is displayed
+    let watchdog = crate::watchdog::Wdt::new(crate::WATCHDOG_TIMEOUT_MS);
+    watchdog.tickle();
+    // MicroBit v2 has a microphone LED, turn it on to signal the panic
     let led_kernel_pin = &nrf52833::gpio::GPIOPin::new(Pin::P0_20);
     let led = &mut led::LedLow::new(led_kernel_pin);
-    // MatrixLed(&gpio::PORT[Pin::P0_28], &gpio::PORT[Pin::P0_21]);
+    led.init();
+    led.on();
     let writer = &mut WRITER;
-    debug::panic(
-        &mut [led],
-        writer,
-        pi,
-        &cortexm4::support::nop,
-        &PROCESSES,
-        &CHIP,
-    )
+    debug::panic_print(writer, pi, &cortexm4::support::nop, &PROCESSES, &CHIP);
+    watchdog.tickle();
+
+    // Drive the LED matrix GPIOs directly, as interrupts are not available
+    let cols = crate::LED_MATRIX_COLS.map(gpio::GPIOPin::new);
+    let rows = crate::LED_MATRIX_ROWS.map(gpio::GPIOPin::new);
+    let matrix = PanicMatrix::new(
+        [&cols[0], &cols[1], &cols[2], &cols[3], &cols[4]],
+        ActivationMode::ActiveLow,
+        [&rows[0], &rows[1], &rows[2], &rows[3], &rows[4]],
+        ActivationMode::ActiveHigh,
+    );
+    let message = PanicMessage::new(pi.location(), pi.message(), &PROCESSES);
+    matrix.scroll_forever(message.as_bytes(), PANIC_COLUMN_REFRESHES, &|| {
+        for _ in 0..PANIC_ROW_DELAY {
+            cortexm4::support::nop();
+        }
+        watchdog.tickle();
+    })
 }
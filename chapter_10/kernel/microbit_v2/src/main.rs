@@ -7,12 +7,15 @@
 // https://github.com/rust-lang/rust/issues/62184.
 #![cfg_attr(not(doc), no_main)]
 #![deny(missing_docs)]
+#![feature(panic_info_message)]
 
 use capsules::led_matrix::LedMatrixLed;
 use kernel::capabilities;
 use kernel::component::Component;
 use kernel::dynamic_deferred_call::{DynamicDeferredCall, DynamicDeferredCallClientState};
+use kernel::hil::text_screen::TextScreen;
 use kernel::hil::time::Counter;
+use kernel::hil::uart::{Receive, Transmit};
 use kernel::platform::{KernelResources, SyscallDriverLookup};
 use kernel::scheduler::round_robin::RoundRobinSched;
 
@@ -31,6 +34,38 @@ const BUTTON_A: Pin = Pin::P0_14;
 const BUTTON_B: Pin = Pin::P0_23;
 const TOUCH_LOGO: Pin = Pin::P1_04;
 
+// The speeds, in milliseconds between two characters, that the
+// buttons set when they control the LedMatrixText driver.
+const MATRIX_BUTTON_SPEEDS: drivers::button_controls::SpeedRange =
+    drivers::button_controls::SpeedRange {
+        step_ms: 100,
+        min_ms: 100,
+        max_ms: 2000,
+    };
+
+// When true, the accelerometer rotates the LedMatrixText and the
+// GrayscaleDisplay drivers so that the image stays upright.
+const MATRIX_AUTO_ROTATE: bool = false;
+
+// The time in milliseconds between two readings of the accelerometer
+// when MATRIX_AUTO_ROTATE is true.
+const MATRIX_AUTO_ROTATE_PERIOD_MS: u32 = 500;
+
+// The directions of the LSM303AGR's axes, which point to the left
+// and to the bottom of the LED matrix. Change them if the image
+// turns the wrong way.
+const MATRIX_AUTO_ROTATE_AXES: drivers::orientation_tracker::Axes =
+    drivers::orientation_tracker::Axes {
+        invert_x: true,
+        invert_y: true,
+    };
+
+// The time in milliseconds between two ambient light samples.
+const AMBIENT_LIGHT_SAMPLE_MS: u32 = 1000;
+
+// The brightness of the LED matrix in complete darkness, about 10%.
+const AMBIENT_LIGHT_MIN_BRIGHTNESS: u8 = 26;
+
 // GPIOs
 
 // P0, P1 and P2 are used as ADC, comment them in the ADC section to use them as GPIO
@@ -59,14 +94,62 @@ const I2C_SCL_PIN: Pin = Pin::P0_08;
 /// UART Writer for panic!()s.
 pub mod io;
 
-// State for loading and holding applications.
+/// The hardware watchdog.
+pub mod watchdog;
+
+/// The flash of the boot message's key-value store.
+pub mod flash_controller;
+
 // How should the kernel respond when a process faults.
-const FAULT_RESPONSE: kernel::process::PanicFaultPolicy = kernel::process::PanicFaultPolicy {};
+// A faulted process is restarted after FAULT_RESTART_DELAY_MS, the delay
+// doubles with each restart, up to FAULT_MAX_RESTARTS restarts. A process
+// that runs for FAULT_RESET_MS without faulting starts over with the first delay.
+const FAULT_RESTART_DELAY_MS: u32 = 1000;
+const FAULT_MAX_RESTARTS: usize = 5;
+const FAULT_RESET_MS: u32 = 60000;
+
+// The kernel loop has to tickle the watchdog at least once every
+// WATCHDOG_TIMEOUT_MS, otherwise the watchdog resets the board.
+const WATCHDOG_TIMEOUT_MS: u32 = 2000;
+
+// How long the board displays that the watchdog has reset it.
+const RESET_MESSAGE_MS: u32 = 10000;
 
+// The flash area that stores the boot message of the LED matrix,
+// the last 16 KB of the flash, after the applications (see layout.ld).
+const BOOT_MESSAGE_FLASH_START: usize = 0x7C000;
+const BOOT_MESSAGE_FLASH_SIZE: usize = 0x4000;
+
+// Saving the boot message writes and erases the flash, processes may
+// save it at most once every BOOT_MESSAGE_SAVE_INTERVAL_MS.
+const BOOT_MESSAGE_SAVE_INTERVAL_MS: u32 = 10000;
+
+// State for loading and holding applications.
 // Number of concurrent processes this platform supports.
 const NUM_PROCS: usize = 4;
 const NUM_UPCALLS_IPC: usize = NUM_PROCS + 1;
 
+// The drivers that only some applications may use, each entry allows
+// an application to use a range of commands of a driver.
+const SYSCALL_PERMISSIONS: [drivers::syscall_filter::Permission; 3] = [
+    // Only the example application drives the display.
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::led_matrix_text::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::grayscale_display::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::ambient_light::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+];
+
 static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PROCS] =
     [None; NUM_PROCS];
 
@@ -79,6 +162,16 @@ pub static mut STACK_MEMORY: [u8; 0x1000] = [0; 0x1000];
 // debug mode requires more stack space
 // pub static mut STACK_MEMORY: [u8; 0x2000] = [0; 0x2000];
 
+// The alarm of the LED matrix refresh, the AmbientLight driver
+// dims the rows that the refresh displays and samples the light
+// between two frames.
+type LedMatrixAlarm = drivers::ambient_light::AmbientLight<
+    'static,
+    nrf52::gpio::GPIOPin<'static>,
+    capsules::virtual_adc::AdcDevice<'static, nrf52833::adc::Adc>,
+    capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+>;
+
 /// Supported drivers by the platform
 pub struct MicroBit {
     ble_radio: &'static capsules::ble_advertising_driver::BLE<
@@ -91,7 +184,7 @@ pub struct MicroBit {
     led: &'static capsules::led_matrix::LedMatrixDriver<
         'static,
         nrf52::gpio::GPIOPin<'static>,
-        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        LedMatrixAlarm,
     >,
     button: &'static capsules::button::Button<'static, nrf52::gpio::GPIOPin<'static>>,
     rng: &'static capsules::rng::RngDriver<'static>,
@@ -111,7 +204,10 @@ pub struct MicroBit {
     app_flash: &'static capsules::app_flash_driver::AppFlash<'static>,
     sound_pressure: &'static capsules::sound_pressure::SoundPressureSensor<'static>,
 
+    syscall_filter: &'static drivers::syscall_filter::AllowlistSyscallFilter,
+
     scheduler: &'static RoundRobinSched<'static>,
+    watchdog: &'static watchdog::Wdt,
     systick: cortexm4::systick::SysTick,
 
     /// Add Tock's `TextScreen` driver to the board implementation structure.
@@ -119,13 +215,17 @@ pub struct MicroBit {
     /// Add the `LedMatrixText` driver to the board implementation structure.
     led_matrix_text: &'static drivers::led_matrix_text::LedMatrixText<
         'static,
-        LedMatrixLed<
-            'static,
-            nrf52::gpio::GPIOPin<'static>,
-            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
-        >,
+        LedMatrixLed<'static, nrf52::gpio::GPIOPin<'static>, LedMatrixAlarm>,
         capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc<'static>>,
     >,
+    /// Add the `GrayscaleDisplay` driver to the board implementation structure.
+    grayscale_display: &'static drivers::grayscale_display::GrayscaleDisplay<
+        'static,
+        LedMatrixLed<'static, nrf52::gpio::GPIOPin<'static>, LedMatrixAlarm>,
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+    >,
+    /// Add the `AmbientLight` driver to the board implementation structure.
+    ambient_light: &'static LedMatrixAlarm,
 }
 
 impl SyscallDriverLookup for MicroBit {
@@ -152,6 +252,10 @@ impl SyscallDriverLookup for MicroBit {
             capsules::text_screen::DRIVER_NUM => f(Some(self.text_screen)),
             // Register the `LedMatrixText` driver with the kernel.
             drivers::led_matrix_text::DRIVER_NUM => f(Some(self.led_matrix_text)),
+            // Register the `GrayscaleDisplay` driver with the kernel.
+            drivers::grayscale_display::DRIVER_NUM => f(Some(self.grayscale_display)),
+            // Register the `AmbientLight` driver with the kernel.
+            drivers::ambient_light::DRIVER_NUM => f(Some(self.ambient_light)),
             kernel::ipc::DRIVER_NUM => f(Some(&self.ipc)),
             _ => f(None),
         }
@@ -162,17 +266,17 @@ impl KernelResources<nrf52833::chip::NRF52<'static, Nrf52833DefaultPeripherals<'
     for MicroBit
 {
     type SyscallDriverLookup = Self;
-    type SyscallFilter = ();
+    type SyscallFilter = drivers::syscall_filter::AllowlistSyscallFilter;
     type ProcessFault = ();
     type Scheduler = RoundRobinSched<'static>;
     type SchedulerTimer = cortexm4::systick::SysTick;
-    type WatchDog = ();
+    type WatchDog = watchdog::Wdt;
 
     fn syscall_driver_lookup(&self) -> &Self::SyscallDriverLookup {
         &self
     }
     fn syscall_filter(&self) -> &Self::SyscallFilter {
-        &()
+        self.syscall_filter
     }
     fn process_fault(&self) -> &Self::ProcessFault {
         &()
@@ -184,7 +288,7 @@ impl KernelResources<nrf52833::chip::NRF52<'static, Nrf52833DefaultPeripherals<'
         &self.systick
     }
     fn watchdog(&self) -> &Self::WatchDog {
-        &()
+        self.watchdog
     }
 }
 
@@ -207,6 +311,9 @@ unsafe fn get_peripherals() -> &'static mut Nrf52833DefaultPeripherals<'static>
 pub unsafe fn main() {
     nrf52833::init();
 
+    // Read the reset cause before anything else can reset the board.
+    let reset_cause = watchdog::reset_cause();
+
     let nrf52833_peripherals = get_peripherals();
 
     // set up circular peripheral dependencies
@@ -263,27 +370,28 @@ pub unsafe fn main() {
     //--------------------------------------------------------------------------
     // Buttons
     //--------------------------------------------------------------------------
+    let button_pins = components::button_component_helper!(
+        nrf52833::gpio::GPIOPin,
+        (
+            &nrf52833_peripherals.gpio_port[BUTTON_A],
+            kernel::hil::gpio::ActivationMode::ActiveLow,
+            kernel::hil::gpio::FloatingState::PullNone
+        ), // A
+        (
+            &nrf52833_peripherals.gpio_port[BUTTON_B],
+            kernel::hil::gpio::ActivationMode::ActiveLow,
+            kernel::hil::gpio::FloatingState::PullNone
+        ), // B
+        (
+            &nrf52833_peripherals.gpio_port[TOUCH_LOGO],
+            kernel::hil::gpio::ActivationMode::ActiveLow,
+            kernel::hil::gpio::FloatingState::PullNone
+        ), // Touch Logo
+    );
     let button = components::button::ButtonComponent::new(
         board_kernel,
         capsules::button::DRIVER_NUM,
-        components::button_component_helper!(
-            nrf52833::gpio::GPIOPin,
-            (
-                &nrf52833_peripherals.gpio_port[BUTTON_A],
-                kernel::hil::gpio::ActivationMode::ActiveLow,
-                kernel::hil::gpio::FloatingState::PullNone
-            ), // A
-            (
-                &nrf52833_peripherals.gpio_port[BUTTON_B],
-                kernel::hil::gpio::ActivationMode::ActiveLow,
-                kernel::hil::gpio::FloatingState::PullNone
-            ), // B
-            (
-                &nrf52833_peripherals.gpio_port[TOUCH_LOGO],
-                kernel::hil::gpio::ActivationMode::ActiveLow,
-                kernel::hil::gpio::FloatingState::PullNone
-            ), // Touch Logo
-        ),
+        button_pins,
     )
     .finalize(components::button_component_buf!(nrf52833::gpio::GPIOPin));
 
@@ -292,7 +400,7 @@ pub unsafe fn main() {
     //--------------------------------------------------------------------------
 
     let dynamic_deferred_call_clients =
-        static_init!([DynamicDeferredCallClientState; 4], Default::default());
+        static_init!([DynamicDeferredCallClientState; 5], Default::default());
     let dynamic_deferred_caller = static_init!(
         DynamicDeferredCall,
         DynamicDeferredCall::new(dynamic_deferred_call_clients)
@@ -384,6 +492,8 @@ pub unsafe fn main() {
     // Create the debugger object that handles calls to `debug!()`.
     components::debug_writer::DebugWriterComponent::new(uart_mux).finalize(());
 
+    debug!("Reset cause: {}", reset_cause);
+
     //--------------------------------------------------------------------------
     // RANDOM NUMBERS
     //--------------------------------------------------------------------------
@@ -550,37 +660,107 @@ pub unsafe fn main() {
     // LED Matrix
     //--------------------------------------------------------------------------
 
-    let led = components::led_matrix_component_helper!(
-        nrf52833::gpio::GPIOPin,
-        nrf52::rtc::Rtc<'static>,
-        mux_alarm,
-        @fps => 60,
-        @cols => kernel::hil::gpio::ActivationMode::ActiveLow,
+    let led_matrix_cols = static_init!(
+        [&'static nrf52::gpio::GPIOPin<'static>; 5],
+        [
             &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[0]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[1]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[2]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[3]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[4]],
-        @rows => kernel::hil::gpio::ActivationMode::ActiveHigh,
+        ]
+    );
+    let led_matrix_rows = static_init!(
+        [&'static nrf52::gpio::GPIOPin<'static>; 5],
+        [
             &nrf52833_peripherals.gpio_port[LED_MATRIX_ROWS[0]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_ROWS[1]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_ROWS[2]],
             &nrf52833_peripherals.gpio_port[LED_MATRIX_ROWS[3]],
-            &nrf52833_peripherals.gpio_port[LED_MATRIX_ROWS[4]]
+            &nrf52833_peripherals.gpio_port[LED_MATRIX_ROWS[4]],
+        ]
+    );
 
-    )
-    .finalize(components::led_matrix_component_buf!(
-        nrf52833::gpio::GPIOPin,
-        nrf52::rtc::Rtc<'static>
-    ));
+    // Initialize the ADC channels of the LED matrix columns that
+    // are connected to analog inputs (columns 1, 3 and 5)
+    let ambient_light_channels = static_init!(
+        [&'static capsules::virtual_adc::AdcDevice<'static, nrf52833::adc::Adc>; 3],
+        [
+            components::adc::AdcComponent::new(
+                &adc_mux,
+                nrf52833::adc::AdcChannelSetup::new(nrf52833::adc::AdcChannel::AnalogInput4)
+            )
+            .finalize(components::adc_component_helper!(nrf52833::adc::Adc)),
+            components::adc::AdcComponent::new(
+                &adc_mux,
+                nrf52833::adc::AdcChannelSetup::new(nrf52833::adc::AdcChannel::AnalogInput7)
+            )
+            .finalize(components::adc_component_helper!(nrf52833::adc::Adc)),
+            components::adc::AdcComponent::new(
+                &adc_mux,
+                nrf52833::adc::AdcChannelSetup::new(nrf52833::adc::AdcChannel::AnalogInput6)
+            )
+            .finalize(components::adc_component_helper!(nrf52833::adc::Adc)),
+        ]
+    );
+    let ambient_light_columns = static_init!(
+        [&'static nrf52::gpio::GPIOPin<'static>; 3],
+        [
+            &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[0]],
+            &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[2]],
+            &nrf52833_peripherals.gpio_port[LED_MATRIX_COLS[4]],
+        ]
+    );
 
-    //--------------------------------------------------------------------------
-    // Process Console
-    //--------------------------------------------------------------------------
-    let process_console =
-        components::process_console::ProcessConsoleComponent::new(board_kernel, uart_mux)
-            .finalize(());
-    let _ = process_console.start();
+    // Initialize a virtual alarm for the AmbientLight driver
+    let virtual_alarm_ambient_light = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize the AmbientLight driver, which measures the light with
+    // the LED matrix and sets its brightness
+    let ambient_light = static_init!(
+        LedMatrixAlarm,
+        drivers::ambient_light::AmbientLight::new(
+            led_matrix_rows,
+            ambient_light_columns,
+            ambient_light_channels,
+            virtual_alarm_ambient_light,
+            AMBIENT_LIGHT_SAMPLE_MS,
+            AMBIENT_LIGHT_MIN_BRIGHTNESS
+        )
+    );
+    virtual_alarm_ambient_light.set_alarm_client(ambient_light);
+    for channel in ambient_light_channels.iter() {
+        kernel::hil::adc::AdcChannel::set_client(*channel, ambient_light);
+    }
+
+    // The state of the LED matrix, one bit for each of the 25 LEDs
+    let led_matrix_state = static_init!([u8; 4], [0; 4]);
+
+    // The LED matrix driver uses the AmbientLight driver as its alarm,
+    // so that the rows are dimmed and the light is sampled in step with
+    // the refresh, whatever the drivers display.
+    let led = static_init!(
+        capsules::led_matrix::LedMatrixDriver<
+            'static,
+            nrf52::gpio::GPIOPin<'static>,
+            LedMatrixAlarm,
+        >,
+        capsules::led_matrix::LedMatrixDriver::new(
+            led_matrix_cols,
+            led_matrix_rows,
+            led_matrix_state,
+            ambient_light,
+            kernel::hil::gpio::ActivationMode::ActiveLow,
+            kernel::hil::gpio::ActivationMode::ActiveHigh,
+            60
+        )
+    );
+    ambient_light.set_alarm_client(led);
+    ambient_light.start();
+    led.init();
 
     //--------------------------------------------------------------------------
     // FINAL SETUP AND BOARD BOOT
@@ -610,11 +790,7 @@ pub unsafe fn main() {
             // 'a becomes 'static
             'static,
             // L: Led becomes LedMatrixLed<...>
-            LedMatrixLed<
-                'static,
-                nrf52::gpio::GPIOPin<'static>,
-                capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
-            >,
+            LedMatrixLed<'static, nrf52::gpio::GPIOPin<'static>, LedMatrixAlarm>,
             // A: Alarm becomes VirtualMuxAlarm<...>
             capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
         >,
@@ -626,7 +802,7 @@ pub unsafe fn main() {
         drivers::led_matrix_text::LedMatrixText::new(
             components::led_matrix_leds!(
                 nrf52::gpio::GPIOPin<'static>,
-                capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+                LedMatrixAlarm,
                 led,
                 (0, 0),
                 (1, 0),
@@ -657,6 +833,8 @@ pub unsafe fn main() {
             virtual_alarm_led_matrix_text,
             // Send the allocated buffer to the driver
             led_matrix_buffer,
+            // Split the buffer into a screen of 5 rows of 10 characters
+            10,
             // Set the default speed in ms
             300,
             // Set the kernel's deferred caller
@@ -677,15 +855,392 @@ pub unsafe fn main() {
             .expect("no deferred call slot available for led matrix text"),
     );
 
+    // Let the buttons control the LedMatrixText driver: button A slows the
+    // text down, button B speeds it up and the touch logo pauses or resumes
+    // it. The controls forward the interrupts to the Button driver, so
+    // processes still receive them. Pass true as the last argument of the
+    // constructor to enable the controls.
+    let button_controls = static_init!(
+        drivers::button_controls::ButtonControls<
+            'static,
+            nrf52::gpio::GPIOPin<'static>,
+            drivers::led_matrix_text::LedMatrixText<
+                'static,
+                LedMatrixLed<'static, nrf52::gpio::GPIOPin<'static>, LedMatrixAlarm>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc<'static>>,
+            >,
+            nrf52::rtc::Rtc<'static>,
+        >,
+        drivers::button_controls::ButtonControls::new(
+            &nrf52833_peripherals.gpio_port[BUTTON_A],
+            &nrf52833_peripherals.gpio_port[BUTTON_B],
+            &nrf52833_peripherals.gpio_port[TOUCH_LOGO],
+            kernel::hil::gpio::ActivationMode::ActiveLow,
+            led_matrix_text,
+            rtc,
+            MATRIX_BUTTON_SPEEDS,
+            false
+        )
+    );
+    // The controls sit between the pins and the Button driver,
+    // they forward the interrupts to the driver.
+    for (pin, _, _) in button_pins.iter() {
+        kernel::hil::gpio::InterruptWithValue::set_client(*pin, button_controls);
+    }
+    button_controls.set_client(button);
+    button_controls.setup();
+
+    // Initialize the key-value store of the boot message
+    let boot_message_read_buffer = static_init!(
+        [u8; flash_controller::PAGE_SIZE],
+        [0; flash_controller::PAGE_SIZE]
+    );
+    let boot_message_store = static_init!(
+        drivers::boot_message::TicKVStore<
+            'static,
+            flash_controller::NvmcFlash,
+            { flash_controller::PAGE_SIZE },
+        >,
+        drivers::boot_message::TicKVStore::new(
+            flash_controller::NvmcFlash::new(BOOT_MESSAGE_FLASH_START),
+            boot_message_read_buffer,
+            BOOT_MESSAGE_FLASH_SIZE
+        )
+    );
+
+    // Display the boot message, if one has been saved
+    match boot_message_store.initialise() {
+        Ok(()) => led_matrix_text
+            .set_boot_message_store(boot_message_store, BOOT_MESSAGE_SAVE_INTERVAL_MS),
+        Err(err) => debug!("Boot message store unavailable: {:?}", err),
+    }
+
+    // Initialize a virtual alarm for the text screen mux
+    let virtual_alarm_mux_text_screen = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize a 'static buffer of 50 for the text screen mux
+    let mux_text_screen_buffer = static_init!([u8; 50], [0; 50]);
+
+    // Initialize the mux that shares the LedMatrixText driver between
+    // several kernel clients. Clients with the same priority take
+    // turns every 5 seconds.
+    let mux_text_screen = static_init!(
+        drivers::virtual_text_screen::MuxTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::virtual_text_screen::MuxTextScreen::new(
+            led_matrix_text,
+            virtual_alarm_mux_text_screen,
+            mux_text_screen_buffer,
+            5000,
+            dynamic_deferred_caller
+        )
+    );
+
+    // The mux receives the LedMatrixText driver's callbacks
+    led_matrix_text.set_client(Some(mux_text_screen));
+    virtual_alarm_mux_text_screen.set_alarm_client(mux_text_screen);
+    mux_text_screen.initialize_callback_handle(
+        dynamic_deferred_caller
+            .register(mux_text_screen)
+            .expect("no deferred call slot available for text screen mux"),
+    );
+
+    // Initialize the virtual screen used by the TextScreen driver
+    let virtual_text_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_text_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_text_screen_buffer,
+            // Processes have the lowest priority
+            0
+        )
+    );
+    virtual_text_screen.setup();
+
+    // Initialize a virtual alarm for the fault policy
+    let virtual_alarm_fault_policy = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize the virtual screen that displays the process faults
+    let virtual_fault_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_fault_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_fault_screen_buffer,
+            // Faults are displayed over the processes' text
+            1
+        )
+    );
+    virtual_fault_screen.setup();
+
+    // Initialize the fault policy that restarts the faulted processes
+    let fault_policy_message = static_init!(
+        [u8; drivers::restart_fault_policy::MESSAGE_LEN],
+        [0; drivers::restart_fault_policy::MESSAGE_LEN]
+    );
+    let fault_policy = static_init!(
+        drivers::restart_fault_policy::RestartFaultPolicy<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+            NUM_PROCS,
+        >,
+        drivers::restart_fault_policy::RestartFaultPolicy::new(
+            &PROCESSES,
+            virtual_alarm_fault_policy,
+            virtual_fault_screen,
+            fault_policy_message,
+            FAULT_RESTART_DELAY_MS,
+            FAULT_MAX_RESTARTS,
+            FAULT_RESET_MS
+        )
+    );
+    virtual_alarm_fault_policy.set_alarm_client(fault_policy);
+    virtual_fault_screen.set_client(Some(fault_policy));
+
+    // Initialize a virtual alarm for the reset message
+    let virtual_alarm_reset_message = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize the virtual screen that displays the reset cause
+    let virtual_reset_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_reset_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_reset_screen_buffer,
+            // The reset cause is displayed over the faults
+            2
+        )
+    );
+    virtual_reset_screen.setup();
+
+    // Initialize the message that displays the reset cause
+    let reset_message_buffer = static_init!([u8; 10], [0; 10]);
+    let reset_message = static_init!(
+        drivers::screen_message::ScreenMessage<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::screen_message::ScreenMessage::new(
+            virtual_reset_screen,
+            virtual_alarm_reset_message,
+            reset_message_buffer
+        )
+    );
+    virtual_alarm_reset_message.set_alarm_client(reset_message);
+    virtual_reset_screen.set_client(Some(reset_message));
+
+    // Tell the user that the watchdog has reset the board
+    if reset_cause == watchdog::WATCHDOG_RESET {
+        let _ = reset_message.show("WDT", RESET_MESSAGE_MS);
+    }
+
+    // Initialize the virtual screen driven by the display console
+    let virtual_console_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_console_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_console_screen_buffer,
+            // The technician's text is displayed over everything else
+            3
+        )
+    );
+    virtual_console_screen.setup();
+
+    // Initialize the display console on its own device of the UART mux,
+    // so that the matrix can be driven over serial with no application
+    // loaded. The process console receives its lines from the display
+    // console and transmits them on a device of its own.
+    let display_console_uart = static_init!(
+        capsules::virtual_uart::UartDevice<'static>,
+        capsules::virtual_uart::UartDevice::new(uart_mux, true)
+    );
+    display_console_uart.setup();
+    let process_console_uart = static_init!(
+        capsules::virtual_uart::UartDevice<'static>,
+        capsules::virtual_uart::UartDevice::new(uart_mux, false)
+    );
+    process_console_uart.setup();
+    let display_console_tx_buffer = static_init!([u8; 32], [0; 32]);
+    let display_console_queue = static_init!([u8; 64], [0; 64]);
+    let display_console_rx_buffer = static_init!([u8; 1], [0; 1]);
+    let display_console_command = static_init!([u8; 64], [0; 64]);
+    let display_console_text = static_init!([u8; 50], [0; 50]);
+    let display_console = static_init!(
+        drivers::display_console::DisplayConsole<'static>,
+        drivers::display_console::DisplayConsole::new(
+            display_console_uart,
+            process_console_uart,
+            virtual_console_screen,
+            led_matrix_text,
+            display_console_tx_buffer,
+            display_console_queue,
+            display_console_rx_buffer,
+            display_console_command,
+            display_console_text
+        )
+    );
+    display_console_uart.set_transmit_client(display_console);
+    display_console_uart.set_receive_client(display_console);
+    virtual_console_screen.set_client(Some(display_console));
+    let _ = display_console.start();
+
+    // Initialize the process console, the display console
+    // forwards it the lines that are not display commands
+    let process_console = static_init!(
+        capsules::process_console::ProcessConsole<
+            'static,
+            components::process_console::Capability,
+        >,
+        capsules::process_console::ProcessConsole::new(
+            display_console,
+            &mut capsules::process_console::WRITE_BUF,
+            &mut capsules::process_console::READ_BUF,
+            &mut capsules::process_console::QUEUE_BUF,
+            &mut capsules::process_console::COMMAND_BUF,
+            board_kernel,
+            components::process_console::Capability
+        )
+    );
+    display_console.set_transmit_client(process_console);
+    display_console.set_receive_client(process_console);
+    let _ = process_console.start();
+
     // Initialize a new TextScreen driver...
     let text_screen = components::text_screen::TextScreenComponent::new(
         board_kernel,
         capsules::text_screen::DRIVER_NUM,
-        led_matrix_text,
+        virtual_text_screen,
     )
     // ... with a buffer of length 50.
     .finalize(components::screen_buffer_size!(50));
 
+    // Initialize a virtual alarm for the GrayscaleDisplay driver
+    let virtual_alarm_grayscale_display = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize a 'static framebuffer for the GrayscaleDisplay driver
+    let grayscale_framebuffer = static_init!(
+        [u8; drivers::grayscale_display::FRAMEBUFFER_LEN],
+        [0; drivers::grayscale_display::FRAMEBUFFER_LEN]
+    );
+
+    // Initialize the GrayscaleDisplay using the static_init! macro
+    // The LEDs are the ones of the LedMatrixText driver, the two
+    // drivers are not supposed to display at the same time.
+    let grayscale_display = static_init!(
+        drivers::grayscale_display::GrayscaleDisplay<
+            'static,
+            LedMatrixLed<'static, nrf52::gpio::GPIOPin<'static>, LedMatrixAlarm>,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::grayscale_display::GrayscaleDisplay::new(
+            components::led_matrix_leds!(
+                nrf52::gpio::GPIOPin<'static>,
+                LedMatrixAlarm,
+                led,
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (0, 3),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (4, 3),
+                (0, 4),
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4)
+            ),
+            virtual_alarm_grayscale_display,
+            grayscale_framebuffer,
+            board_kernel.create_grant(
+                drivers::grayscale_display::DRIVER_NUM,
+                &memory_allocation_capability
+            )
+        )
+    );
+
+    // Set the driver as the alarm's client.
+    virtual_alarm_grayscale_display.set_alarm_client(grayscale_display);
+
+    // Rotate the displays using the accelerometer, if enabled
+    if MATRIX_AUTO_ROTATE {
+        let virtual_alarm_orientation_tracker = static_init!(
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+            capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+        );
+        let rotated_displays = static_init!(
+            [&'static dyn drivers::display::MatrixOrientation; 2],
+            [led_matrix_text, grayscale_display]
+        );
+        let orientation_tracker = static_init!(
+            drivers::orientation_tracker::OrientationTracker<
+                'static,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+            >,
+            drivers::orientation_tracker::OrientationTracker::new(
+                lsm303agr,
+                virtual_alarm_orientation_tracker,
+                rotated_displays,
+                MATRIX_AUTO_ROTATE_AXES,
+                MATRIX_AUTO_ROTATE_PERIOD_MS
+            )
+        );
+        virtual_alarm_orientation_tracker.set_alarm_client(orientation_tracker);
+        // The tracker sits between the sensor and the NineDof driver,
+        // it forwards the processes' readings to the driver.
+        kernel::hil::sensors::NineDof::set_client(lsm303agr, orientation_tracker);
+        orientation_tracker.set_client(ninedof);
+        orientation_tracker.start();
+    }
+
+    // Only the applications allowed by the permissions may use the
+    // restricted drivers.
+    let syscall_filter = static_init!(
+        drivers::syscall_filter::AllowlistSyscallFilter,
+        drivers::syscall_filter::AllowlistSyscallFilter::new(&PROCESSES, &SYSCALL_PERMISSIONS)
+    );
+
     let scheduler = components::sched::round_robin::RoundRobinComponent::new(&PROCESSES)
         .finalize(components::rr_component_helper!(NUM_PROCS));
 
@@ -710,13 +1265,20 @@ pub unsafe fn main() {
             &memory_allocation_capability,
         ),
 
+        syscall_filter,
+
         scheduler,
+        watchdog: static_init!(watchdog::Wdt, watchdog::Wdt::new(WATCHDOG_TIMEOUT_MS)),
         systick: cortexm4::systick::SysTick::new_with_calibration(64000000),
 
         // Add the TextScreen driver to the boards implementation initialization.
         text_screen,
         // Add the LedMatrixText driver to the boards implementation initialization.
         led_matrix_text,
+        // Add the GrayscaleDisplay driver to the boards implementation initialization.
+        grayscale_display,
+        // Add the AmbientLight driver to the boards implementation initialization.
+        ambient_light,
     };
 
     let chip = static_init!(
@@ -755,7 +1317,7 @@ pub unsafe fn main() {
             &_eappmem as *const u8 as usize - &_sappmem as *const u8 as usize,
         ),
         &mut PROCESSES,
-        &FAULT_RESPONSE,
+        fault_policy,
         &process_management_capability,
     )
     .unwrap_or_else(|err| {
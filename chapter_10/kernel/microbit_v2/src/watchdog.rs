@@ -0,0 +1,125 @@
+use kernel::platform::watchdog::WatchDog;
+use kernel::utilities::registers::interfaces::{Readable, Writeable};
+use kernel::utilities::registers::{
+    register_bitfields, register_structs, ReadOnly, ReadWrite, WriteOnly,
+};
+use kernel::utilities::StaticRef;
+
+register_structs! {
+    WdtRegisters {
+        (0x000 => tasks_start: WriteOnly<u32>),
+        (0x004 => _reserved0),
+        (0x400 => runstatus: ReadOnly<u32, RunStatus::Register>),
+        (0x404 => _reserved1),
+        (0x504 => crv: ReadWrite<u32>),
+        (0x508 => rren: ReadWrite<u32>),
+        (0x50C => config: ReadWrite<u32, Config::Register>),
+        (0x510 => _reserved2),
+        (0x600 => rr: [WriteOnly<u32>; 8]),
+        (0x620 => @END),
+    }
+}
+
+register_bitfields! [u32,
+    RunStatus [
+        RUNNING OFFSET(0) NUMBITS(1) []
+    ],
+    Config [
+        SLEEP OFFSET(0) NUMBITS(1) [
+            Pause = 0,
+            Run = 1
+        ],
+        HALT OFFSET(3) NUMBITS(1) [
+            Pause = 0,
+            Run = 1
+        ]
+    ],
+    ResetReason [
+        RESETPIN OFFSET(0) NUMBITS(1) [],
+        DOG OFFSET(1) NUMBITS(1) [],
+        SREQ OFFSET(2) NUMBITS(1) [],
+        LOCKUP OFFSET(3) NUMBITS(1) [],
+        OFF OFFSET(16) NUMBITS(1) []
+    ]
+];
+
+const WDT_BASE: StaticRef<WdtRegisters> =
+    unsafe { StaticRef::new(0x4001_0000 as *const WdtRegisters) };
+
+/// The POWER peripheral's RESETREAS register
+const RESETREAS: StaticRef<ReadWrite<u32, ResetReason::Register>> =
+    unsafe { StaticRef::new(0x4000_0400 as *const ReadWrite<u32, ResetReason::Register>) };
+
+/// The value that reloads the watchdog's counter
+const RELOAD_VALUE: u32 = 0x6E52_4635;
+
+/// The frequency of the watchdog's counter
+const WDT_FREQUENCY_HZ: u32 = 32768;
+
+/// The reset cause reported after a watchdog reset
+pub const WATCHDOG_RESET: &str = "watchdog";
+
+/// Returns the cause of the last reset and clears it
+///
+/// The cause is recorded by the hardware, so this has to be called
+/// once, before anything else resets the chip.
+pub fn reset_cause() -> &'static str {
+    let reason = RESETREAS.extract();
+    // The bits are cleared by writing 1 to them.
+    RESETREAS.set(reason.get());
+    if reason.is_set(ResetReason::DOG) {
+        WATCHDOG_RESET
+    } else if reason.is_set(ResetReason::LOCKUP) {
+        "CPU lockup"
+    } else if reason.is_set(ResetReason::SREQ) {
+        "software"
+    } else if reason.is_set(ResetReason::RESETPIN) {
+        "reset pin"
+    } else if reason.is_set(ResetReason::OFF) {
+        "wake up"
+    } else {
+        "power on"
+    }
+}
+
+/// The nRF52833 watchdog timer
+///
+/// The watchdog resets the chip if the kernel loop does not tickle
+/// it for `timeout_ms` milliseconds. It is paused while the CPU sleeps
+/// and while a debugger halts it. Once started, it cannot be stopped,
+/// so the panic handler tickles it while it displays the panic.
+pub struct Wdt {
+    registers: StaticRef<WdtRegisters>,
+    timeout_ms: u32,
+}
+
+impl Wdt {
+    /// Initializes a new watchdog structure
+    pub const fn new(timeout_ms: u32) -> Wdt {
+        Wdt {
+            registers: WDT_BASE,
+            timeout_ms,
+        }
+    }
+}
+
+impl WatchDog for Wdt {
+    fn setup(&self) {
+        // The configuration cannot be changed while the watchdog runs.
+        if !self.registers.runstatus.is_set(RunStatus::RUNNING) {
+            let ticks = (self.timeout_ms as u64 * WDT_FREQUENCY_HZ as u64 / 1000) as u32;
+            // The minimum reload value is 0xF.
+            self.registers.crv.set(ticks.max(0xF));
+            // Only the reload register 0 is used.
+            self.registers.rren.set(1);
+            self.registers
+                .config
+                .write(Config::SLEEP::Pause + Config::HALT::Pause);
+            self.registers.tasks_start.set(1);
+        }
+    }
+
+    fn tickle(&self) {
+        self.registers.rr[0].set(RELOAD_VALUE);
+    }
+}
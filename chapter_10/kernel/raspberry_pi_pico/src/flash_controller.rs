@@ -0,0 +1,166 @@
+use crate::flash_bootloader::FLASH_BOOTLOADER;
+use tickv::error_codes::ErrorCode;
+use tickv::flash_controller::FlashController;
+
+/// The address at which the flash is mapped (XIP)
+const XIP_BASE: usize = 0x1000_0000;
+
+/// The size of a flash sector, the smallest area that can be erased
+pub const SECTOR_SIZE: usize = 4096;
+
+/// The size of a flash page, the smallest area that can be programmed
+const PAGE_SIZE: usize = 256;
+
+/// The erase command and block size used by the ROM's erase function
+/// for large areas, smaller areas are erased sector by sector
+const BLOCK_SIZE: u32 = 1 << 16;
+const BLOCK_ERASE_CMD: u8 = 0xd8;
+
+/// The ROM functions used to write and erase the flash
+/// (RP2040 Datasheet, 2.8.3 Bootrom Contents)
+struct RomFunctions {
+    connect_internal_flash: extern "C" fn(),
+    flash_exit_xip: extern "C" fn(),
+    flash_range_erase: extern "C" fn(u32, usize, u32, u8),
+    flash_range_program: extern "C" fn(u32, *const u8, usize),
+    flash_flush_cache: extern "C" fn(),
+    /// The second stage bootloader, copied to RAM, that restores
+    /// the fast XIP mode
+    boot2: extern "C" fn(),
+}
+
+/// The RAM copy of the second stage bootloader
+static mut BOOT2: [u32; 64] = [0; 64];
+
+/// Looks up a function of the ROM's function table by its code
+unsafe fn rom_function(code: &[u8; 2]) -> usize {
+    let table = *(0x0000_0014 as *const u16) as usize as *const u16;
+    let lookup: extern "C" fn(*const u16, u32) -> usize =
+        core::mem::transmute(*(0x0000_0018 as *const u16) as usize);
+    lookup(table, code[0] as u32 | (code[1] as u32) << 8)
+}
+
+impl RomFunctions {
+    unsafe fn new() -> RomFunctions {
+        for (index, word) in BOOT2.iter_mut().enumerate() {
+            *word = u32::from_le_bytes([
+                FLASH_BOOTLOADER[index * 4],
+                FLASH_BOOTLOADER[index * 4 + 1],
+                FLASH_BOOTLOADER[index * 4 + 2],
+                FLASH_BOOTLOADER[index * 4 + 3],
+            ]);
+        }
+        RomFunctions {
+            connect_internal_flash: core::mem::transmute(rom_function(b"IF")),
+            flash_exit_xip: core::mem::transmute(rom_function(b"EX")),
+            flash_range_erase: core::mem::transmute(rom_function(b"RE")),
+            flash_range_program: core::mem::transmute(rom_function(b"RP")),
+            flash_flush_cache: core::mem::transmute(rom_function(b"FC")),
+            // Thumb code, the lowest bit of the address has to be set.
+            boot2: core::mem::transmute(BOOT2.as_ptr() as usize + 1),
+        }
+    }
+}
+
+/// The operation performed with the flash disconnected from XIP
+enum Operation {
+    Erase(u32, usize),
+    Program(u32, *const u8, usize),
+}
+
+/// Runs `operation` with the flash out of XIP mode
+///
+/// While XIP is disabled the code cannot run from the flash, so this
+/// function is placed in RAM and only calls the ROM functions and
+/// the RAM copy of the second stage bootloader.
+#[link_section = ".ramfunc"]
+#[inline(never)]
+unsafe fn run_from_ram(rom: &RomFunctions, operation: &Operation) {
+    (rom.connect_internal_flash)();
+    (rom.flash_exit_xip)();
+    match *operation {
+        Operation::Erase(offset, len) => {
+            (rom.flash_range_erase)(offset, len, BLOCK_SIZE, BLOCK_ERASE_CMD)
+        }
+        Operation::Program(offset, data, len) => (rom.flash_range_program)(offset, data, len),
+    }
+    (rom.flash_flush_cache)();
+    (rom.boot2)();
+}
+
+/// The RP2040's external flash, used by the boot message's key-value store
+///
+/// The flash is written and erased through the ROM functions,
+/// with the interrupts disabled, as no code can run from the
+/// flash in the meantime.
+pub struct RomFlash {
+    /// The offset of the key-value region within the flash
+    start: usize,
+}
+
+impl RomFlash {
+    /// Initializes a new flash structure for the key-value region
+    /// that starts at the sector aligned offset `start` of the flash
+    pub const fn new(start: usize) -> RomFlash {
+        RomFlash { start }
+    }
+
+    fn run(&self, operation: Operation) {
+        unsafe {
+            cortexm0p::support::atomic(|| {
+                let rom = RomFunctions::new();
+                run_from_ram(&rom, &operation);
+            });
+        }
+    }
+}
+
+impl FlashController<SECTOR_SIZE> for RomFlash {
+    fn read_region(
+        &self,
+        region_number: usize,
+        offset: usize,
+        buf: &mut [u8; SECTOR_SIZE],
+    ) -> Result<(), ErrorCode> {
+        let address = XIP_BASE + self.start + region_number * SECTOR_SIZE + offset;
+        for (index, byte) in buf.iter_mut().enumerate() {
+            *byte = unsafe { core::ptr::read_volatile((address + index) as *const u8) };
+        }
+        Ok(())
+    }
+
+    fn write(&self, address: usize, buf: &[u8]) -> Result<(), ErrorCode> {
+        let start = self.start + address;
+        let end = start + buf.len();
+        // The flash is programmed one page at a time, the bytes of
+        // the page that are not part of `buf` are programmed again
+        // with their current value, which leaves them unchanged.
+        let mut page_offset = start & !(PAGE_SIZE - 1);
+        while page_offset < end {
+            let mut page = [0u8; PAGE_SIZE];
+            for (index, byte) in page.iter_mut().enumerate() {
+                let offset = page_offset + index;
+                *byte = if offset >= start && offset < end {
+                    buf[offset - start]
+                } else {
+                    unsafe { core::ptr::read_volatile((XIP_BASE + offset) as *const u8) }
+                };
+            }
+            self.run(Operation::Program(
+                page_offset as u32,
+                page.as_ptr(),
+                PAGE_SIZE,
+            ));
+            page_offset = page_offset + PAGE_SIZE;
+        }
+        Ok(())
+    }
+
+    fn erase_region(&self, region_number: usize) -> Result<(), ErrorCode> {
+        self.run(Operation::Erase(
+            (self.start + region_number * SECTOR_SIZE) as u32,
+            SECTOR_SIZE,
+        ));
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use core::fmt::Write;
 use core::panic::PanicInfo;
 
+use drivers::panic_matrix::{PanicMatrix, PanicMessage};
 use kernel::debug::{self, IoWrite};
-use kernel::hil::led::LedHigh;
+use kernel::hil::gpio::ActivationMode;
+use kernel::hil::led::{Led, LedHigh};
 use kernel::hil::uart::{Configure, Parameters, Parity, StopBits, Width};
+use kernel::platform::watchdog::WatchDog;
 use kernel::utilities::cells::OptionalCell;
 
 use rp2040::gpio::{GpioFunction, RPGpio, RPGpioPin};
@@ -74,24 +77,48 @@ impl IoWrite for Writer {
     }
 }
 
-/// Default panic handler for the Raspberry Pi Pico board.
+/// The number of *nop* loop iterations that a row of the
+/// matrix stays lit while panicking
+const PANIC_ROW_DELAY: usize = 16000;
+
+/// The number of matrix refreshes that a column of the
+/// panic message is displayed for
+const PANIC_COLUMN_REFRESHES: usize = 40;
+
+/// Panic handler for the Raspberry Pi Pico board.
 ///
-/// We just use the standard default provided by the debug module in the kernel.
+/// We print the panic information to the serial port using the debug
+/// module in the kernel. As there is usually no serial cable in the field,
+/// we then scroll the panic location and the faulting process name on the
+/// LED matrix forever.
 #[cfg(not(test))]
 #[no_mangle]
 #[panic_handler]
 pub unsafe extern "C" fn panic_fmt(pi: &PanicInfo) -> ! {
-    // LED is conneted to GPIO 25
+    // Stop the watchdog, so that it does not reset the board
+    // while the panic is displayed
+    crate::watchdog::Watchdog::new(crate::WATCHDOG_TIMEOUT_MS).suspend();
+    // LED is conneted to GPIO 25, turn it on to signal the panic
     let led_kernel_pin = &RPGpioPin::new(RPGpio::GPIO25);
     let led = &mut LedHigh::new(led_kernel_pin);
+    led.init();
+    led.on();
     let writer = &mut WRITER;
+    debug::panic_print(writer, pi, &cortexm0p::support::nop, &PROCESSES, &CHIP);
 
-    debug::panic(
-        &mut [led],
-        writer,
-        pi,
-        &cortexm0p::support::nop,
-        &PROCESSES,
-        &CHIP,
-    )
+    // Drive the LED matrix GPIOs directly, as interrupts are not available
+    let cols = crate::LED_MATRIX_COLS.map(RPGpioPin::new);
+    let rows = crate::LED_MATRIX_ROWS.map(RPGpioPin::new);
+    let matrix = PanicMatrix::new(
+        [&cols[0], &cols[1], &cols[2], &cols[3], &cols[4]],
+        ActivationMode::ActiveHigh,
+        [&rows[0], &rows[1], &rows[2], &rows[3], &rows[4]],
+        ActivationMode::ActiveLow,
+    );
+    let message = PanicMessage::new(pi.location(), pi.message(), &PROCESSES);
+    matrix.scroll_forever(message.as_bytes(), PANIC_COLUMN_REFRESHES, &|| {
+        for _ in 0..PANIC_ROW_DELAY {
+            cortexm0p::support::nop();
+        }
+    })
 }
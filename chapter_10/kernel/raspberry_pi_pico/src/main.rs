@@ -7,7 +7,7 @@
 // https://github.com/rust-lang/rust/issues/62184.
 #![cfg_attr(not(doc), no_main)]
 #![deny(missing_docs)]
-#![feature(asm, naked_functions)]
+#![feature(asm, naked_functions, panic_info_message)]
 
 use capsules::virtual_alarm::VirtualMuxAlarm;
 use components::gpio::GpioComponent;
@@ -17,7 +17,9 @@ use kernel::component::Component;
 use kernel::debug;
 use kernel::dynamic_deferred_call::{DynamicDeferredCall, DynamicDeferredCallClientState};
 use kernel::hil::led::LedHigh;
+use kernel::hil::text_screen::TextScreen;
 use kernel::hil::time::Alarm;
+use kernel::hil::uart::{Receive, Transmit};
 use kernel::platform::{KernelResources, SyscallDriverLookup};
 use kernel::scheduler::round_robin::RoundRobinSched;
 use kernel::syscall::SyscallDriver;
@@ -39,6 +41,10 @@ use rp2040::timer::RPTimer;
 
 mod io;
 
+mod watchdog;
+
+mod flash_controller;
+
 mod flash_bootloader;
 
 /// Allocate memory for the stack
@@ -51,14 +57,68 @@ pub static mut STACK_MEMORY: [u8; 0x1000] = [0; 0x1000];
 #[link_section = ".flash_bootloader"]
 static FLASH_BOOTLOADER: [u8; 256] = flash_bootloader::FLASH_BOOTLOADER;
 
-// State for loading and holding applications.
+/// LED matrix
+const LED_MATRIX_COLS: [RPGpio; 5] = [
+    RPGpio::GPIO2,
+    RPGpio::GPIO3,
+    RPGpio::GPIO4,
+    RPGpio::GPIO5,
+    RPGpio::GPIO6,
+];
+const LED_MATRIX_ROWS: [RPGpio; 5] = [
+    RPGpio::GPIO7,
+    RPGpio::GPIO8,
+    RPGpio::GPIO9,
+    RPGpio::GPIO10,
+    RPGpio::GPIO11,
+];
+
 // How should the kernel respond when a process faults.
-const FAULT_RESPONSE: kernel::process::PanicFaultPolicy = kernel::process::PanicFaultPolicy {};
+// A faulted process is restarted after FAULT_RESTART_DELAY_MS, the delay
+// doubles with each restart, up to FAULT_MAX_RESTARTS restarts. A process
+// that runs for FAULT_RESET_MS without faulting starts over with the first delay.
+const FAULT_RESTART_DELAY_MS: u32 = 1000;
+const FAULT_MAX_RESTARTS: usize = 5;
+const FAULT_RESET_MS: u32 = 60000;
+
+// The kernel loop has to tickle the watchdog at least once every
+// WATCHDOG_TIMEOUT_MS, otherwise the watchdog resets the board.
+const WATCHDOG_TIMEOUT_MS: u32 = 2000;
+
+// How long the board displays that the watchdog has reset it.
+const RESET_MESSAGE_MS: u32 = 10000;
+
+// The flash area that stores the boot message of the LED matrix,
+// the last 16 KB of the 2 MB flash, after the applications.
+const BOOT_MESSAGE_FLASH_START: usize = 0x1F_C000;
+const BOOT_MESSAGE_FLASH_SIZE: usize = 0x4000;
+
+// Saving the boot message writes and erases the flash, processes may
+// save it at most once every BOOT_MESSAGE_SAVE_INTERVAL_MS.
+// The flash is written with the interrupts disabled.
+const BOOT_MESSAGE_SAVE_INTERVAL_MS: u32 = 10000;
 
+// State for loading and holding applications.
 // Number of concurrent processes this platform supports.
 const NUM_PROCS: usize = 4;
 const NUM_UPCALLS_IPC: usize = NUM_PROCS + 1;
 
+// The drivers that only some applications may use, each entry allows
+// an application to use a range of commands of a driver.
+const SYSCALL_PERMISSIONS: [drivers::syscall_filter::Permission; 2] = [
+    // Only the example application drives the display.
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::led_matrix_text::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::grayscale_display::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+];
+
 static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PROCS] =
     [None; NUM_PROCS];
 
@@ -75,7 +135,10 @@ pub struct RaspberryPiPico {
     adc: &'static capsules::adc::AdcVirtualized<'static>,
     temperature: &'static capsules::temperature::TemperatureSensor<'static>,
 
+    syscall_filter: &'static drivers::syscall_filter::AllowlistSyscallFilter,
+
     scheduler: &'static RoundRobinSched<'static>,
+    watchdog: &'static watchdog::Watchdog,
     systick: cortexm0p::systick::SysTick,
 
     /// Add Tock's `TextScreen` driver to the board implementation structure.
@@ -90,6 +153,16 @@ pub struct RaspberryPiPico {
         >,
         capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
     >,
+    /// Add the `GrayscaleDisplay` driver to the board implementation structure.
+    grayscale_display: &'static drivers::grayscale_display::GrayscaleDisplay<
+        'static,
+        LedMatrixLed<
+            'static,
+            RPGpioPin<'static>,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+    >,
 }
 
 impl SyscallDriverLookup for RaspberryPiPico {
@@ -109,6 +182,8 @@ impl SyscallDriverLookup for RaspberryPiPico {
             capsules::text_screen::DRIVER_NUM => f(Some(self.text_screen)),
             // Register the `LedMatrixText` driver with the kernel.
             drivers::led_matrix_text::DRIVER_NUM => f(Some(self.led_matrix_text)),
+            // Register the `GrayscaleDisplay` driver with the kernel.
+            drivers::grayscale_display::DRIVER_NUM => f(Some(self.grayscale_display)),
             _ => f(None),
         }
     }
@@ -116,17 +191,17 @@ impl SyscallDriverLookup for RaspberryPiPico {
 
 impl KernelResources<Rp2040<'static, Rp2040DefaultPeripherals<'static>>> for RaspberryPiPico {
     type SyscallDriverLookup = Self;
-    type SyscallFilter = ();
+    type SyscallFilter = drivers::syscall_filter::AllowlistSyscallFilter;
     type ProcessFault = ();
     type Scheduler = RoundRobinSched<'static>;
     type SchedulerTimer = cortexm0p::systick::SysTick;
-    type WatchDog = ();
+    type WatchDog = watchdog::Watchdog;
 
     fn syscall_driver_lookup(&self) -> &Self::SyscallDriverLookup {
         &self
     }
     fn syscall_filter(&self) -> &Self::SyscallFilter {
-        &()
+        self.syscall_filter
     }
     fn process_fault(&self) -> &Self::ProcessFault {
         &()
@@ -138,7 +213,7 @@ impl KernelResources<Rp2040<'static, Rp2040DefaultPeripherals<'static>>> for Ras
         &self.systick
     }
     fn watchdog(&self) -> &Self::WatchDog {
-        &()
+        self.watchdog
     }
 }
 
@@ -251,6 +326,9 @@ pub unsafe fn main() {
     // Loads relocations and clears BSS
     rp2040::init();
 
+    // Read the reset cause before anything else can reset the board.
+    let reset_cause = watchdog::reset_cause();
+
     let peripherals = get_peripherals();
 
     // Set the UART used for panic
@@ -312,7 +390,7 @@ pub unsafe fn main() {
     let memory_allocation_capability = create_capability!(capabilities::MemoryAllocationCapability);
 
     let dynamic_deferred_call_clients =
-        static_init!([DynamicDeferredCallClientState; 3], Default::default());
+        static_init!([DynamicDeferredCallClientState; 4], Default::default());
     let dynamic_deferred_caller = static_init!(
         DynamicDeferredCall,
         DynamicDeferredCall::new(dynamic_deferred_call_clients)
@@ -348,6 +426,8 @@ pub unsafe fn main() {
     // Create the debugger object that handles calls to `debug!()`.
     components::debug_writer::DebugWriterComponent::new(uart_mux).finalize(());
 
+    debug!("Reset cause: {}", reset_cause);
+
     let gpio = GpioComponent::new(
         board_kernel,
         capsules::gpio::DRIVER_NUM,
@@ -454,17 +534,17 @@ pub unsafe fn main() {
         mux_alarm,
         @fps => 60,
         @cols => kernel::hil::gpio::ActivationMode::ActiveHigh,
-            &peripherals.pins.get_pin(RPGpio::GPIO2),
-            &peripherals.pins.get_pin(RPGpio::GPIO3),
-            &peripherals.pins.get_pin(RPGpio::GPIO4),
-            &peripherals.pins.get_pin(RPGpio::GPIO5),
-            &peripherals.pins.get_pin(RPGpio::GPIO6),
+            &peripherals.pins.get_pin(LED_MATRIX_COLS[0]),
+            &peripherals.pins.get_pin(LED_MATRIX_COLS[1]),
+            &peripherals.pins.get_pin(LED_MATRIX_COLS[2]),
+            &peripherals.pins.get_pin(LED_MATRIX_COLS[3]),
+            &peripherals.pins.get_pin(LED_MATRIX_COLS[4]),
         @rows => kernel::hil::gpio::ActivationMode::ActiveLow,
-            &peripherals.pins.get_pin(RPGpio::GPIO7),
-            &peripherals.pins.get_pin(RPGpio::GPIO8),
-            &peripherals.pins.get_pin(RPGpio::GPIO9),
-            &peripherals.pins.get_pin(RPGpio::GPIO10),
-            &peripherals.pins.get_pin(RPGpio::GPIO11),
+            &peripherals.pins.get_pin(LED_MATRIX_ROWS[0]),
+            &peripherals.pins.get_pin(LED_MATRIX_ROWS[1]),
+            &peripherals.pins.get_pin(LED_MATRIX_ROWS[2]),
+            &peripherals.pins.get_pin(LED_MATRIX_ROWS[3]),
+            &peripherals.pins.get_pin(LED_MATRIX_ROWS[4]),
 
     )
     .finalize(components::led_matrix_component_buf!(
@@ -535,6 +615,8 @@ pub unsafe fn main() {
             virtual_alarm_led_matrix_text,
             // Send the allocated buffer to the driver
             led_matrix_buffer,
+            // Split the buffer into a screen of 5 rows of 10 characters
+            10,
             // Set the default speed in ms
             300,
             // Set the kernel's deferred caller
@@ -555,20 +637,329 @@ pub unsafe fn main() {
             .expect("no deferred call slot available for led matrix text"),
     );
 
+    // Initialize the key-value store of the boot message
+    let boot_message_read_buffer = static_init!(
+        [u8; flash_controller::SECTOR_SIZE],
+        [0; flash_controller::SECTOR_SIZE]
+    );
+    let boot_message_store = static_init!(
+        drivers::boot_message::TicKVStore<
+            'static,
+            flash_controller::RomFlash,
+            { flash_controller::SECTOR_SIZE },
+        >,
+        drivers::boot_message::TicKVStore::new(
+            flash_controller::RomFlash::new(BOOT_MESSAGE_FLASH_START),
+            boot_message_read_buffer,
+            BOOT_MESSAGE_FLASH_SIZE
+        )
+    );
+
+    // Display the boot message, if one has been saved
+    match boot_message_store.initialise() {
+        Ok(()) => led_matrix_text
+            .set_boot_message_store(boot_message_store, BOOT_MESSAGE_SAVE_INTERVAL_MS),
+        Err(err) => debug!("Boot message store unavailable: {:?}", err),
+    }
+
+    // Initialize a virtual alarm for the text screen mux
+    let virtual_alarm_mux_text_screen = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize a 'static buffer of 50 for the text screen mux
+    let mux_text_screen_buffer = static_init!([u8; 50], [0; 50]);
+
+    // Initialize the mux that shares the LedMatrixText driver between
+    // several kernel clients. Clients with the same priority take
+    // turns every 5 seconds.
+    let mux_text_screen = static_init!(
+        drivers::virtual_text_screen::MuxTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::virtual_text_screen::MuxTextScreen::new(
+            led_matrix_text,
+            virtual_alarm_mux_text_screen,
+            mux_text_screen_buffer,
+            5000,
+            dynamic_deferred_caller
+        )
+    );
+
+    // The mux receives the LedMatrixText driver's callbacks
+    led_matrix_text.set_client(Some(mux_text_screen));
+    virtual_alarm_mux_text_screen.set_alarm_client(mux_text_screen);
+    mux_text_screen.initialize_callback_handle(
+        dynamic_deferred_caller
+            .register(mux_text_screen)
+            .expect("no deferred call slot available for text screen mux"),
+    );
+
+    // Initialize the virtual screen used by the TextScreen driver
+    let virtual_text_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_text_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_text_screen_buffer,
+            // Processes have the lowest priority
+            0
+        )
+    );
+    virtual_text_screen.setup();
+
+    // Initialize a virtual alarm for the fault policy
+    let virtual_alarm_fault_policy = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize the virtual screen that displays the process faults
+    let virtual_fault_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_fault_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_fault_screen_buffer,
+            // Faults are displayed over the processes' text
+            1
+        )
+    );
+    virtual_fault_screen.setup();
+
+    // Initialize the fault policy that restarts the faulted processes
+    let fault_policy_message = static_init!(
+        [u8; drivers::restart_fault_policy::MESSAGE_LEN],
+        [0; drivers::restart_fault_policy::MESSAGE_LEN]
+    );
+    let fault_policy = static_init!(
+        drivers::restart_fault_policy::RestartFaultPolicy<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+            NUM_PROCS,
+        >,
+        drivers::restart_fault_policy::RestartFaultPolicy::new(
+            &PROCESSES,
+            virtual_alarm_fault_policy,
+            virtual_fault_screen,
+            fault_policy_message,
+            FAULT_RESTART_DELAY_MS,
+            FAULT_MAX_RESTARTS,
+            FAULT_RESET_MS
+        )
+    );
+    virtual_alarm_fault_policy.set_alarm_client(fault_policy);
+    virtual_fault_screen.set_client(Some(fault_policy));
+
+    // Initialize a virtual alarm for the reset message
+    let virtual_alarm_reset_message = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize the virtual screen that displays the reset cause
+    let virtual_reset_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_reset_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_reset_screen_buffer,
+            // The reset cause is displayed over the faults
+            2
+        )
+    );
+    virtual_reset_screen.setup();
+
+    // Initialize the message that displays the reset cause
+    let reset_message_buffer = static_init!([u8; 10], [0; 10]);
+    let reset_message = static_init!(
+        drivers::screen_message::ScreenMessage<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::screen_message::ScreenMessage::new(
+            virtual_reset_screen,
+            virtual_alarm_reset_message,
+            reset_message_buffer
+        )
+    );
+    virtual_alarm_reset_message.set_alarm_client(reset_message);
+    virtual_reset_screen.set_client(Some(reset_message));
+
+    // Tell the user that the watchdog has reset the board
+    if reset_cause == watchdog::WATCHDOG_RESET {
+        let _ = reset_message.show("WDT", RESET_MESSAGE_MS);
+    }
+
+    // Initialize the virtual screen driven by the display console
+    let virtual_console_screen_buffer = static_init!([u8; 50], [0; 50]);
+    let virtual_console_screen = static_init!(
+        drivers::virtual_text_screen::VirtualTextScreen<
+            'static,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::virtual_text_screen::VirtualTextScreen::new(
+            mux_text_screen,
+            virtual_console_screen_buffer,
+            // The technician's text is displayed over everything else
+            3
+        )
+    );
+    virtual_console_screen.setup();
+
+    // Initialize the display console on its own device of the UART mux,
+    // so that the matrix can be driven over serial with no application
+    // loaded. The process console receives its lines from the display
+    // console and transmits them on a device of its own.
+    let display_console_uart = static_init!(
+        capsules::virtual_uart::UartDevice<'static>,
+        capsules::virtual_uart::UartDevice::new(uart_mux, true)
+    );
+    display_console_uart.setup();
+    let process_console_uart = static_init!(
+        capsules::virtual_uart::UartDevice<'static>,
+        capsules::virtual_uart::UartDevice::new(uart_mux, false)
+    );
+    process_console_uart.setup();
+    let display_console_tx_buffer = static_init!([u8; 32], [0; 32]);
+    let display_console_queue = static_init!([u8; 64], [0; 64]);
+    let display_console_rx_buffer = static_init!([u8; 1], [0; 1]);
+    let display_console_command = static_init!([u8; 64], [0; 64]);
+    let display_console_text = static_init!([u8; 50], [0; 50]);
+    let display_console = static_init!(
+        drivers::display_console::DisplayConsole<'static>,
+        drivers::display_console::DisplayConsole::new(
+            display_console_uart,
+            process_console_uart,
+            virtual_console_screen,
+            led_matrix_text,
+            display_console_tx_buffer,
+            display_console_queue,
+            display_console_rx_buffer,
+            display_console_command,
+            display_console_text
+        )
+    );
+    display_console_uart.set_transmit_client(display_console);
+    display_console_uart.set_receive_client(display_console);
+    virtual_console_screen.set_client(Some(display_console));
+    let _ = display_console.start();
+
+    // Initialize the process console, the display console
+    // forwards it the lines that are not display commands
+    let process_console = static_init!(
+        capsules::process_console::ProcessConsole<
+            'static,
+            components::process_console::Capability,
+        >,
+        capsules::process_console::ProcessConsole::new(
+            display_console,
+            &mut capsules::process_console::WRITE_BUF,
+            &mut capsules::process_console::READ_BUF,
+            &mut capsules::process_console::QUEUE_BUF,
+            &mut capsules::process_console::COMMAND_BUF,
+            board_kernel,
+            components::process_console::Capability
+        )
+    );
+    display_console.set_transmit_client(process_console);
+    display_console.set_receive_client(process_console);
+    let _ = process_console.start();
+
     // Initialize a new TextScreen driver...
     let text_screen = components::text_screen::TextScreenComponent::new(
         board_kernel,
         capsules::text_screen::DRIVER_NUM,
-        led_matrix_text,
+        virtual_text_screen,
     )
     // ... with a buffer of length 50.
     .finalize(components::screen_buffer_size!(50));
 
-    // PROCESS CONSOLE
-    let process_console =
-        components::process_console::ProcessConsoleComponent::new(board_kernel, uart_mux)
-            .finalize(());
-    let _ = process_console.start();
+    // Initialize a virtual alarm for the GrayscaleDisplay driver
+    let virtual_alarm_grayscale_display = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // Initialize a 'static framebuffer for the GrayscaleDisplay driver
+    let grayscale_framebuffer = static_init!(
+        [u8; drivers::grayscale_display::FRAMEBUFFER_LEN],
+        [0; drivers::grayscale_display::FRAMEBUFFER_LEN]
+    );
+
+    // Initialize the GrayscaleDisplay using the static_init! macro
+    // The LEDs are the ones of the LedMatrixText driver, the two
+    // drivers are not supposed to display at the same time.
+    let grayscale_display = static_init!(
+        drivers::grayscale_display::GrayscaleDisplay<
+            'static,
+            LedMatrixLed<
+                'static,
+                RPGpioPin<'static>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+            >,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::grayscale_display::GrayscaleDisplay::new(
+            components::led_matrix_leds!(
+                RPGpioPin<'static>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+                led_matrix_driver,
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (0, 3),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (4, 3),
+                (0, 4),
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4)
+            ),
+            virtual_alarm_grayscale_display,
+            grayscale_framebuffer,
+            board_kernel.create_grant(
+                drivers::grayscale_display::DRIVER_NUM,
+                &memory_allocation_capability
+            )
+        )
+    );
+
+    // Set the driver as the alarm's client.
+    virtual_alarm_grayscale_display.set_alarm_client(grayscale_display);
+
+    // Only the applications allowed by the permissions may use the
+    // restricted drivers.
+    let syscall_filter = static_init!(
+        drivers::syscall_filter::AllowlistSyscallFilter,
+        drivers::syscall_filter::AllowlistSyscallFilter::new(&PROCESSES, &SYSCALL_PERMISSIONS)
+    );
 
     let scheduler = components::sched::round_robin::RoundRobinComponent::new(&PROCESSES)
         .finalize(components::rr_component_helper!(NUM_PROCS));
@@ -586,13 +977,21 @@ pub unsafe fn main() {
         adc: adc_syscall,
         temperature: temp,
 
+        syscall_filter,
+
         scheduler,
+        watchdog: static_init!(
+            watchdog::Watchdog,
+            watchdog::Watchdog::new(WATCHDOG_TIMEOUT_MS)
+        ),
         systick: cortexm0p::systick::SysTick::new_with_calibration(125_000_000),
 
         // Add the TextScreen driver to the boards implementation initialization.
         text_screen,
         // Add the LedMatrixText driver to the boards implementation initialization.
         led_matrix_text,
+        // Add the GrayscaleDisplay driver to the boards implementation initialization.
+        grayscale_display,
     };
 
     let platform_type = match peripherals.sysinfo.get_platform() {
@@ -631,7 +1030,7 @@ pub unsafe fn main() {
             &_eappmem as *const u8 as usize - &_sappmem as *const u8 as usize,
         ),
         &mut PROCESSES,
-        &FAULT_RESPONSE,
+        fault_policy,
         &process_management_capability,
     )
     .unwrap_or_else(|err| {
@@ -0,0 +1,121 @@
+use kernel::platform::watchdog::WatchDog;
+use kernel::utilities::registers::interfaces::{ReadWriteable, Readable, Writeable};
+use kernel::utilities::registers::{
+    register_bitfields, register_structs, ReadOnly, ReadWrite, WriteOnly,
+};
+use kernel::utilities::StaticRef;
+
+register_structs! {
+    WatchdogRegisters {
+        (0x00 => ctrl: ReadWrite<u32, Ctrl::Register>),
+        (0x04 => load: WriteOnly<u32>),
+        (0x08 => reason: ReadOnly<u32, Reason::Register>),
+        (0x0C => @END),
+    }
+}
+
+register_structs! {
+    PsmRegisters {
+        (0x00 => _reserved0),
+        (0x08 => wdsel: ReadWrite<u32>),
+        (0x0C => @END),
+    }
+}
+
+register_bitfields! [u32,
+    Ctrl [
+        TRIGGER OFFSET(31) NUMBITS(1) [],
+        ENABLE OFFSET(30) NUMBITS(1) [],
+        PAUSE_DBG1 OFFSET(26) NUMBITS(1) [],
+        PAUSE_DBG0 OFFSET(25) NUMBITS(1) [],
+        PAUSE_JTAG OFFSET(24) NUMBITS(1) [],
+        TIME OFFSET(0) NUMBITS(24) []
+    ],
+    Reason [
+        FORCE OFFSET(1) NUMBITS(1) [],
+        TIMER OFFSET(0) NUMBITS(1) []
+    ]
+];
+
+const WATCHDOG_BASE: StaticRef<WatchdogRegisters> =
+    unsafe { StaticRef::new(0x4005_8000 as *const WatchdogRegisters) };
+
+const PSM_BASE: StaticRef<PsmRegisters> =
+    unsafe { StaticRef::new(0x4001_0000 as *const PsmRegisters) };
+
+/// The blocks reset by the watchdog, all except the oscillators
+const PSM_WDSEL_ALL_BUT_OSCILLATORS: u32 = 0x0001_fffc;
+
+/// The largest value of the watchdog's counter
+const MAX_LOAD: u32 = 0x00ff_ffff;
+
+/// The reset cause reported after a watchdog reset
+pub const WATCHDOG_RESET: &str = "watchdog";
+
+/// Returns the cause of the last reset
+///
+/// The watchdog only records its own resets, all the other
+/// causes are reported as a power on or a reset pin.
+pub fn reset_cause() -> &'static str {
+    let reason = WATCHDOG_BASE.reason.extract();
+    if reason.is_set(Reason::TIMER) {
+        WATCHDOG_RESET
+    } else if reason.is_set(Reason::FORCE) {
+        "watchdog forced"
+    } else {
+        "power on or reset pin"
+    }
+}
+
+/// The RP2040 watchdog
+///
+/// The watchdog resets the chip if the kernel loop does not tickle
+/// it for `timeout_ms` milliseconds. It counts the 1 µs ticks started
+/// by `init_clocks` and is disabled while the kernel sleeps. The panic
+/// handler disables it, so that the panic stays displayed.
+pub struct Watchdog {
+    registers: StaticRef<WatchdogRegisters>,
+    timeout_ms: u32,
+}
+
+impl Watchdog {
+    /// Initializes a new watchdog structure
+    pub const fn new(timeout_ms: u32) -> Watchdog {
+        Watchdog {
+            registers: WATCHDOG_BASE,
+            timeout_ms,
+        }
+    }
+
+    /// Returns the value loaded into the watchdog's counter
+    fn load_value(&self) -> u32 {
+        // The counter decrements twice per tick (RP2040 erratum E1).
+        (self.timeout_ms.saturating_mul(1000).saturating_mul(2)).min(MAX_LOAD)
+    }
+}
+
+impl WatchDog for Watchdog {
+    fn setup(&self) {
+        self.registers.ctrl.modify(Ctrl::ENABLE::CLEAR);
+        // Reset everything except the oscillators.
+        PSM_BASE.wdsel.set(PSM_WDSEL_ALL_BUT_OSCILLATORS);
+        self.registers
+            .ctrl
+            .modify(Ctrl::PAUSE_DBG0::SET + Ctrl::PAUSE_DBG1::SET + Ctrl::PAUSE_JTAG::SET);
+        self.tickle();
+        self.registers.ctrl.modify(Ctrl::ENABLE::SET);
+    }
+
+    fn tickle(&self) {
+        self.registers.load.set(self.load_value());
+    }
+
+    fn suspend(&self) {
+        self.registers.ctrl.modify(Ctrl::ENABLE::CLEAR);
+    }
+
+    fn resume(&self) {
+        self.tickle();
+        self.registers.ctrl.modify(Ctrl::ENABLE::SET);
+    }
+}
@@ -4,27 +4,89 @@
 #include <tock.h>
 #include <ipc.h>
 
+// The number of the kernel's TextDisplayService driver
+#define DRIVER_NUM_TEXT_DISPLAY_SERVICE 0xa0005
+
 char display_buffer[DISPLAY_BUFFER_LEN] __attribute__((aligned(64)));
 int text_display_service = -1;
 
+// Stores whether the kernel provides the service, in which
+// case the library uses the driver instead of IPC
+static bool text_display_driver = false;
+
 static void ipc_callback(__attribute__((unused)) int pid, __attribute__((unused)) int len, __attribute__((unused)) int buf, void* ud) {
   bool *done = (bool*)ud;
   *done = true;
 }
 
+// The driver calls this function when it has copied the text
+// from the shared buffer
+static void driver_callback(__attribute__((unused)) int len, __attribute__((unused)) int unused1, __attribute__((unused)) int unused2, void* ud) {
+  bool *done = (bool*)ud;
+  *done = true;
+}
+
 bool display_text_is_present (void) {
-  // verifies if the service is present and 
+  // verifies if the kernel provides the service
+  syscall_return_t ret = command (DRIVER_NUM_TEXT_DISPLAY_SERVICE, 0, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    text_display_driver = true;
+    return true;
+  }
+  // verifies if the service is present
   // and registers its id
   return ipc_discover ("text_display.service", &text_display_service) == RETURNCODE_SUCCESS;
 }
 
+// Display a text using the kernel's driver
+static int display_text_driver (void) {
+  int ret = RETURNCODE_SUCCESS;
+  bool done = false;
+
+  // share the buffer with the driver
+  allow_ro_return_t allow_ret = allow_readonly (DRIVER_NUM_TEXT_DISPLAY_SERVICE, 0, display_buffer, DISPLAY_BUFFER_LEN);
+  if (allow_ret.success) {
+    // register the callback to get notified when the driver
+    // has finished copying the data from the buffer
+    subscribe_return_t subscribe_ret = subscribe (DRIVER_NUM_TEXT_DISPLAY_SERVICE, 0, driver_callback, &done);
+    if (subscribe_ret.success) {
+      // ask the driver to display the text
+      syscall_return_t command_ret = command (DRIVER_NUM_TEXT_DISPLAY_SERVICE, 1, 0, 0);
+      if (command_ret.type == TOCK_SYSCALL_SUCCESS) {
+        // wait for the driver to copy the text from the shared
+        // buffer to its own buffer
+        yield_for(&done);
+      } else {
+        ret = tock_status_to_returncode(command_ret.data[0]);
+      }
+      subscribe (DRIVER_NUM_TEXT_DISPLAY_SERVICE, 0, NULL, NULL);
+    } else {
+      ret = tock_status_to_returncode(subscribe_ret.status);
+    }
+    // stop sharing the buffer so that is becomes
+    // accesible to the application
+    allow_readonly (DRIVER_NUM_TEXT_DISPLAY_SERVICE, 0, NULL, 0);
+  } else {
+    ret = tock_status_to_returncode(allow_ret.status);
+  }
+  return ret;
+}
+
 int display_text (const char *buffer) {
   int ret = RETURNCODE_SUCCESS;
   bool done = false;
 
   // if the service ID has not yet been registered,
   // try to register it
-  if (text_display_service == -1) display_text_is_present();
+  if (!text_display_driver && text_display_service == -1) display_text_is_present();
+
+  // if the kernel provides the service, use the driver
+  if (text_display_driver)
+  {
+    // copy the text into the shared buffer
+    strncpy (display_buffer, buffer, DISPLAY_BUFFER_LEN);
+    return display_text_driver ();
+  }
 
   // if the service is present, display the text
   if (text_display_service)
@@ -37,7 +99,7 @@ int display_text (const char *buffer) {
     ret = ipc_share(text_display_service, display_buffer, DISPLAY_BUFFER_LEN);
     if (ret == RETURNCODE_SUCCESS) {
       // register the service client callback
-      // to get notified when the service buffer 
+      // to get notified when the service buffer
       // has finished copying the data from the buffer
       ret = ipc_register_client_callback(text_display_service, ipc_callback, &done);
       if (ret == RETURNCODE_SUCCESS)
@@ -56,4 +118,4 @@ int display_text (const char *buffer) {
     }
   }
   return ret;
-}
\ No newline at end of file
+}
@@ -6,6 +6,11 @@
 
 #define DISPLAY_BUFFER_LEN 64
 
+// The degree sign, the service displays Latin-1 bytes, so a "°"
+// written in a UTF-8 source file would be displayed as two
+// unknown characters, use "21" DISPLAY_TEXT_DEGREE "C" instead
+#define DISPLAY_TEXT_DEGREE "\xb0"
+
 #ifdef __cplusplus
 extern "C" {
 #endif
@@ -0,0 +1,110 @@
+// Generated from the font library by its c_header example, do not edit
+//
+// Each glyph stores the state of the 25 LEDs, the most significant
+// bit being LED 0 (upper left) and the least significant one LED 24
+// (lower right).
+
+#pragma once
+
+#include <stdint.h>
+
+#define FONT5X5_FIRST ' '
+#define FONT5X5_LAST '~'
+
+static const uint32_t FONT5X5[] = {
+    0b0000000000000000000000000, // ' '
+    0b0010000100001000000000100, // '!'
+    0b0101001010000000000000000, // '"'
+    0b0101011111010101111101010, // '#'
+    0b0111110100011100010111110, // '$'
+    0b1100111010001000101110011, // '%'
+    0b0110010010011011001001101, // '&'
+    0b0010000100000000000000000, // '\''
+    0b0001000100001000010000010, // '('
+    0b0100000100001000010001000, // ')'
+    0b0010010101011101010100100, // '*'
+    0b0000000100011100010000000, // '+'
+    0b0000000000000000010001000, // ','
+    0b0000000000011100000000000, // '-'
+    0b0000000000000000000000100, // '.'
+    0b0000100010001000100010000, // '/'
+    0b1111110011101011100111111, // '0'
+    0b0010001100001000010001110, // '1'
+    0b1111000001011101000011111, // '2'
+    0b1111000001111100000111110, // '3'
+    0b1000010000101001111100100, // '4'
+    0b1111110000111100000111110, // '5'
+    0b1111110000111111000111111, // '6'
+    0b1111100001000100010000100, // '7'
+    0b1111110001111111000111111, // '8'
+    0b1111110001111110000111111, // '9'
+    0b0000000100000000010000000, // ':'
+    0b0000000100000000010001000, // ';'
+    0b0001000100010000010000010, // '<'
+    0b0000001110000000111000000, // '='
+    0b0100000100000100010001000, // '>'
+    0b0111010001001100000000100, // '?'
+    0b0111010001101111000001110, // '@'
+    0b0111010001111111000110001, // 'A'
+    0b1111110001111101000111111, // 'B'
+    0b1111110000100001000011111, // 'C'
+    0b1111010001100011000111110, // 'D'
+    0b1111110000111101000011111, // 'E'
+    0b1111110000111101000010000, // 'F'
+    0b1111110000101111000111111, // 'G'
+    0b1000110001111111000110001, // 'H'
+    0b1111100100001000010011111, // 'I'
+    0b0001100001000011000111111, // 'J'
+    0b1000110010111001001010001, // 'K'
+    0b1000010000100001000011111, // 'L'
+    0b1000111011101011000110001, // 'M'
+    0b1000111001101011001110001, // 'N'
+    0b0111010001100011000101110, // 'O'
+    0b1111010001111101000010000, // 'P'
+    0b0111010001100010111000011, // 'Q'
+    0b1111010001111101000110001, // 'R'
+    0b1111110000111110000111111, // 'S'
+    0b1111100100001000010000100, // 'T'
+    0b1000110001100011000111111, // 'U'
+    0b1000110001010100101000100, // 'V'
+    0b1000110001101011010101010, // 'W'
+    0b1000101010001000101010001, // 'X'
+    0b1000110001010100010000100, // 'Y'
+    0b1111100010001000100011111, // 'Z'
+    0b0111001000010000100001110, // '['
+    0b1000001000001000001000001, // '\\'
+    0b0111000010000100001001110, // ']'
+    0b0010001010100010000000000, // '^'
+    0b0000000000000000000011111, // '_'
+    0b0100000100000000000000000, // '`'
+    0b0000001110100101001001111, // 'a'
+    0b1000010000111101000111110, // 'b'
+    0b0000001111100001000001111, // 'c'
+    0b0000100001011111000101111, // 'd'
+    0b0111010001111111000001110, // 'e'
+    0b0011001000111000100001000, // 'f'
+    0b0111110001011110000101110, // 'g'
+    0b1000010000111101000110001, // 'h'
+    0b0010000000001000010000100, // 'i'
+    0b0001000000000101001001100, // 'j'
+    0b1000010010111001001010001, // 'k'
+    0b0100001000010000100000110, // 'l'
+    0b0000011010101011010110101, // 'm'
+    0b0000011110100011000110001, // 'n'
+    0b0000001110100011000101110, // 'o'
+    0b0000011110100011111010000, // 'p'
+    0b0000001111100010111100001, // 'q'
+    0b0000010110110001000010000, // 'r'
+    0b0000001111011000001111110, // 's'
+    0b0100011100010000100000110, // 't'
+    0b0000010001100011001101101, // 'u'
+    0b0000010001100010101000100, // 'v'
+    0b0000010001101011010101010, // 'w'
+    0b0000010010011000110010010, // 'x'
+    0b1000110001011110000101110, // 'y'
+    0b0000011110001000100011110, // 'z'
+    0b0011000100011000010000110, // '{'
+    0b0010000100001000010000100, // '|'
+    0b0110000100001100010001100, // '}'
+    0b0000001000101010001000000, // '~'
+};
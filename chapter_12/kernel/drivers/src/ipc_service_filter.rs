@@ -0,0 +1,91 @@
+use kernel::debug;
+use kernel::platform::SyscallFilter;
+use kernel::process::Process;
+use kernel::syscall::Syscall;
+use kernel::ErrorCode;
+
+/// The IPC upcall number that a service subscribes to
+///
+/// Processes register as services by subscribing to upcall 0 of
+/// the IPC driver, the other upcalls are used by clients.
+const SERVICE_UPCALL: usize = 0;
+
+/// Structure representing the syscall filter
+///
+/// Tock's IPC identifies a service by the package name from the TBF
+/// header of the process that provides it. Any process can register
+/// a service callback, so a process that uses the name of another
+/// service could receive that service's requests and read its clients'
+/// shared buffers.
+///
+/// The filter binds each service name from its table to the only
+/// process loaded with that name. A process may register as a service
+/// only if its name is in the table and no other process uses the same
+/// name. All the other registrations are rejected and logged.
+pub struct IpcServiceFilter {
+    /// The array of processes that the kernel has loaded
+    processes: &'static [Option<&'static dyn Process>],
+
+    /// The names of the services that processes may register
+    services: &'static [&'static str],
+}
+
+impl IpcServiceFilter {
+    /// Initializes a new filter structure
+    pub fn new(
+        processes: &'static [Option<&'static dyn Process>],
+        services: &'static [&'static str],
+    ) -> Self {
+        IpcServiceFilter {
+            processes,
+            services,
+        }
+    }
+
+    /// Verifies that at most one loaded process uses `name`
+    pub(crate) fn is_unique(&self, name: &str) -> bool {
+        // Count the processes that use the same name
+        let count = self
+            .processes
+            .iter()
+            .filter_map(|process| *process)
+            .filter(|process| process.get_process_name() == name)
+            .count();
+        count <= 1
+    }
+
+    /// Verifies if `process` may register the service that uses its name
+    fn may_register(&self, process: &dyn Process) -> Result<(), &'static str> {
+        let name = process.get_process_name();
+        if !self.services.contains(&name) {
+            Err("unknown service")
+        } else if !self.is_unique(name) {
+            Err("name used by several processes")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SyscallFilter for IpcServiceFilter {
+    fn filter_syscall(&self, process: &dyn Process, syscall: &Syscall) -> Result<(), ErrorCode> {
+        match *syscall {
+            // The process registers itself as a service
+            Syscall::Subscribe {
+                driver_number: kernel::ipc::DRIVER_NUM,
+                subdriver_number: SERVICE_UPCALL,
+                ..
+            } => self.may_register(process).map_err(|reason| {
+                debug!(
+                    "IPC: rejected service {} registration from process {:?} ({})",
+                    process.get_process_name(),
+                    process.processid(),
+                    reason
+                );
+                ErrorCode::NODEVICE
+            }),
+            // All the other system calls are allowed
+            _ => Ok(()),
+        }
+    }
+}
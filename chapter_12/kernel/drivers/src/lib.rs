@@ -1,2 +1,11 @@
 #![forbid(unsafe_code)]
 #![no_std]
+
+/// The driver that replaces the text display service application.
+pub mod text_display_service;
+
+/// The syscall filter that binds IPC services to processes.
+pub mod ipc_service_filter;
+
+/// The syscall filter with a per-application allowlist.
+pub mod syscall_filter;
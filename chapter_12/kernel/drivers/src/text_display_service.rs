@@ -0,0 +1,253 @@
+use core::cell::Cell;
+use core::cmp;
+use core::mem;
+use font::{Font, Font5x5};
+use kernel::grant::Grant;
+use kernel::hil::led::Led;
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::process::{Error, ProcessId};
+use kernel::processbuffer::{ReadOnlyProcessBuffer, ReadableProcessBuffer};
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::utilities::cells::TakeCell;
+use kernel::ErrorCode;
+
+/// The driver number
+///
+/// As this is not one of Tock's standard drivers,
+/// its number has to be higher or equal to 0xa0000.
+///
+/// Our previous drivers used the numbers up to 0xa0004
+/// so we use the next number available.
+pub const DRIVER_NUM: usize = 0xa0005;
+
+/// The time in milliseconds that a letter or digit is displayed,
+/// the same as the delay of the *text_display.service* application
+const DELAY_MS: u32 = 300;
+
+/// The data type that will be stored in each
+/// process' grant.
+#[derive(Default)]
+pub struct AppData {
+    /// The buffer shared by the process with the driver
+    /// that contains the text that the driver should copy.
+    buffer: ReadOnlyProcessBuffer,
+}
+
+/// Structure representing the driver
+///
+/// The driver replaces the *text_display.service* IPC application.
+/// It copies the text shared by a process into its own buffer, notifies
+/// the process that it can reuse its buffer and displays the text in
+/// a loop until another process shares a new text.
+pub struct TextDisplayService<'a, L: Led, A: Alarm<'a>> {
+    /// the array of Matrix LEDs
+    /// LED 0 is upper left, LED 24 is lower right
+    leds: &'a [&'a L; 25],
+
+    /// The alarm used to implement the asynchronous deplay
+    alarm: &'a A,
+
+    /// The grant entrypoint
+    ///
+    /// The data type stored by the grant is `AppData` and
+    /// it can register up to 1 upcall.
+    ///
+    /// Upcall 0 receives the number of bytes copied from
+    /// the shared buffer.
+    grant: Grant<AppData, 1>,
+
+    /// The driver's copy of the text
+    buffer: TakeCell<'a, [u8]>,
+
+    /// The length of the text stored in the buffer
+    len: Cell<usize>,
+
+    /// The position within the buffer that the driver will
+    /// display next
+    position: Cell<usize>,
+
+    /// Stores whether the LEDs still show a letter or a digit
+    /// that has to be cleared
+    should_clear: Cell<bool>,
+}
+
+impl<'a, L: Led, A: Alarm<'a>> TextDisplayService<'a, L, A> {
+    /// Initializes a new driver structure
+    pub fn new(
+        leds: &'a [&'a L; 25],
+        alarm: &'a A,
+        buffer: &'a mut [u8],
+        grant: Grant<AppData, 1>,
+    ) -> Self {
+        TextDisplayService {
+            leds,
+            alarm,
+            grant,
+            buffer: TakeCell::new(buffer),
+            len: Cell::new(0),
+            position: Cell::new(0),
+            should_clear: Cell::new(false),
+        }
+    }
+
+    /// Copies the text from the buffer shared by the process and
+    /// notifies the process that it may use its buffer again
+    ///
+    /// The text ends at the first 0 byte or at the end of the shared
+    /// buffer and is truncated to the size of the driver's buffer.
+    fn copy(&self, process_id: ProcessId) -> Result<(), ErrorCode> {
+        self.grant
+            .enter(process_id, |app, upcalls| {
+                app.buffer
+                    .enter(|shared| {
+                        self.buffer.map_or(0, |buffer| {
+                            let max_len = cmp::min(shared.len(), buffer.len());
+                            let mut len = 0;
+                            while len < max_len && shared[len].get() != 0 {
+                                buffer[len] = shared[len].get();
+                                len = len + 1;
+                            }
+                            len
+                        })
+                    })
+                    .map(|len| {
+                        self.len.set(len);
+                        self.position.set(0);
+                        // Inform the process that the driver has
+                        // finished copying the data from the shared buffer.
+                        let _ = upcalls.schedule_upcall(0, (len, 0, 0));
+                    })
+                    .map_err(ErrorCode::from)
+            })
+            .map_err(ErrorCode::from)
+            .and_then(|res| res)
+    }
+
+    /// Displays the next letter or digit from the driver's buffer
+    ///
+    /// The text is displayed in a loop. Once the text is empty, the
+    /// LEDs are cleared and the driver stops its alarm.
+    fn display_next(&self) {
+        let len = self.len.get();
+        if len == 0 {
+            self.position.set(0);
+            if self.should_clear.get() {
+                self.should_clear.set(false);
+                self.clear();
+            }
+        } else {
+            let position = self.position.get();
+            self.buffer.map(|buffer| {
+                let _ = self.display(buffer[position] as char);
+            });
+            self.should_clear.set(true);
+            self.position.set((position + 1) % len);
+            // Set up an alarm for the next letter or digit.
+            self.alarm
+                .set_alarm(self.alarm.now(), self.alarm.ticks_from_ms(DELAY_MS));
+        }
+    }
+
+    /// Prints the a font `glyph` by setting LEDs
+    /// on and off depending on the glyph's bits
+    ///
+    /// A font glyph is a set of bits that represents that
+    /// state of the LEDs
+    fn print(&self, glyph: u32) {
+        for index in 0..25 {
+            match (glyph >> (24 - index)) & 0x01 {
+                0 => self.leds[index].off(),
+                _ => self.leds[index].on(),
+            }
+        }
+    }
+
+    /// Clears the displayed glyph by turning off
+    /// all the LEDs
+    fn clear(&self) {
+        for index in 0..25 {
+            self.leds[index].off();
+        }
+    }
+
+    /// Displays a character
+    fn display(&self, character: char) -> Result<(), ErrorCode> {
+        match Font5x5.glyph(character) {
+            Some(glyph) => {
+                self.print(glyph.bits());
+                Ok(())
+            }
+            None => {
+                self.clear();
+                Err(ErrorCode::INVAL)
+            }
+        }
+    }
+}
+
+/// The implementation of `SyscallDriver` makes `TextDisplayService` a syscall driver
+impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for TextDisplayService<'a, L, A> {
+    fn allow_readonly(
+        &self,
+        process_id: ProcessId,
+        allow_number: usize,
+        mut buffer: ReadOnlyProcessBuffer,
+    ) -> Result<ReadOnlyProcessBuffer, (ReadOnlyProcessBuffer, ErrorCode)> {
+        match allow_number {
+            // The process has shared or unshared (if buffer is indirectly None) a buffer with us
+            0 => {
+                let res = self.grant.enter(process_id, |app, _| {
+                    mem::swap(&mut app.buffer, &mut buffer);
+                });
+                match res {
+                    Ok(()) => Ok(buffer),
+                    Err(err) => Err((buffer, err.into())),
+                }
+            }
+            // We only know what to do with buffer number 0
+            _ => Err((buffer, ErrorCode::NOSUPPORT)),
+        }
+    }
+
+    fn allocate_grant(&self, process_id: ProcessId) -> Result<(), Error> {
+        self.grant.enter(process_id, |_, _| {})
+    }
+
+    fn command(
+        &self,
+        command_number: usize,
+        _r2: usize,
+        _r3: usize,
+        process_id: ProcessId,
+    ) -> CommandReturn {
+        match command_number {
+            // Tock's convention states that all syscall drivers must return *success* or *success_...* for
+            // command number 0. This allows processes to verify if a driver is present.
+            0 => CommandReturn::success(),
+            // Copy and display the text from the buffer shared with allow number 0
+            //
+            // This is the equivalent of the process notifying the service.
+            1 => match self.copy(process_id) {
+                Ok(()) => {
+                    // Start displaying the text if the driver was idle,
+                    // otherwise the new text continues the current loop.
+                    if !self.alarm.is_armed() {
+                        self.display_next();
+                    }
+                    CommandReturn::success()
+                }
+                Err(err) => CommandReturn::failure(err),
+            },
+            // Inform the process that we do not understand the command
+            _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
+        }
+    }
+}
+
+/// This implementation allows `TextDisplayService` to use an alarm.
+impl<'a, L: Led, A: Alarm<'a>> AlarmClient for TextDisplayService<'a, L, A> {
+    /// Called when the alarm expires
+    fn alarm(&self) {
+        self.display_next();
+    }
+}
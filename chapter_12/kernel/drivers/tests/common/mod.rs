@@ -0,0 +1,9 @@
+//! The fixtures shared by the tests of the drivers.
+//!
+//! Each test file uses only some of them.
+#![allow(dead_code)]
+
+/// Leaks `value` so that it lives forever
+pub fn leak<T>(value: T) -> &'static T {
+    Box::leak(Box::new(value))
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use common::leak;
+use drivers::ipc_service_filter::IpcServiceFilter;
+use drivers::syscall_filter::{AllowlistSyscallFilter, Permission};
+use kernel::platform::SyscallFilter;
+use kernel::syscall::Syscall;
+use kernel::ErrorCode;
+use mock_hil::{capture_debug, MockKernel};
+
+/// The driver that the permissions restrict
+const DRIVER_NUM: usize = 0xa0005;
+
+/// A driver that no permission restricts
+const OTHER_DRIVER_NUM: usize = 0x30000;
+
+/// Allows the example application to use commands 0 to 2 and 5 to 6
+const PERMISSIONS: [Permission; 2] = [
+    Permission::new("example_app", DRIVER_NUM, 0..=2),
+    Permission::new("example_app", DRIVER_NUM, 5..=6),
+];
+
+/// Creates a filter for the processes that `kernel` has loaded
+fn filter(kernel: &MockKernel) -> AllowlistSyscallFilter {
+    AllowlistSyscallFilter::new(
+        leak(PERMISSIONS),
+        IpcServiceFilter::new(kernel.processes(), &[]),
+    )
+}
+
+/// Builds the command `subdriver_number` of the driver `driver_number`
+fn command(driver_number: usize, subdriver_number: usize) -> Syscall {
+    Syscall::Command {
+        driver_number,
+        subdriver_number,
+        arg0: 0,
+        arg1: 0,
+    }
+}
+
+/// Builds a subscribe to the upcall 0 of the driver `driver_number`
+fn subscribe(driver_number: usize) -> Syscall {
+    Syscall::Subscribe {
+        driver_number,
+        subdriver_number: 0,
+        upcall_ptr: core::ptr::null_mut(),
+        appdata: 0,
+    }
+}
+
+#[test]
+fn a_command_in_the_first_range_is_allowed() {
+    let kernel = MockKernel::with_processes(&["example_app"]);
+    let filter = filter(&kernel);
+
+    assert_eq!(
+        filter.filter_syscall(kernel.process(0), &command(DRIVER_NUM, 1)),
+        Ok(())
+    );
+    assert_eq!(filter.denied(), 0);
+}
+
+#[test]
+fn a_command_in_a_later_range_is_allowed() {
+    let kernel = MockKernel::with_processes(&["example_app"]);
+    let filter = filter(&kernel);
+
+    // The first permission does not match, the second one does
+    assert_eq!(
+        filter.filter_syscall(kernel.process(0), &command(DRIVER_NUM, 5)),
+        Ok(())
+    );
+    assert_eq!(filter.denied(), 0);
+}
+
+#[test]
+fn a_command_outside_the_ranges_is_denied() {
+    let kernel = MockKernel::with_processes(&["example_app"]);
+    let filter = filter(&kernel);
+    let debug = capture_debug();
+
+    assert_eq!(
+        filter.filter_syscall(kernel.process(0), &command(DRIVER_NUM, 3)),
+        Err(ErrorCode::NODEVICE)
+    );
+    assert_eq!(filter.denied(), 1);
+    assert!(debug.text().contains("Denied access to driver 0xa0005"));
+}
+
+#[test]
+fn another_application_is_denied() {
+    let kernel = MockKernel::with_processes(&["example_app", "other_app"]);
+    let filter = filter(&kernel);
+    let _debug = capture_debug();
+
+    assert_eq!(
+        filter.filter_syscall(kernel.process(1), &command(DRIVER_NUM, 1)),
+        Err(ErrorCode::NODEVICE)
+    );
+    assert_eq!(
+        filter.filter_syscall(kernel.process(1), &subscribe(DRIVER_NUM)),
+        Err(ErrorCode::NODEVICE)
+    );
+    assert_eq!(filter.denied(), 2);
+}
+
+#[test]
+fn a_subscribe_only_needs_a_permission_for_the_driver() {
+    let kernel = MockKernel::with_processes(&["example_app"]);
+    let filter = filter(&kernel);
+
+    assert_eq!(
+        filter.filter_syscall(kernel.process(0), &subscribe(DRIVER_NUM)),
+        Ok(())
+    );
+}
+
+#[test]
+fn an_unrestricted_driver_is_allowed() {
+    let kernel = MockKernel::with_processes(&["other_app"]);
+    let filter = filter(&kernel);
+
+    assert_eq!(
+        filter.filter_syscall(kernel.process(0), &command(OTHER_DRIVER_NUM, 3)),
+        Ok(())
+    );
+    assert_eq!(filter.denied(), 0);
+}
+
+#[test]
+fn processes_that_share_a_name_are_denied() {
+    let kernel = MockKernel::with_processes(&["example_app", "example_app"]);
+    let filter = filter(&kernel);
+    let _debug = capture_debug();
+
+    for index in 0..2 {
+        assert_eq!(
+            filter.filter_syscall(kernel.process(index), &command(DRIVER_NUM, 1)),
+            Err(ErrorCode::NODEVICE)
+        );
+    }
+    assert_eq!(filter.denied(), 2);
+}
+
+#[test]
+fn denied_counts_only_the_denied_system_calls() {
+    let kernel = MockKernel::with_processes(&["example_app", "other_app"]);
+    let filter = filter(&kernel);
+    let _debug = capture_debug();
+
+    let _ = filter.filter_syscall(kernel.process(0), &command(DRIVER_NUM, 0));
+    let _ = filter.filter_syscall(kernel.process(0), &command(DRIVER_NUM, 4));
+    let _ = filter.filter_syscall(kernel.process(1), &command(DRIVER_NUM, 0));
+    let _ = filter.filter_syscall(kernel.process(1), &command(OTHER_DRIVER_NUM, 0));
+
+    assert_eq!(filter.denied(), 2);
+}
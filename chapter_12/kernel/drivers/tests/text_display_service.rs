@@ -0,0 +1,194 @@
+mod common;
+
+use common::leak;
+use drivers::text_display_service::{TextDisplayService, DRIVER_NUM};
+use font::{Font, Font5x5};
+use kernel::hil::time::{Alarm, AlarmClient};
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::ErrorCode;
+use mock_hil::{MockAlarm, MockKernel, MockLed, MockMatrix};
+
+type Service = TextDisplayService<'static, MockLed, MockAlarm<'static>>;
+
+/// The time that a character is displayed, in milliseconds
+const DELAY_MS: u32 = 300;
+
+/// The size of the driver's copy of the text
+const BUFFER_LEN: usize = 50;
+
+/// The index of a process that the kernel has not loaded
+const NOT_LOADED: usize = 2;
+
+/// Returns the glyph of `character` the way the matrix displays it
+fn glyph(character: char) -> u32 {
+    Font5x5.glyph(character).unwrap().bits()
+}
+
+/// The kernel, the LED matrix, the alarm and the driver
+///
+/// The kernel has loaded two processes.
+struct Board {
+    kernel: MockKernel,
+    matrix: &'static MockMatrix,
+    alarm: &'static MockAlarm<'static>,
+    driver: &'static Service,
+}
+
+impl Board {
+    fn new() -> Board {
+        let kernel = MockKernel::with_processes(&["example_app", "other_app"]);
+        let matrix = leak(MockMatrix::default());
+        let leds: &'static [&'static MockLed; 25] = leak(matrix.leds());
+        let alarm = leak(MockAlarm::default());
+        let buffer: &'static mut [u8] = Box::leak(vec![0; BUFFER_LEN].into_boxed_slice());
+        let driver = leak(TextDisplayService::new(
+            leds,
+            alarm,
+            buffer,
+            kernel.create_grant(DRIVER_NUM),
+        ));
+        alarm.set_alarm_client(driver);
+        Board {
+            kernel,
+            matrix,
+            alarm,
+            driver,
+        }
+    }
+
+    /// Issues the command `command_number` for the process at `index`
+    fn command(&self, index: usize, command_number: usize) -> CommandReturn {
+        self.driver
+            .command(command_number, 0, 0, self.kernel.process_id(index))
+    }
+
+    /// Shares `text` and subscribes to the upcall, the way the
+    /// process' library does, and notifies the driver
+    fn show(&self, index: usize, text: &[u8]) -> CommandReturn {
+        let process_id = self.kernel.process_id(index);
+        let buffer = self.kernel.process(index).readonly_buffer(text);
+        assert!(self.driver.allow_readonly(process_id, 0, buffer).is_ok());
+        assert_eq!(self.driver.allocate_grant(process_id), Ok(()));
+        self.kernel.process(index).subscribe(DRIVER_NUM, 0);
+        self.command(index, 1)
+    }
+
+    /// Verifies that the matrix displays `text`, one character
+    /// every `DELAY_MS` milliseconds
+    fn assert_displays(&self, text: &str) {
+        for (position, character) in text.chars().enumerate() {
+            if position > 0 {
+                assert!(self.alarm.advance(DELAY_MS));
+            }
+            assert_eq!(
+                self.matrix.state(),
+                glyph(character),
+                "{:?} in {:?}",
+                character,
+                text
+            );
+        }
+    }
+}
+
+#[test]
+fn command_0_reports_the_driver_as_present() {
+    let board = Board::new();
+
+    assert!(board.command(0, 0).is_success());
+}
+
+#[test]
+fn command_1_fails_for_a_process_that_is_not_running() {
+    let board = Board::new();
+
+    // The driver cannot copy the text of the process, so
+    // it does not start a display.
+    assert!(board.command(NOT_LOADED, 1).is_failure());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.changes(), 0);
+}
+
+#[test]
+fn alarm_without_a_text_is_ignored() {
+    let board = Board::new();
+
+    board.driver.alarm();
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.changes(), 0);
+}
+
+#[test]
+fn unknown_commands_are_not_supported() {
+    let board = Board::new();
+
+    let ret = board.command(0, 100);
+    assert_eq!(ret.get_failure(), Some(ErrorCode::NOSUPPORT));
+}
+
+#[test]
+fn the_text_is_copied_up_to_the_first_0_byte() {
+    let board = Board::new();
+
+    assert!(board.show(0, b"AB\0C").is_success());
+
+    assert_eq!(board.kernel.process(0).take_upcalls(), [(0, (2, 0, 0))]);
+    board.assert_displays("ABAB");
+}
+
+#[test]
+fn the_text_is_truncated_to_the_driver_buffer() {
+    let board = Board::new();
+
+    assert!(board.show(0, &[b'A'; BUFFER_LEN + 10]).is_success());
+
+    assert_eq!(
+        board.kernel.process(0).take_upcalls(),
+        [(0, (BUFFER_LEN, 0, 0))]
+    );
+}
+
+#[test]
+fn the_text_is_displayed_in_a_loop() {
+    let board = Board::new();
+
+    assert!(board.show(0, b"HI").is_success());
+
+    assert_eq!(board.matrix.state(), glyph('H'));
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS));
+    board.assert_displays("HIHIH");
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS));
+}
+
+#[test]
+fn a_new_text_continues_the_current_loop() {
+    let board = Board::new();
+    assert!(board.show(0, b"HI").is_success());
+    assert!(!board.alarm.advance(DELAY_MS / 2));
+
+    assert!(board.show(1, b"OK").is_success());
+
+    // The first character of the previous text stays
+    // for the rest of its delay.
+    assert_eq!(board.matrix.state(), glyph('H'));
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS / 2));
+    assert!(board.alarm.advance(DELAY_MS / 2));
+    board.assert_displays("OKO");
+    assert_eq!(board.kernel.process(1).take_upcalls(), [(0, (2, 0, 0))]);
+}
+
+#[test]
+fn an_empty_text_clears_the_leds_and_stops_the_loop() {
+    let board = Board::new();
+    assert!(board.show(0, b"HI").is_success());
+
+    assert!(board.show(0, b"\0").is_success());
+    assert_eq!(
+        board.kernel.process(0).take_upcalls().last(),
+        Some(&(0, (0, 0, 0)))
+    );
+    assert!(board.alarm.advance(DELAY_MS));
+
+    assert_eq!(board.matrix.state(), 0);
+    assert!(!board.alarm.is_armed());
+}
@@ -8,6 +8,7 @@
 #![cfg_attr(not(doc), no_main)]
 #![deny(missing_docs)]
 
+use capsules::led_matrix::LedMatrixLed;
 use kernel::capabilities;
 use kernel::component::Component;
 use kernel::dynamic_deferred_call::{DynamicDeferredCall, DynamicDeferredCallClientState};
@@ -66,6 +67,28 @@ const FAULT_RESPONSE: kernel::process::PanicFaultPolicy = kernel::process::Panic
 const NUM_PROCS: usize = 4;
 const NUM_UPCALLS_IPC: usize = NUM_PROCS + 1;
 
+// The IPC services that processes may register, each one is bound
+// to the process whose TBF header has the service's name.
+const IPC_SERVICES: [&str; 1] = ["text_display.service"];
+
+// The drivers that only some applications may use, each entry allows
+// an application to use a range of commands of a driver.
+const SYSCALL_PERMISSIONS: [drivers::syscall_filter::Permission; 2] = [
+    // Only the display service drives the LEDs of the matrix.
+    drivers::syscall_filter::Permission::new(
+        "text_display.service",
+        capsules::led_matrix::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+    // Only the example application displays texts with the
+    // kernel's display service.
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::text_display_service::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+];
+
 static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PROCS] =
     [None; NUM_PROCS];
 
@@ -110,6 +133,19 @@ pub struct MicroBit {
     app_flash: &'static capsules::app_flash_driver::AppFlash<'static>,
     sound_pressure: &'static capsules::sound_pressure::SoundPressureSensor<'static>,
 
+    /// Add the `TextDisplayService` driver to the board implementation structure.
+    text_display_service: &'static drivers::text_display_service::TextDisplayService<
+        'static,
+        LedMatrixLed<
+            'static,
+            nrf52::gpio::GPIOPin<'static>,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc<'static>>,
+    >,
+
+    syscall_filter: &'static drivers::syscall_filter::AllowlistSyscallFilter,
+
     scheduler: &'static RoundRobinSched<'static>,
     systick: cortexm4::systick::SysTick,
 }
@@ -135,6 +171,8 @@ impl SyscallDriverLookup for MicroBit {
             capsules::app_flash_driver::DRIVER_NUM => f(Some(self.app_flash)),
             capsules::sound_pressure::DRIVER_NUM => f(Some(self.sound_pressure)),
             kernel::ipc::DRIVER_NUM => f(Some(&self.ipc)),
+            // Register the `TextDisplayService` driver with the kernel.
+            drivers::text_display_service::DRIVER_NUM => f(Some(self.text_display_service)),
             _ => f(None),
         }
     }
@@ -144,7 +182,7 @@ impl KernelResources<nrf52833::chip::NRF52<'static, Nrf52833DefaultPeripherals<'
     for MicroBit
 {
     type SyscallDriverLookup = Self;
-    type SyscallFilter = ();
+    type SyscallFilter = drivers::syscall_filter::AllowlistSyscallFilter;
     type ProcessFault = ();
     type Scheduler = RoundRobinSched<'static>;
     type SchedulerTimer = cortexm4::systick::SysTick;
@@ -154,7 +192,7 @@ impl KernelResources<nrf52833::chip::NRF52<'static, Nrf52833DefaultPeripherals<'
         &self
     }
     fn syscall_filter(&self) -> &Self::SyscallFilter {
-        &()
+        self.syscall_filter
     }
     fn process_fault(&self) -> &Self::ProcessFault {
         &()
@@ -556,6 +594,72 @@ pub unsafe fn main() {
         nrf52::rtc::Rtc<'static>
     ));
 
+    //--------------------------------------------------------------------------
+    // Text Display Service
+    //--------------------------------------------------------------------------
+
+    // Initialize a virtual alarm for the TextDisplayService driver
+    let virtual_alarm_text_display_service = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52833::rtc::Rtc>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // The driver's copy of the text, the same size as the buffer
+    // of the text_display.service application
+    let text_display_service_buffer = static_init!([u8; 50], [0; 50]);
+
+    let text_display_service = static_init!(
+        drivers::text_display_service::TextDisplayService<
+            'static,
+            LedMatrixLed<
+                'static,
+                nrf52::gpio::GPIOPin<'static>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+            >,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+        >,
+        drivers::text_display_service::TextDisplayService::new(
+            components::led_matrix_leds!(
+                nrf52::gpio::GPIOPin<'static>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, nrf52::rtc::Rtc<'static>>,
+                led,
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (0, 3),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (4, 3),
+                (0, 4),
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4)
+            ),
+            virtual_alarm_text_display_service,
+            text_display_service_buffer,
+            board_kernel.create_grant(
+                drivers::text_display_service::DRIVER_NUM,
+                &memory_allocation_capability
+            )
+        ),
+    );
+
+    virtual_alarm_text_display_service.set_alarm_client(text_display_service);
+
     //--------------------------------------------------------------------------
     // Process Console
     //--------------------------------------------------------------------------
@@ -576,6 +680,17 @@ pub unsafe fn main() {
     while !base_peripherals.clock.low_started() {}
     while !base_peripherals.clock.high_started() {}
 
+    // Only the applications allowed by the permissions may use the
+    // restricted drivers and only the processes bound to the IPC services
+    // may register them.
+    let syscall_filter = static_init!(
+        drivers::syscall_filter::AllowlistSyscallFilter,
+        drivers::syscall_filter::AllowlistSyscallFilter::new(
+            &SYSCALL_PERMISSIONS,
+            drivers::ipc_service_filter::IpcServiceFilter::new(&PROCESSES, &IPC_SERVICES)
+        )
+    );
+
     let scheduler = components::sched::round_robin::RoundRobinComponent::new(&PROCESSES)
         .finalize(components::rr_component_helper!(NUM_PROCS));
 
@@ -594,12 +709,15 @@ pub unsafe fn main() {
         adc: adc_syscall,
         alarm,
         app_flash,
+        text_display_service,
         ipc: kernel::ipc::IPC::new(
             board_kernel,
             kernel::ipc::DRIVER_NUM,
             &memory_allocation_capability,
         ),
 
+        syscall_filter,
+
         scheduler,
         systick: cortexm4::systick::SysTick::new_with_calibration(64000000),
     };
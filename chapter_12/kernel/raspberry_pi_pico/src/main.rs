@@ -9,12 +9,14 @@
 #![deny(missing_docs)]
 #![feature(asm, naked_functions)]
 
+use capsules::led_matrix::LedMatrixLed;
 use capsules::virtual_alarm::VirtualMuxAlarm;
 use components::gpio::GpioComponent;
 use enum_primitive::cast::FromPrimitive;
 use kernel::component::Component;
 use kernel::debug;
 use kernel::dynamic_deferred_call::{DynamicDeferredCall, DynamicDeferredCallClientState};
+use kernel::hil::time::Alarm;
 use kernel::platform::{KernelResources, SyscallDriverLookup};
 use kernel::scheduler::round_robin::RoundRobinSched;
 use kernel::syscall::SyscallDriver;
@@ -55,6 +57,28 @@ const FAULT_RESPONSE: kernel::process::PanicFaultPolicy = kernel::process::Panic
 const NUM_PROCS: usize = 4;
 const NUM_UPCALLS_IPC: usize = NUM_PROCS + 1;
 
+// The IPC services that processes may register, each one is bound
+// to the process whose TBF header has the service's name.
+const IPC_SERVICES: [&str; 1] = ["text_display.service"];
+
+// The drivers that only some applications may use, each entry allows
+// an application to use a range of commands of a driver.
+const SYSCALL_PERMISSIONS: [drivers::syscall_filter::Permission; 2] = [
+    // Only the display service drives the LEDs of the matrix.
+    drivers::syscall_filter::Permission::new(
+        "text_display.service",
+        capsules::led_matrix::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+    // Only the example application displays texts with the
+    // kernel's display service.
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::text_display_service::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+];
+
 static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PROCS] =
     [None; NUM_PROCS];
 
@@ -75,6 +99,15 @@ pub struct RaspberryPiPico {
     adc: &'static capsules::adc::AdcVirtualized<'static>,
     temperature: &'static capsules::temperature::TemperatureSensor<'static>,
 
+    /// Add the `TextDisplayService` driver to the board implementation structure.
+    text_display_service: &'static drivers::text_display_service::TextDisplayService<
+        'static,
+        LedMatrixLed<'static, RPGpioPin<'static>, VirtualMuxAlarm<'static, RPTimer<'static>>>,
+        VirtualMuxAlarm<'static, RPTimer<'static>>,
+    >,
+
+    syscall_filter: &'static drivers::syscall_filter::AllowlistSyscallFilter,
+
     scheduler: &'static RoundRobinSched<'static>,
     systick: cortexm0p::systick::SysTick,
 }
@@ -92,6 +125,8 @@ impl SyscallDriverLookup for RaspberryPiPico {
             kernel::ipc::DRIVER_NUM => f(Some(&self.ipc)),
             capsules::adc::DRIVER_NUM => f(Some(self.adc)),
             capsules::temperature::DRIVER_NUM => f(Some(self.temperature)),
+            // Register the `TextDisplayService` driver with the kernel.
+            drivers::text_display_service::DRIVER_NUM => f(Some(self.text_display_service)),
             _ => f(None),
         }
     }
@@ -99,7 +134,7 @@ impl SyscallDriverLookup for RaspberryPiPico {
 
 impl KernelResources<Rp2040<'static, Rp2040DefaultPeripherals<'static>>> for RaspberryPiPico {
     type SyscallDriverLookup = Self;
-    type SyscallFilter = ();
+    type SyscallFilter = drivers::syscall_filter::AllowlistSyscallFilter;
     type ProcessFault = ();
     type Scheduler = RoundRobinSched<'static>;
     type SchedulerTimer = cortexm0p::systick::SysTick;
@@ -109,7 +144,7 @@ impl KernelResources<Rp2040<'static, Rp2040DefaultPeripherals<'static>>> for Ras
         &self
     }
     fn syscall_filter(&self) -> &Self::SyscallFilter {
-        &()
+        self.syscall_filter
     }
     fn process_fault(&self) -> &Self::ProcessFault {
         &()
@@ -444,12 +479,87 @@ pub unsafe fn main() {
         RPTimer<'static>
     ));
 
+    // TEXT DISPLAY SERVICE
+
+    // Initialize a virtual alarm for the TextDisplayService driver
+    let virtual_alarm_text_display_service = static_init!(
+        capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        capsules::virtual_alarm::VirtualMuxAlarm::new(mux_alarm)
+    );
+
+    // The driver's copy of the text, the same size as the buffer
+    // of the text_display.service application
+    let text_display_service_buffer = static_init!([u8; 50], [0; 50]);
+
+    let text_display_service = static_init!(
+        drivers::text_display_service::TextDisplayService<
+            'static,
+            LedMatrixLed<
+                'static,
+                RPGpioPin<'static>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+            >,
+            capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+        >,
+        drivers::text_display_service::TextDisplayService::new(
+            components::led_matrix_leds!(
+                RPGpioPin<'static>,
+                capsules::virtual_alarm::VirtualMuxAlarm<'static, RPTimer<'static>>,
+                led_matrix_driver,
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (0, 3),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (4, 3),
+                (0, 4),
+                (1, 4),
+                (2, 4),
+                (3, 4),
+                (4, 4)
+            ),
+            virtual_alarm_text_display_service,
+            text_display_service_buffer,
+            board_kernel.create_grant(
+                drivers::text_display_service::DRIVER_NUM,
+                &memory_allocation_capability
+            )
+        )
+    );
+
+    virtual_alarm_text_display_service.set_alarm_client(text_display_service);
+
     // PROCESS CONSOLE
     let process_console =
         components::process_console::ProcessConsoleComponent::new(board_kernel, uart_mux)
             .finalize(());
     let _ = process_console.start();
 
+    // Only the applications allowed by the permissions may use the
+    // restricted drivers and only the processes bound to the IPC services
+    // may register them.
+    let syscall_filter = static_init!(
+        drivers::syscall_filter::AllowlistSyscallFilter,
+        drivers::syscall_filter::AllowlistSyscallFilter::new(
+            &SYSCALL_PERMISSIONS,
+            drivers::ipc_service_filter::IpcServiceFilter::new(&PROCESSES, &IPC_SERVICES)
+        )
+    );
+
     let scheduler = components::sched::round_robin::RoundRobinComponent::new(&PROCESSES)
         .finalize(components::rr_component_helper!(NUM_PROCS));
 
@@ -465,6 +575,9 @@ pub unsafe fn main() {
         console,
         adc: adc_syscall,
         temperature: temp,
+        text_display_service,
+
+        syscall_filter,
 
         scheduler,
         systick: cortexm0p::systick::SysTick::new_with_calibration(125_000_000),
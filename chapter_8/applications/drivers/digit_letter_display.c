@@ -24,3 +24,14 @@ bool digit_letter_display_show_character (char digit_or_letter) {
     return false;
   }
 }
+
+bool digit_letter_display_show_image (uint32_t image) {
+  // Send command number 2 to the driver with argument 1 (r2) set
+  // to the image to display.
+  syscall_return_t ret = command (DRIVER_NUM_DIGIT_LETTER_DISPLAY, 2, image, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return true;
+  } else {
+    return false;
+  }
+}
@@ -17,9 +17,14 @@ extern "C" {
 // Verifies if the driver is present
 bool digit_letter_display_is_present (void);
 
-// Displays a letter or a digit
+// Displays a printable ASCII character or the degree sign, which
+// is the Latin-1 byte '\xb0' and not the UTF-8 "°"
 bool digit_letter_display_show_character (char digit_or_letter);
 
+// Displays a 25 bit image, the most significant bit is
+// the upper left LED
+bool digit_letter_display_show_image (uint32_t image);
+
 #ifdef __cplusplus
 }
 #endif
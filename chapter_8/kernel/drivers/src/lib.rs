@@ -3,3 +3,6 @@
 
 /// The driver that displays letters or digits.
 pub mod digit_letter_display;
+
+/// The syscall filter that restricts the display drivers to allowed applications.
+pub mod syscall_filter;
@@ -0,0 +1,88 @@
+use drivers::digit_letter_display::DigitLetterDisplay;
+use font::{Font, Font5x5};
+use kernel::syscall::SyscallDriver;
+use kernel::ErrorCode;
+use mock_hil::{MockKernel, MockMatrix};
+
+#[test]
+fn command_0_reports_the_driver_as_present() {
+    let kernel = MockKernel::new();
+    let matrix = MockMatrix::default();
+    let leds = matrix.leds();
+    let driver = DigitLetterDisplay::new(&leds);
+
+    assert!(driver.command(0, 0, 0, kernel.process_id(0)).is_success());
+    // Verifying the presence does not touch the LEDs
+    assert_eq!(matrix.changes(), 0);
+}
+
+#[test]
+fn command_1_displays_the_glyph_of_the_character() {
+    let kernel = MockKernel::new();
+    let matrix = MockMatrix::default();
+    let leds = matrix.leds();
+    let driver = DigitLetterDisplay::new(&leds);
+
+    for character in (' '..='~').chain(core::iter::once('\u{b0}')) {
+        let ret = driver.command(1, character as usize, 0, kernel.process_id(0));
+        assert!(ret.is_success(), "displaying {:?}", character);
+        assert_eq!(
+            matrix.state(),
+            Font5x5.glyph(character).unwrap().bits(),
+            "LEDs after displaying {:?}",
+            character
+        );
+    }
+}
+
+#[test]
+fn command_1_clears_the_matrix_for_unknown_characters() {
+    let kernel = MockKernel::new();
+    let matrix = MockMatrix::default();
+    let leds = matrix.leds();
+    let driver = DigitLetterDisplay::new(&leds);
+
+    assert!(driver
+        .command(1, 'A' as usize, 0, kernel.process_id(0))
+        .is_success());
+    let ret = driver.command(1, '\n' as usize, 0, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+    assert_eq!(matrix.state(), 0);
+}
+
+#[test]
+fn command_2_displays_the_image() {
+    let kernel = MockKernel::new();
+    let matrix = MockMatrix::default();
+    let leds = matrix.leds();
+    let driver = DigitLetterDisplay::new(&leds);
+
+    let image = 0b10001_01010_00100_01010_10001;
+    assert!(driver
+        .command(2, image, 0, kernel.process_id(0))
+        .is_success());
+    assert_eq!(matrix.state(), image as u32);
+}
+
+#[test]
+fn command_2_rejects_images_larger_than_the_matrix() {
+    let kernel = MockKernel::new();
+    let matrix = MockMatrix::default();
+    let leds = matrix.leds();
+    let driver = DigitLetterDisplay::new(&leds);
+
+    let ret = driver.command(2, 1 << 25, 0, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+    assert_eq!(matrix.changes(), 0);
+}
+
+#[test]
+fn unknown_commands_are_not_supported() {
+    let kernel = MockKernel::new();
+    let matrix = MockMatrix::default();
+    let leds = matrix.leds();
+    let driver = DigitLetterDisplay::new(&leds);
+
+    let ret = driver.command(3, 0, 0, kernel.process_id(0));
+    assert_eq!(ret.get_failure(), Some(ErrorCode::NOSUPPORT));
+}
@@ -67,6 +67,17 @@ const FAULT_RESPONSE: kernel::process::PanicFaultPolicy = kernel::process::Panic
 const NUM_PROCS: usize = 4;
 const NUM_UPCALLS_IPC: usize = NUM_PROCS + 1;
 
+// The drivers that only some applications may use, each entry allows
+// an application to use a range of commands of a driver.
+const SYSCALL_PERMISSIONS: [drivers::syscall_filter::Permission; 1] = [
+    // Only the example application drives the display.
+    drivers::syscall_filter::Permission::new(
+        "example_app",
+        drivers::digit_letter_display::DRIVER_NUM,
+        0..=usize::MAX,
+    ),
+];
+
 static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PROCS] =
     [None; NUM_PROCS];
 
@@ -111,6 +122,8 @@ pub struct MicroBit {
     app_flash: &'static capsules::app_flash_driver::AppFlash<'static>,
     sound_pressure: &'static capsules::sound_pressure::SoundPressureSensor<'static>,
 
+    syscall_filter: &'static drivers::syscall_filter::AllowlistSyscallFilter,
+
     scheduler: &'static RoundRobinSched<'static>,
     systick: cortexm4::systick::SysTick,
 
@@ -157,7 +170,7 @@ impl KernelResources<nrf52833::chip::NRF52<'static, Nrf52833DefaultPeripherals<'
     for MicroBit
 {
     type SyscallDriverLookup = Self;
-    type SyscallFilter = ();
+    type SyscallFilter = drivers::syscall_filter::AllowlistSyscallFilter;
     type ProcessFault = ();
     type Scheduler = RoundRobinSched<'static>;
     type SchedulerTimer = cortexm4::systick::SysTick;
@@ -167,7 +180,7 @@ impl KernelResources<nrf52833::chip::NRF52<'static, Nrf52833DefaultPeripherals<'
         &self
     }
     fn syscall_filter(&self) -> &Self::SyscallFilter {
-        &()
+        self.syscall_filter
     }
     fn process_fault(&self) -> &Self::ProcessFault {
         &()
@@ -640,6 +653,13 @@ pub unsafe fn main() {
         ))
     );
 
+    // Only the applications allowed by the permissions may use the
+    // restricted drivers.
+    let syscall_filter = static_init!(
+        drivers::syscall_filter::AllowlistSyscallFilter,
+        drivers::syscall_filter::AllowlistSyscallFilter::new(&PROCESSES, &SYSCALL_PERMISSIONS)
+    );
+
     let scheduler = components::sched::round_robin::RoundRobinComponent::new(&PROCESSES)
         .finalize(components::rr_component_helper!(NUM_PROCS));
 
@@ -664,6 +684,8 @@ pub unsafe fn main() {
             &memory_allocation_capability,
         ),
 
+        syscall_filter,
+
         scheduler,
         systick: cortexm4::systick::SysTick::new_with_calibration(64000000),
 
@@ -6,6 +6,12 @@
 // used by the asynchronous API
 static text_display_done_t *done_callback = NULL;
 static void * done_callback_args = NULL;
+static text_display_pass_t *pass_callback = NULL;
+static void * pass_callback_args = NULL;
+
+// The time the LEDs are off between two characters or frames,
+// sent to the driver before each display
+static unsigned int display_gap_ms = 0;
 
 // used by the synchronous API
 typedef struct {
@@ -54,15 +60,57 @@ void text_display_set_done_callback (text_display_done_t callback, void *callbac
   done_callback_args = callback_args;
 }
 
+// A process will use this function to set a callback function
+// to be called each time the driver has displayed the whole text.
+void text_display_set_pass_callback (text_display_pass_t callback, void *callback_args) {
+  pass_callback      = callback;
+  pass_callback_args = callback_args;
+}
+
+// Set the time the LEDs are off between two characters or frames
+// for the following displays
+returncode_t text_display_set_gap (unsigned int gap_ms) {
+  // The driver only keeps the gap for the next display, so
+  // the library sends it again before each display.
+  display_gap_ms = gap_ms;
+  return RETURNCODE_SUCCESS;
+}
+
+// Send the settings of the next display to the driver, as
+// they only apply to the display that follows them
+static returncode_t text_display_set_next (unsigned int repeat) {
+  // Send command 7 to the driver to set the repeat count
+  syscall_return_t ret = text_display_command (7, repeat, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    // Send command 8 to the driver to set the gap
+    ret = text_display_command (8, display_gap_ms, 0);
+  }
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
+
 // The library registers this function with the driver for the asynchronous calls.
 // The driver will call this function when a display action is done.
 ///
 // We use this aproach to make sure that the shared buffer 
 // is unallowed each time when an action is done.
-static void text_displayed (int status, __attribute__ ((unused)) int unused2, __attribute__ (
-                              (unused)) int unused3, __attribute__ ((unused)) void *user_data) {
-  // Unallow the buffer so that we can access it.
+//
+// The driver also calls it after each pass, in which case done is 0
+// and the display continues.
+static void text_displayed (int status, int passes, int done, __attribute__ ((unused)) void *user_data) {
+  if (!done) {
+    // Verify if the process has registered a pass callback
+    if (pass_callback != NULL) {
+      (*pass_callback)(passes, pass_callback_args);
+    }
+    return;
+  }
+  // Unallow the buffers so that we can access them.
   text_display_allow (0, NULL, 0);
+  text_display_allow (1, NULL, 0);
   // Unsubscribe as we are not waiting any other action
   text_display_subscribe (0, NULL, NULL);
   // Verify if the process has registered a callback
@@ -72,11 +120,21 @@ static void text_displayed (int status, __attribute__ ((unused)) int unused2, __
   }
 }
 
-// Display a text
+// Display a text once
 returncode_t text_display_show_text (const char* text, unsigned int display_ms) {
+  return text_display_show_text_repeat (text, display_ms, 1);
+}
+
+// Display a text several times
+returncode_t text_display_show_text_repeat (const char* text, unsigned int display_ms, unsigned int repeat) {
   if (text == NULL) {
     return RETURNCODE_EINVAL;
   }
+  // Set the repeat count and the gap of the text
+  returncode_t next_ret = text_display_set_next (repeat);
+  if (next_ret != RETURNCODE_SUCCESS) {
+    return next_ret;
+  }
   // Allow the buffer with the driver
   allow_ro_return_t allow_ret = text_display_allow (0, text, strlen (text));
   if (allow_ret.success) {
@@ -120,6 +178,76 @@ returncode_t text_display_show_text (const char* text, unsigned int display_ms)
   }
 }
 
+// Play frames
+returncode_t text_display_play_frames (const uint32_t* frames, unsigned int frames_count, unsigned int display_ms) {
+  if (frames == NULL) {
+    return RETURNCODE_EINVAL;
+  }
+  // Play the frames once, with the gap
+  returncode_t next_ret = text_display_set_next (1);
+  if (next_ret != RETURNCODE_SUCCESS) {
+    return next_ret;
+  }
+  // Allow the frames with the driver
+  allow_ro_return_t allow_ret = text_display_allow (1, frames, frames_count * sizeof (uint32_t));
+  if (allow_ret.success) {
+    // Subscribe to the display finished event using the library's function
+    subscribe_return_t subscribe_ret = text_display_subscribe (0, text_displayed, NULL);
+    if (subscribe_ret.success) {
+      // Send command 3 to the driver
+      syscall_return_t ret = text_display_command (3, frames_count, display_ms);
+      if (ret.type == TOCK_SYSCALL_SUCCESS) {
+        return RETURNCODE_SUCCESS;
+      } else {
+        // There was an error and the playback could not be started
+        text_display_allow (1, NULL, 0);
+        text_display_subscribe (0, NULL, NULL);
+        return tock_status_to_returncode(ret.data[0]);
+      }
+    } else {
+      text_display_allow (1, NULL, 0);
+      return tock_status_to_returncode(subscribe_ret.status);
+    }
+  } else {
+    return tock_status_to_returncode(allow_ret.status);
+  }
+}
+
+// Cancel the display, or the display that waits its turn
+//
+// The driver calls the done callback with RETURNCODE_ECANCEL.
+returncode_t text_display_cancel (void) {
+  // Send command 4 to the driver
+  syscall_return_t ret = text_display_command (4, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
+
+// Pause the display
+returncode_t text_display_pause (void) {
+  // Send command 5 to the driver
+  syscall_return_t ret = text_display_command (5, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
+
+// Resume the paused display
+returncode_t text_display_resume (void) {
+  // Send command 6 to the driver
+  syscall_return_t ret = text_display_command (6, 0, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
+
 /******* Synchronous API *********/
 
 // The library registers this function with the driver for the synchronous calls.
@@ -171,3 +299,36 @@ returncode_t text_display_show_text_sync (const char* text, unsigned int display
     return ret;
   }
 }
+
+// Play frames and wait for it to finish
+returncode_t text_display_play_frames_sync (const uint32_t* frames, unsigned int frames_count, unsigned int display_ms) {
+  text_display_status_t display_status;
+  display_status.done   = false;
+  display_status.status = 0;
+
+  // Register the callback with the driver
+  text_display_set_done_callback (text_displayed_sync, &display_status);
+
+  // Use the asychronous API to play the frames
+  returncode_t ret = text_display_play_frames (frames, frames_count, display_ms);
+
+  if (ret == RETURNCODE_SUCCESS) {
+    yield_for (&display_status.done);
+    return tock_status_to_returncode(display_status.status);
+  } else {
+    return ret;
+  }
+}
+
+/******* Images *********/
+
+// Display an image
+returncode_t text_display_show_image (uint32_t image) {
+  // Send command 2 to the driver
+  syscall_return_t ret = text_display_command (2, image, 0);
+  if (ret.type == TOCK_SYSCALL_SUCCESS) {
+    return RETURNCODE_SUCCESS;
+  } else {
+    return tock_status_to_returncode(ret.data[0]);
+  }
+}
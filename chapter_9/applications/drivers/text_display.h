@@ -7,6 +7,11 @@
 
 #define DRIVER_NUM_TEXT_DISPLAY 0xa0002
 
+// The degree sign, the driver displays Latin-1 bytes, so a "°"
+// written in a UTF-8 source file would be displayed as two
+// unknown characters, use "21" TEXT_DISPLAY_DEGREE "C" instead
+#define TEXT_DISPLAY_DEGREE "\xb0"
+
 // Make sure that functions are exported as C functions and not C++
 // This prevents the compiler from exporing the functions using
 // the C++ name mangling style 
@@ -15,23 +20,65 @@ extern "C" {
 #endif
 
 typedef void (text_display_done_t)(returncode_t, void *user_data);
+typedef void (text_display_pass_t)(unsigned int passes, void *user_data);
 
 // Presence
 bool text_display_is_present (void);
 
+// Set the time the LEDs are off between two characters or frames,
+// so that repeated ones are distinguishable. It is used by the
+// displays started afterwards.
+//
+// The driver keeps the gap (command 8) and the repeat count (command 7)
+// only for the next display request, commands 1 and 3 then use both
+// and clear them. The repeat count has its own command instead of
+// being packed into the arguments of command 1, as its 0 means forever
+// and the processes that only send the delay would loop forever. This
+// library sends both settings before each display.
+returncode_t text_display_set_gap (unsigned int gap_ms);
+
 /******** Asynchronous API *********/
 
 // Set a callback function to be called when the text display is done.
 void text_display_set_done_callback (text_display_done_t callback, void *callback_args);
 
+// Set a callback function to be called each time the whole text has been displayed.
+void text_display_set_pass_callback (text_display_pass_t callback, void *callback_args);
+
 // Display the text and immediately return
+// If the display shows another process' text, the text waits its turn.
 returncode_t text_display_show_text (const char* text, unsigned int display_ms);
 
+// Display the text repeat times (0 meaning forever) and immediately return
+returncode_t text_display_show_text_repeat (const char* text, unsigned int display_ms, unsigned int repeat);
+
+// Play the frames and immediately return. Each frame is a 25 bit
+// image, the most significant bit is the upper left LED.
+returncode_t text_display_play_frames (const uint32_t* frames, unsigned int frames_count, unsigned int display_ms);
+
+// Cancel the display or the display that waits for another process'
+// display to finish. The done callback receives RETURNCODE_ECANCEL.
+returncode_t text_display_cancel (void);
+
+// Pause the display at the current character or frame
+returncode_t text_display_pause (void);
+
+// Resume the paused display
+returncode_t text_display_resume (void);
+
 /******** Synchronous API **********/
 
 // Display the text and wait until it is done
 returncode_t text_display_show_text_sync (const char* text, unsigned int display_ms);
 
+// Play the frames and wait until it is done
+returncode_t text_display_play_frames_sync (const uint32_t* frames, unsigned int frames_count, unsigned int display_ms);
+
+/******** Images **********/
+
+// Display a 25 bit image, the most significant bit is the upper left LED
+returncode_t text_display_show_image (uint32_t image);
+
 #ifdef __cplusplus
 }
 #endif
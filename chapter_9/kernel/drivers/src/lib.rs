@@ -3,3 +3,6 @@
 
 /// The driver that displays a text.
 pub mod text_display;
+
+/// The syscall filter that restricts the display drivers to allowed applications.
+pub mod syscall_filter;
@@ -0,0 +1,139 @@
+use core::cell::Cell;
+use core::ops::RangeInclusive;
+
+use kernel::debug;
+use kernel::platform::SyscallFilter;
+use kernel::process::Process;
+use kernel::syscall::Syscall;
+use kernel::ErrorCode;
+
+/// Allows the application `name` to use the commands `commands`
+/// of the driver `driver_num`
+///
+/// A driver that appears in a permission is restricted, only the
+/// applications that have a permission for it may use it. All the
+/// other drivers may be used by any application.
+///
+/// As several processes may be loaded with the same name, a permission
+/// is only granted to a process whose name no other process uses.
+pub struct Permission {
+    name: &'static str,
+    driver_num: usize,
+    commands: RangeInclusive<usize>,
+}
+
+impl Permission {
+    /// Initializes a new permission
+    pub const fn new(
+        name: &'static str,
+        driver_num: usize,
+        commands: RangeInclusive<usize>,
+    ) -> Self {
+        Permission {
+            name,
+            driver_num,
+            commands,
+        }
+    }
+}
+
+/// The board's syscall filter
+///
+/// It denies the system calls to restricted drivers that the
+/// permissions table does not allow.
+pub struct AllowlistSyscallFilter {
+    /// The array of processes that the kernel has loaded
+    processes: &'static [Option<&'static dyn Process>],
+
+    /// The table of permissions
+    permissions: &'static [Permission],
+
+    /// The number of denied system calls
+    denied: Cell<usize>,
+}
+
+impl AllowlistSyscallFilter {
+    /// Initializes a new filter structure
+    pub fn new(
+        processes: &'static [Option<&'static dyn Process>],
+        permissions: &'static [Permission],
+    ) -> Self {
+        AllowlistSyscallFilter {
+            processes,
+            permissions,
+            denied: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of denied system calls
+    pub fn denied(&self) -> usize {
+        self.denied.get()
+    }
+
+    /// Verifies that at most one loaded process uses `name`
+    fn is_unique(&self, name: &str) -> bool {
+        // Count the processes that use the same name
+        let count = self
+            .processes
+            .iter()
+            .filter_map(|process| *process)
+            .filter(|process| process.get_process_name() == name)
+            .count();
+        count <= 1
+    }
+
+    /// Verifies if the permissions allow `name` to use the `command`
+    /// of the driver `driver_num`
+    ///
+    /// `command` is `None` for the system calls that are not commands,
+    /// these only need a permission for the driver.
+    fn is_allowed(&self, name: &str, driver_num: usize, command: Option<usize>) -> bool {
+        let mut restricted = false;
+        for permission in self.permissions {
+            if permission.driver_num == driver_num {
+                restricted = true;
+                if permission.name == name
+                    && command.map_or(true, |command| permission.commands.contains(&command))
+                {
+                    // Another process loaded with the same name
+                    // must not get the permission.
+                    return self.is_unique(name);
+                }
+            }
+        }
+        !restricted
+    }
+}
+
+impl SyscallFilter for AllowlistSyscallFilter {
+    fn filter_syscall(&self, process: &dyn Process, syscall: &Syscall) -> Result<(), ErrorCode> {
+        // Extract the driver and the command that the system call targets
+        let target = match *syscall {
+            Syscall::Command {
+                driver_number,
+                subdriver_number,
+                ..
+            } => Some((driver_number, Some(subdriver_number))),
+            Syscall::Subscribe { driver_number, .. }
+            | Syscall::ReadWriteAllow { driver_number, .. }
+            | Syscall::ReadOnlyAllow { driver_number, .. } => Some((driver_number, None)),
+            // The other system calls do not use a driver
+            _ => None,
+        };
+        match target {
+            Some((driver_num, command))
+                if !self.is_allowed(process.get_process_name(), driver_num, command) =>
+            {
+                self.denied.set(self.denied.get() + 1);
+                debug!(
+                    "Denied access to driver {:#x} for process {} ({} denied)",
+                    driver_num,
+                    process.get_process_name(),
+                    self.denied()
+                );
+                Err(ErrorCode::NODEVICE)
+            }
+            _ => Ok(()),
+        }
+    }
+}
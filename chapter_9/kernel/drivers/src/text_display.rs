@@ -1,5 +1,6 @@
 use core::cell::Cell;
 use core::mem;
+use font::{Font, Font5x5};
 use kernel::grant::Grant;
 use kernel::hil::led::Led;
 use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
@@ -18,91 +19,157 @@ use kernel::ErrorCode;
 /// number available.
 pub const DRIVER_NUM: usize = 0xa0002;
 
-/// Font glyph definition for digits
+/// The number of bytes of a frame shared by a process
 ///
-/// A font glyph is a set of bits that represents that
-/// state of the LEDs
-const DIGITS: [u32; 10] = [
-    // 0
-    0b11111_10011_10101_11001_11111,
-    // 1
-    0b00100_01100_00100_00100_01110,
-    // 2
-    0b11110_00001_01110_10000_11111,
-    // 3
-    0b11110_00001_11110_00001_11110,
-    // 4
-    0b10000_10000_10100_11111_00100,
-    // 5
-    0b11111_10000_11110_00001_11110,
-    // 6
-    0b11111_10000_11111_10001_11111,
-    // 7
-    0b11111_00001_00010_00100_00100,
-    // 8
-    0b11111_10001_11111_10001_11111,
-    // 9
-    0b11111_10001_11111_00001_11111,
-];
-
-/// Font glyph definition for capital letters
+/// A frame is an image stored as a little endian *u32*
+/// that uses the 25 bits expected by *print*.
+const FRAME_LEN: usize = 4;
+
+/// The bits of an image that correspond to LEDs
+const IMAGE_MASK: u32 = (1 << 25) - 1;
+
+/// The content that the driver displays for a process
+#[derive(Copy, Clone, PartialEq)]
+enum Content {
+    /// The text from the buffer shared with allow number 0
+    Text,
+    /// The frames from the buffer shared with allow number 1
+    Frames,
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Content::Text
+    }
+}
+
+/// What the LED matrix shows during the current alarm
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Phase {
+    /// The LEDs are off, between two characters or frames
+    Blank,
+    /// A character or a frame
+    Character,
+}
+
+impl Default for Phase {
+    fn default() -> Self {
+        Phase::Blank
+    }
+}
+
+/// What the driver does when the alarm of a display fires
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Step {
+    /// Turn the LEDs off between two characters or frames
+    Gap,
+    /// Display the character or frame at this position
+    Show(usize),
+    /// The content has been fully displayed this number of times,
+    /// inform the process and display the first character or frame again
+    Repeat(usize),
+    /// The content has been fully displayed this number of times,
+    /// inform the process that the display is over
+    Done(usize),
+}
+
+/// The progress of a display through the content of a process
 ///
-/// A font glyph is a set of bits that represents that
-/// state of the LEDs
-const LETTERS: [u32; 26] = [
-    // A
-    0b01110_10001_11111_10001_10001,
-    // B
-    0b11111_10001_11110_10001_11111,
-    // C
-    0b11111_10000_10000_10000_11111,
-    // D
-    0b11110_10001_10001_10001_11110,
-    // E
-    0b11111_10000_11110_10000_11111,
-    // F
-    0b11111_10000_11110_10000_10000,
-    // G
-    0b11111_10000_10111_10001_11111,
-    // H
-    0b10001_10001_11111_10001_10001,
-    // I
-    0b11111_00100_00100_00100_11111,
-    // J
-    0b00011_00001_00001_10001_11111,
-    // K
-    0b10001_10010_11100_10010_10001,
-    // L
-    0b10000_10000_10000_10000_11111,
-    // M
-    0b10001_11011_10101_10001_10001,
-    // N
-    0b10001_11001_10101_10011_10001,
-    // O
-    0b01110_10001_10001_10001_01110,
-    // P
-    0b11110_10001_11110_10000_10000,
-    // Q
-    0b01110_10001_10001_01110_00011,
-    // R
-    0b11110_10001_11110_10001_10001,
-    // S
-    0b11111_10000_11111_00001_11111,
-    // T
-    0b11111_00100_00100_00100_00100,
-    // U
-    0b10001_10001_10001_10001_11111,
-    // V
-    0b10001_10001_01010_01010_00100,
-    // W
-    0b10001_10001_10101_10101_01010,
-    // X
-    0b10001_01010_00100_01010_10001,
-    // Y
-    0b10001_10001_01010_00100_00100,
-    // Z
-    0b11111_00010_00100_01000_11111,
-];
+/// Each time the alarm fires, the driver asks the playback of the
+/// process it displays for what to do next.
+#[derive(Copy, Clone, Default, Debug)]
+struct Playback {
+    /// The position within the content that the driver will display next
+    position: usize,
+    /// The number of characters or frames
+    len: usize,
+    /// The number of times the content is displayed,
+    /// 0 meaning forever
+    repeat: usize,
+    /// The number of times the content has been fully displayed
+    pass: usize,
+    /// Whether the LEDs are off between two characters or frames
+    gap: bool,
+    /// What the LED matrix shows until the alarm fires
+    phase: Phase,
+}
+
+impl Playback {
+    /// Starts the display of `len` characters or frames, `repeat` times
+    /// (0 meaning forever), separated by a gap if `gap` is set
+    fn new(len: usize, repeat: usize, gap: bool) -> Playback {
+        Playback {
+            len,
+            repeat,
+            gap,
+            ..Playback::default()
+        }
+    }
+
+    /// Returns the number of times the content has been fully displayed
+    fn pass(&self) -> usize {
+        self.pass
+    }
+
+    /// Returns whether the LEDs are off between two characters or frames
+    fn in_gap(&self) -> bool {
+        self.phase == Phase::Blank
+    }
+
+    /// Stops the display, keeping the number of passes
+    fn stop(&mut self) {
+        self.position = 0;
+        self.len = 0;
+    }
+
+    /// Moves to the next character, frame or gap
+    fn next(&mut self) -> Step {
+        // Verify if the LEDs have to be turned off before the
+        // next letter, digit or frame, so that two identical
+        // consecutive ones are distinguishable.
+        if self.phase == Phase::Character && self.gap {
+            self.phase = Phase::Blank;
+            return Step::Gap;
+        }
+        // Verify if we have displayed the whole content.
+        if self.position >= self.len {
+            // A display that repeats forever eventually wraps around.
+            self.pass = self.pass.wrapping_add(1);
+            // Verify if the content has to be displayed again.
+            if self.len > 0 && (self.repeat == 0 || self.pass < self.repeat) {
+                // Start again from the first letter, digit or frame.
+                self.position = 1;
+                self.phase = Phase::Character;
+                Step::Repeat(self.pass)
+            } else {
+                Step::Done(self.pass)
+            }
+        } else {
+            let position = self.position;
+            self.position = self.position + 1;
+            self.phase = Phase::Character;
+            Step::Show(position)
+        }
+    }
+}
+
+/// A display request that waits for the driver to be free
+#[derive(Copy, Clone)]
+struct Request {
+    /// The content to display, text or frames
+    content: Content,
+    /// The number of characters or frames
+    len: usize,
+    /// The number of milliseconds that each character
+    /// or frame will be displayed
+    delay_ms: usize,
+    /// The number of times the content is displayed,
+    /// 0 meaning forever
+    repeat: usize,
+    /// The number of milliseconds that the LEDs are off
+    /// between two characters or frames
+    gap_ms: usize,
+}
 
 /// The data type that will be stored in each
 /// process' grant.
@@ -112,16 +179,36 @@ pub struct AppData {
     //// that contains the text that thr driver should display.
     buffer: ReadOnlyProcessBuffer,
 
-    /// The position within the buffer that the driver will
-    // display next
-    position: usize,
+    /// The buffer shared by the process with the driver
+    /// that contains the frames that the driver should play.
+    frames: ReadOnlyProcessBuffer,
 
-    /// The length of the usefull data stored in the buffer
-    len: usize,
+    /// The content that the driver displays, text or frames
+    content: Content,
 
-    /// The number of milliseconds that each digit or letter
-    /// will be displayed
+    /// The progress of the display through the content
+    playback: Playback,
+
+    /// The number of milliseconds that each digit, letter
+    /// or frame will be displayed
     delay_ms: usize,
+
+    /// The number of milliseconds that the LEDs are off
+    /// between two digits, letters or frames
+    gap_ms: usize,
+
+    /// The number of milliseconds that the LEDs are off between two
+    /// digits, letters or frames of the next requested content,
+    /// no gap if the process has not set it
+    next_gap_ms: usize,
+
+    /// The number of times the next requested content is displayed,
+    /// 0 meaning forever, once if the process has not set it
+    next_repeat: Option<usize>,
+
+    /// The request that waits for the driver to finish
+    /// displaying for another process
+    pending: Option<Request>,
 }
 
 /// Structure representing the driver
@@ -137,13 +224,19 @@ pub struct TextDisplay<'a, L: Led, A: Alarm<'a>> {
     ///
     /// The data type stored by the grant is `AppData` and
     /// it can register up to 1 upcall.
+    ///
+    /// Upcall 0 receives the status, the number of passes displayed
+    /// and whether the display is over (1) or continues (0).
     grant: Grant<AppData, 1>,
 
     /// Stores whether the driver is in the middle of displaying a text
     in_progress: Cell<bool>,
 
+    /// Stores whether the display in progress is paused
+    paused: Cell<bool>,
+
     /// The ProcessId of the process for which the driver is currently
-    /// displaying a text
+    /// displaying, or has last displayed, a text
     process_id: OptionalCell<ProcessId>,
 }
 
@@ -155,6 +248,7 @@ impl<'a, L: Led, A: Alarm<'a>> TextDisplay<'a, L, A> {
             alarm,
             grant,
             in_progress: Cell::new(false),
+            paused: Cell::new(false),
             process_id: OptionalCell::empty(),
         }
     }
@@ -180,46 +274,68 @@ impl<'a, L: Led, A: Alarm<'a>> TextDisplay<'a, L, A> {
                 |process_id| {
                     // The process is still valid, so we try to enter its grant area.
                     let res = self.grant.enter(*process_id, |app, upcalls| {
-                        // Verify if there are still letters or digites to display
-                        if app.position < app.len {
-                            // Access the buffer shared by the process and display the next
-                            // letter or digit.
-                            let res = app
-                                .buffer
-                                .enter(|buffer| {
-                                    // Call the display function to set the LEDs.
-                                    let _ = self.display(buffer[app.position].get() as char);
-                                    // Set up an alarm after the specified milliseconds.
-                                    self.alarm.set_alarm(
-                                        self.alarm.now(),
-                                        self.alarm.ticks_from_ms(app.delay_ms as u32),
-                                    );
-                                    // Return success
-                                    // This will set res = true
-                                    true
-                                })
-                                // If we cannot access the buffer, return false
-                                // This will set res = false
-                                .unwrap_or(false);
-                            if res {
-                                // We successfully displayed a letter or a digit,
-                                // so we increase the current position
-                                app.position = app.position + 1;
-                            } else {
+                        let position = match app.playback.next() {
+                            Step::Gap => {
+                                self.clear();
+                                // Set up an alarm for the end of the gap.
+                                self.alarm.set_alarm(
+                                    self.alarm.now(),
+                                    self.alarm.ticks_from_ms(app.gap_ms as u32),
+                                );
+                                return;
+                            }
+                            Step::Show(position) => position,
+                            Step::Repeat(pass) => {
+                                // Inform the process that a pass is done, so that
+                                // it can count the progress.
+                                let _ = upcalls.schedule_upcall(0, (0, pass, 0));
+                                0
+                            }
+                            Step::Done(pass) => {
+                                // We have displayed all the letters and digits from the
+                                // buffer, we are done.
+                                self.in_progress.set(false);
+                                // Inform the process that the display is done.
+                                let _ = upcalls.schedule_upcall(0, (0, pass, 1));
+                                return;
+                            }
+                        };
+                        // Access the buffer shared by the process and display the next
+                        // letter, digit or frame.
+                        let res = match app.content {
+                            Content::Text => app.buffer.enter(|buffer| {
+                                // Call the display function to set the LEDs.
+                                let _ = self.display(buffer[position].get() as char);
+                            }),
+                            Content::Frames => app.frames.enter(|frames| {
+                                // Read the little endian frame and set the LEDs.
+                                let offset = position * FRAME_LEN;
+                                let frame = (0..FRAME_LEN).fold(0, |frame, byte| {
+                                    frame | (frames[offset + byte].get() as u32) << (8 * byte)
+                                });
+                                self.print(frame & IMAGE_MASK);
+                            }),
+                        };
+                        match res {
+                            Ok(()) => {
+                                // Set up an alarm after the specified milliseconds.
+                                self.alarm.set_alarm(
+                                    self.alarm.now(),
+                                    self.alarm.ticks_from_ms(app.delay_ms as u32),
+                                );
+                            }
+                            Err(_) => {
                                 // There was an error when we tried to display
                                 // a letter or a digit, we we cannot continue
                                 // the current action.
                                 self.in_progress.set(false);
                                 // Inform the process that the display has failed,
                                 // due to a buffer access error.
-                                let _ = upcalls.schedule_upcall(0, (ErrorCode::NOMEM.into(), 0, 0));
+                                let _ = upcalls.schedule_upcall(
+                                    0,
+                                    (ErrorCode::NOMEM.into(), app.playback.pass(), 1),
+                                );
                             }
-                        } else {
-                            // We have displayed all the letters and digits from the
-                            // buffer, we are done.
-                            self.in_progress.set(false);
-                            // Inform the process that the display is done.
-                            let _ = upcalls.schedule_upcall(0, (0, 0, 0));
                         }
                     });
                     match res {
@@ -237,6 +353,285 @@ impl<'a, L: Led, A: Alarm<'a>> TextDisplay<'a, L, A> {
         }
     }
 
+    /// Verifies that the process has shared a buffer large enough
+    /// for the `request`
+    fn validate(app: &AppData, request: &Request) -> Result<(), ErrorCode> {
+        // Select the buffer that stores the content and compute
+        // how many bytes it should have.
+        let (shared_len, required_len) = match request.content {
+            Content::Text => (app.buffer.len(), request.len),
+            Content::Frames => (app.frames.len(), request.len.saturating_mul(FRAME_LEN)),
+        };
+        // Verify is the process has previously shared a buffer.
+        if shared_len > 0 {
+            // Verify that the length that the process is requesting us to
+            // display is less or equal to the capacity of the buffer.
+            if shared_len >= required_len {
+                Ok(())
+            } else {
+                // The buffer is to small.
+                Err(ErrorCode::SIZE)
+            }
+        } else {
+            // The process has not shared with us a buffer.
+            Err(ErrorCode::NOMEM)
+        }
+    }
+
+    /// Queues a request to display `len` characters or frames from the
+    /// buffer shared by the process, each of them for `delay_ms` milliseconds
+    ///
+    /// The LEDs are off between two characters or frames for the time set by
+    /// the process with command 8, or not at all. The content is displayed the
+    /// number of times set by the process with command 7, or once. Both settings
+    /// only apply to this request.
+    ///
+    /// Each process can have one request waiting in its grant. If the driver
+    /// is free, the request starts immediately.
+    fn request(
+        &self,
+        process_id: ProcessId,
+        content: Content,
+        len: usize,
+        delay_ms: usize,
+    ) -> CommandReturn {
+        // We enter the process' grant data to store the request.
+        let res = self.grant.enter(process_id, |app, _| {
+            // Verify if the process already has a request waiting.
+            if app.pending.is_none() {
+                let request = Request {
+                    content,
+                    len,
+                    delay_ms,
+                    gap_ms: app.next_gap_ms,
+                    repeat: app.next_repeat.unwrap_or(1),
+                };
+                // Verify the buffer now, so that the process finds out
+                // about errors without waiting for its turn.
+                Self::validate(app, &request).map(|()| {
+                    app.pending = Some(request);
+                    // The gap and the repeat count only apply to this request.
+                    app.next_gap_ms = 0;
+                    app.next_repeat = None;
+                })
+            } else {
+                // The process has to wait for its previous request
+                // to start or to cancel it.
+                Err(ErrorCode::BUSY)
+            }
+        });
+        match res {
+            // The request is queued
+            Ok(Ok(())) => {
+                // If the driver is free, start the request
+                self.start_next();
+                // Inform the process that we have accepted the request
+                CommandReturn::success()
+            }
+            // The buffer is probably too small, inform the process that we cannot display.
+            Ok(Err(err)) => CommandReturn::failure(err),
+            // There is no shared buffer, inform the process that we cannot display.
+            Err(err) => CommandReturn::failure(err.into()),
+        }
+    }
+
+    /// Sets the number of times the next requested content
+    /// of the process is displayed, 0 meaning forever
+    fn set_repeat(&self, process_id: ProcessId, repeat: usize) -> CommandReturn {
+        let res = self.grant.enter(process_id, |app, _| {
+            app.next_repeat = Some(repeat);
+        });
+        match res {
+            Ok(()) => CommandReturn::success(),
+            Err(err) => CommandReturn::failure(err.into()),
+        }
+    }
+
+    /// Sets the number of milliseconds that the LEDs are off between
+    /// two characters or frames of the next requested content of the process
+    fn set_gap(&self, process_id: ProcessId, gap_ms: usize) -> CommandReturn {
+        let res = self.grant.enter(process_id, |app, _| {
+            app.next_gap_ms = gap_ms;
+        });
+        match res {
+            Ok(()) => CommandReturn::success(),
+            Err(err) => CommandReturn::failure(err.into()),
+        }
+    }
+
+    /// Returns whether the driver is displaying for the process
+    fn is_displaying_for(&self, process_id: ProcessId) -> bool {
+        self.in_progress.get() && self.process_id.map_or(false, |id| *id == process_id)
+    }
+
+    /// Cancels the request of the process, either the one that is
+    /// displayed or the one that waits in its grant
+    ///
+    /// The process receives the upcall with the `CANCEL` status and the
+    /// number of passes displayed, 0 for a request that was waiting.
+    fn cancel(&self, process_id: ProcessId) -> CommandReturn {
+        let displaying = self.is_displaying_for(process_id);
+        let res = self.grant.enter(process_id, |app, upcalls| {
+            if displaying {
+                // Stop the display right away.
+                let _ = self.alarm.disarm();
+                self.clear();
+                app.playback.stop();
+                Ok(app.playback.pass())
+            } else {
+                // Remove the request from the queue, it has not
+                // been displayed yet.
+                app.pending.take().map_or(Err(ErrorCode::INVAL), |_| Ok(0))
+            }
+            .map(|pass| {
+                // Inform the process that the display was cancelled.
+                let _ = upcalls.schedule_upcall(0, (ErrorCode::CANCEL.into(), pass, 1));
+            })
+        });
+        match res {
+            Ok(Ok(())) => {
+                if displaying {
+                    // The driver is free, start the next process' request
+                    self.in_progress.set(false);
+                    self.paused.set(false);
+                    self.start_next();
+                }
+                CommandReturn::success()
+            }
+            // The process has no request displayed or waiting.
+            Ok(Err(err)) => CommandReturn::failure(err),
+            Err(err) => CommandReturn::failure(err.into()),
+        }
+    }
+
+    /// Freezes the display of the process at the current position
+    fn pause(&self, process_id: ProcessId) -> CommandReturn {
+        if self.is_displaying_for(process_id) && !self.paused.get() {
+            // Without the alarm, the driver does not move
+            // to the next character or frame.
+            let _ = self.alarm.disarm();
+            self.paused.set(true);
+            CommandReturn::success()
+        } else {
+            // The driver does not display for the process
+            // or the display is already paused.
+            CommandReturn::failure(ErrorCode::INVAL)
+        }
+    }
+
+    /// Continues the paused display of the process
+    fn resume(&self, process_id: ProcessId) -> CommandReturn {
+        if self.is_displaying_for(process_id) && self.paused.get() {
+            let res = self.grant.enter(process_id, |app, _| {
+                // Display the current character, frame or gap
+                // for its full time before moving on.
+                let ms = if app.playback.in_gap() {
+                    app.gap_ms
+                } else {
+                    app.delay_ms
+                };
+                self.alarm
+                    .set_alarm(self.alarm.now(), self.alarm.ticks_from_ms(ms as u32));
+            });
+            match res {
+                Ok(()) => {
+                    self.paused.set(false);
+                    CommandReturn::success()
+                }
+                Err(err) => CommandReturn::failure(err.into()),
+            }
+        } else {
+            // The driver does not display for the process
+            // or the display is not paused.
+            CommandReturn::failure(ErrorCode::INVAL)
+        }
+    }
+
+    /// Returns the process whose request is next in line
+    ///
+    /// Processes are served round-robin: the next process is the first
+    /// one with a request waiting after the process that the driver has
+    /// displayed for. If there is none, the driver starts over with the
+    /// first process that has a request waiting.
+    fn next_pending(&self) -> Option<ProcessId> {
+        let current = self.process_id.map_or(0, |process_id| process_id.id());
+        // The first process with a request waiting after the current one
+        let mut next: Option<ProcessId> = None;
+        // The first process with a request waiting
+        let mut first: Option<ProcessId> = None;
+        for app in self.grant.iter() {
+            let process_id = app.processid();
+            if app.enter(|app, _| app.pending.is_some()) {
+                if process_id.id() > current
+                    && next.map_or(true, |next| process_id.id() < next.id())
+                {
+                    next = Some(process_id);
+                }
+                if first.map_or(true, |first| process_id.id() < first.id()) {
+                    first = Some(process_id);
+                }
+            }
+        }
+        next.or(first)
+    }
+
+    /// Starts the requests waiting in the processes' grants
+    /// until one of them is in progress or none is left
+    fn start_next(&self) {
+        // A paused display has no alarm that finds out if its process
+        // has stopped, so we verify that the process is still valid.
+        if self.paused.get() {
+            if let Some(process_id) = self.process_id.extract() {
+                if self.grant.enter(process_id, |_, _| {}).is_err() {
+                    self.paused.set(false);
+                    self.in_progress.set(false);
+                }
+            }
+        }
+        while !self.in_progress.get() {
+            match self.next_pending() {
+                Some(process_id) => self.start(process_id),
+                None => break,
+            }
+        }
+    }
+
+    /// Starts the request that the process has waiting in its grant
+    fn start(&self, process_id: ProcessId) {
+        let res = self.grant.enter(process_id, |app, upcalls| {
+            app.pending.take().map_or(false, |request| {
+                // The process might have shared another buffer
+                // since it made the request.
+                match Self::validate(app, &request) {
+                    Ok(()) => {
+                        // Reset the parameters
+                        app.content = request.content;
+                        // The first letter, digit or frame is not preceded by a gap.
+                        app.playback =
+                            Playback::new(request.len, request.repeat, request.gap_ms > 0);
+                        app.delay_ms = request.delay_ms;
+                        app.gap_ms = request.gap_ms;
+                        true
+                    }
+                    Err(err) => {
+                        // Inform the process that the display has failed.
+                        let _ = upcalls.schedule_upcall(0, (err.into(), 0, 1));
+                        false
+                    }
+                }
+            })
+        });
+        // Store the ProcessId, so that the next request
+        // is searched starting after this process.
+        self.process_id.set(process_id);
+        if res.unwrap_or(false) {
+            // Set that we have a display in progress
+            self.in_progress.set(true);
+            // Display the next digit, letter or frame
+            self.display_next();
+        }
+    }
+
     /// Prints the a font `glyph` by setting LEDs
     /// on and off depending on the glyph's bits
     ///
@@ -261,17 +656,12 @@ impl<'a, L: Led, A: Alarm<'a>> TextDisplay<'a, L, A> {
 
     /// Displays a character
     fn display(&self, character: char) -> Result<(), ErrorCode> {
-        let displayed_character = character.to_ascii_uppercase();
-        match displayed_character {
-            '0'..='9' => {
-                self.print(DIGITS[displayed_character as usize - '0' as usize]);
+        match Font5x5.glyph(character) {
+            Some(glyph) => {
+                self.print(glyph.bits());
                 Ok(())
             }
-            'A'..='Z' => {
-                self.print(LETTERS[displayed_character as usize - 'A' as usize]);
-                Ok(())
-            }
-            _ => {
+            None => {
                 self.clear();
                 Err(ErrorCode::INVAL)
             }
@@ -287,19 +677,23 @@ impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for TextDisplay<'a, L, A> {
         allow_number: usize,
         mut buffer: ReadOnlyProcessBuffer,
     ) -> Result<ReadOnlyProcessBuffer, (ReadOnlyProcessBuffer, ErrorCode)> {
+        // The buffer that the driver displays for the process
+        // cannot be replaced until the display is over.
+        let displaying = self.is_displaying_for(process_id);
         match allow_number {
             // The process has shared or unshared (if buffer is indirectly None) a buffer with us
             0 => {
                 // Enter the grant and try to swap the previous buffer
                 // with the one that we have just recevied.
                 let res = self.grant.enter(process_id, |app, _| {
-                    // buffer will become app.buffer
-                    // app.buffer will become buffer
-                    mem::swap(&mut app.buffer, &mut buffer);
-                    // reset the positions as this is a new buffer
-                    app.len = 0;
-                    app.position = 0;
-                    app.delay_ms = 0;
+                    if displaying && app.content == Content::Text {
+                        Err(ErrorCode::BUSY)
+                    } else {
+                        // buffer will become app.buffer
+                        // app.buffer will become buffer
+                        mem::swap(&mut app.buffer, &mut buffer);
+                        Ok(())
+                    }
                 });
                 match res {
                     // We have registered the new buffer
@@ -307,13 +701,32 @@ impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for TextDisplay<'a, L, A> {
                     // The actual value of buffer was swapped (mem::swap) whe
                     // we registered the new buffer, so the buffer
                     // argument now stores the old buffer
-                    Ok(()) => Ok(buffer),
+                    Ok(Ok(())) => Ok(buffer),
+                    // The text is being displayed, we return
+                    // an error and the new buffer.
+                    Ok(Err(err)) => Err((buffer, err)),
                     // We did not register the buffer, we return an
                     // error and the new buffer.
                     Err(err) => Err((buffer, err.into())),
                 }
             }
-            // We only know what to do with buffer number 0,
+            // The process has shared or unshared a buffer of frames with us
+            1 => {
+                let res = self.grant.enter(process_id, |app, _| {
+                    if displaying && app.content == Content::Frames {
+                        Err(ErrorCode::BUSY)
+                    } else {
+                        mem::swap(&mut app.frames, &mut buffer);
+                        Ok(())
+                    }
+                });
+                match res {
+                    Ok(Ok(())) => Ok(buffer),
+                    Ok(Err(err)) => Err((buffer, err)),
+                    Err(err) => Err((buffer, err.into())),
+                }
+            }
+            // We only know what to do with buffers number 0 and 1,
             // so we return an error is a process tries to
             // share with us a buffer with another number.
             _ => Err((buffer, ErrorCode::NOSUPPORT)),
@@ -342,65 +755,51 @@ impl<'a, L: Led, A: Alarm<'a>> SyscallDriver for TextDisplay<'a, L, A> {
             // Tock's convention states that all syscall drivers must return *success* or *success_...* for
             // command number 0. This allows processes to verify if a driver is present.
             0 => CommandReturn::success(),
-            // Display the text from the buffer
-            //  r2 - is the length of the text
-            //  r3 - is the time in milliseconds that a letter or digit is displayed
-            1 => {
-                // Verify if there is another display action in progress.
-                if !self.in_progress.get() {
-                    // If there is no action in progress,
-                    // we can start.
-                    // We enter the process' grant data to set the
-                    // parameters.
-                    let res = self.grant.enter(process_id, |app, _| {
-                        // Verify is the process has previously shared a buffer.
-                        if app.buffer.len() > 0 {
-                            // Verify that the length that the process is requesting us to
-                            // display is less or equal to the capacity of the buffer.
-                            if app.buffer.len() >= r2 {
-                                // Reset the parameters
-                                app.position = 0;
-                                app.len = r2;
-                                app.delay_ms = r3;
-                                // We can start displaying.
-                                // res = Ok(())
-                                Ok(())
-                            } else {
-                                // The buffer is to small.
-                                // res = Err(ErrorCode::SIZE)
-                                Err(ErrorCode::SIZE)
-                            }
-                        } else {
-                            // The process has not shared with us a buffer.
-                            // res = Err(ErrorCode::NOMEM)
-                            Err(ErrorCode::NOMEM)
-                        }
-                    });
-                    match res {
-                        // If we can start displaying
-                        Ok(Ok(())) => {
-                            // Store the ProcessId if the requesting process
-                            self.process_id.set(process_id);
-                            // Set that we have a display in progress
-                            self.in_progress.set(true);
-                            // Display the next digit or letter
-                            self.display_next();
-                            // Inform the process that we have started the displaying
-                            // of the text
-                            CommandReturn::success()
-                        }
-                        // The buffer is probably too small, inform the process that we cannot display.
-                        Ok(Err(err)) => CommandReturn::failure(err),
-                        // There is no shared buffer, inform the process that we cannot display.
-                        Err(err) => CommandReturn::failure(err.into()),
-                    }
-                } else {
-                    // If another display action is in progress,
-                    // inform the process that we are busy and
-                    // that it should try again later.
+            // Display the text from the buffer shared with allow number 0
+            //  r2 - the length of the text
+            //  r3 - the time in milliseconds that a letter or digit is displayed
+            // The LEDs are off between two letters or digits for the time set
+            // with command 8, or not at all. The text is displayed the number
+            // of times set with command 7, or once.
+            // If the driver displays for another process, the request waits
+            // its turn.
+            1 => self.request(process_id, Content::Text, r2, r3),
+            // Display the image received in *r2*
+            // The image uses the 25 bits expected by *print*.
+            2 => {
+                if r2 > IMAGE_MASK as usize {
+                    // The image has more than 25 bits
+                    CommandReturn::failure(ErrorCode::INVAL)
+                } else if self.in_progress.get() {
+                    // The image would be overwritten by the
+                    // display action in progress.
                     CommandReturn::failure(ErrorCode::BUSY)
+                } else {
+                    self.print(r2 as u32);
+                    CommandReturn::success()
                 }
             }
+            // Play the frames from the buffer shared with allow number 1
+            //  r2 - the number of frames
+            //  r3 - the time in milliseconds that a frame is displayed
+            // The LEDs are off between two frames for the time set with command 8,
+            // or not at all. The frames are played the number of times set with
+            // command 7, or once.
+            3 => self.request(process_id, Content::Frames, r2, r3),
+            // Cancel the display of the process, or the request that waits its turn
+            4 => self.cancel(process_id),
+            // Pause the display of the process
+            5 => self.pause(process_id),
+            // Resume the paused display of the process
+            6 => self.resume(process_id),
+            // Set the number of times the next text or frames of the process
+            // are displayed
+            //  r2 - the number of times, 0 meaning forever
+            7 => self.set_repeat(process_id, r2),
+            // Set the time that the LEDs are off between two letters, digits
+            // or frames of the next text or frames of the process
+            //  r2 - the time in milliseconds, 0 for no gap
+            8 => self.set_gap(process_id, r2),
             // Inform the process that we do not understand the command
             _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
         }
@@ -414,5 +813,7 @@ impl<'a, L: Led, A: Alarm<'a>> AlarmClient for TextDisplay<'a, L, A> {
         // The alarm has expired, the current letter or digit has been displayed enugh,
         // display the next letter or digit
         self.display_next();
+        // If the display is done, start the next process' request
+        self.start_next();
     }
 }
@@ -0,0 +1,402 @@
+use drivers::text_display::{TextDisplay, DRIVER_NUM};
+use font::{Font, Font5x5};
+use kernel::hil::time::{Alarm, AlarmClient};
+use kernel::process::ProcessId;
+use kernel::syscall::{CommandReturn, SyscallDriver};
+use kernel::ErrorCode;
+use mock_hil::{MockAlarm, MockKernel, MockLed, MockMatrix, MockProcess};
+
+type Display = TextDisplay<'static, MockLed, MockAlarm<'static>>;
+
+/// The time that a character or frame is displayed, in milliseconds
+const DELAY_MS: usize = 300;
+
+/// The index of a process that the kernel has not loaded
+const NOT_LOADED: usize = 2;
+
+/// Leaks `value` so that it lives forever
+fn leak<T>(value: T) -> &'static T {
+    Box::leak(Box::new(value))
+}
+
+/// Returns the glyph of `character` the way the matrix displays it
+fn glyph(character: char) -> u32 {
+    Font5x5.glyph(character).unwrap().bits()
+}
+
+/// The kernel, the LED matrix, the alarm and the driver
+///
+/// The kernel has loaded two processes.
+struct Board {
+    kernel: MockKernel,
+    matrix: &'static MockMatrix,
+    alarm: &'static MockAlarm<'static>,
+    driver: &'static Display,
+}
+
+impl Board {
+    fn new() -> Board {
+        let kernel = MockKernel::with_processes(&["example_app", "other_app"]);
+        let matrix = leak(MockMatrix::default());
+        let leds: &'static [&'static MockLed; 25] = leak(matrix.leds());
+        let alarm = leak(MockAlarm::default());
+        let driver = leak(TextDisplay::new(
+            leds,
+            alarm,
+            kernel.create_grant(DRIVER_NUM),
+        ));
+        alarm.set_alarm_client(driver);
+        Board {
+            kernel,
+            matrix,
+            alarm,
+            driver,
+        }
+    }
+
+    /// Returns the id of the process at `index`
+    fn process_id(&self, index: usize) -> ProcessId {
+        self.kernel.process_id(index)
+    }
+
+    /// Returns the process at `index`
+    fn process(&self, index: usize) -> &'static MockProcess {
+        self.kernel.process(index)
+    }
+
+    /// Shares `data` with allow number `allow_number` and subscribes
+    /// to the upcall, the way the process' library does
+    fn share(&self, index: usize, allow_number: usize, data: &[u8]) {
+        let buffer = self.process(index).readonly_buffer(data);
+        assert!(self
+            .driver
+            .allow_readonly(self.process_id(index), allow_number, buffer)
+            .is_ok());
+        assert_eq!(self.driver.allocate_grant(self.process_id(index)), Ok(()));
+        self.process(index).subscribe(DRIVER_NUM, 0);
+    }
+
+    /// Issues the command `command_number` for the process at `index`
+    fn command(&self, index: usize, command_number: usize, r2: usize, r3: usize) -> CommandReturn {
+        self.driver
+            .command(command_number, r2, r3, self.process_id(index))
+    }
+
+    /// Shares `text` and asks the driver to display it
+    fn show_text(&self, index: usize, text: &str) {
+        self.share(index, 0, text.as_bytes());
+        assert!(self.command(index, 1, text.len(), DELAY_MS).is_success());
+    }
+}
+
+#[test]
+fn command_0_reports_the_driver_as_present() {
+    let board = Board::new();
+
+    assert!(board.command(0, 0, 0, 0).is_success());
+}
+
+#[test]
+fn command_1_fails_for_a_process_that_is_not_running() {
+    let board = Board::new();
+
+    // The driver cannot enter the grant of the process, so
+    // it does not start a display.
+    assert!(board.command(NOT_LOADED, 1, 5, 300).is_failure());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.changes(), 0);
+}
+
+#[test]
+fn command_4_fails_for_a_process_that_is_not_running() {
+    let board = Board::new();
+
+    assert!(board.command(NOT_LOADED, 4, 0, 0).is_failure());
+}
+
+#[test]
+fn command_7_fails_for_a_process_that_is_not_running() {
+    let board = Board::new();
+
+    // The repeat count is stored in the grant of the process.
+    assert!(board.command(NOT_LOADED, 7, 3, 0).is_failure());
+}
+
+#[test]
+fn command_8_fails_for_a_process_that_is_not_running() {
+    let board = Board::new();
+
+    // The gap is stored in the grant of the process.
+    assert!(board.command(NOT_LOADED, 8, 100, 0).is_failure());
+}
+
+#[test]
+fn alarm_without_a_display_in_progress_is_ignored() {
+    let board = Board::new();
+
+    board.driver.alarm();
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.changes(), 0);
+}
+
+#[test]
+fn command_2_displays_the_image() {
+    let board = Board::new();
+
+    let image = 0b00100_01110_11111_01110_00100;
+    assert!(board.command(0, 2, image, 0).is_success());
+    assert_eq!(board.matrix.state(), image as u32);
+    // Displaying an image is synchronous
+    assert!(!board.alarm.is_armed());
+
+    let ret = board.command(0, 2, 1 << 25, 0);
+    assert_eq!(ret.get_failure(), Some(ErrorCode::INVAL));
+    assert_eq!(board.matrix.state(), image as u32);
+}
+
+#[test]
+fn unknown_commands_are_not_supported() {
+    let board = Board::new();
+
+    let ret = board.command(0, 100, 0, 0);
+    assert_eq!(ret.get_failure(), Some(ErrorCode::NOSUPPORT));
+}
+
+#[test]
+fn command_1_fails_without_a_large_enough_buffer() {
+    let board = Board::new();
+
+    assert_eq!(
+        board.command(0, 1, 2, 300).get_failure(),
+        Some(ErrorCode::NOMEM)
+    );
+    board.share(0, 0, b"AB");
+    assert_eq!(
+        board.command(0, 1, 3, 300).get_failure(),
+        Some(ErrorCode::SIZE)
+    );
+    assert!(!board.alarm.is_armed());
+}
+
+#[test]
+fn command_1_shows_each_character_then_informs_the_process() {
+    let board = Board::new();
+
+    board.show_text(0, "AB");
+    assert_eq!(board.matrix.state(), glyph('A'));
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS as u32));
+
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('B'));
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS as u32));
+    assert!(board.process(0).take_upcalls().is_empty());
+
+    // The process receives a single upcall, at the end.
+    assert!(board.alarm.fire());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+}
+
+#[test]
+fn command_1_with_a_gap_turns_the_leds_off_between_characters() {
+    let board = Board::new();
+
+    assert!(board.command(0, 8, 100, 0).is_success());
+    board.show_text(0, "AA");
+    assert_eq!(board.matrix.state(), glyph('A'));
+
+    for &(remaining, state) in [(100, 0), (300, glyph('A')), (100, 0)].iter() {
+        assert!(board.alarm.fire());
+        assert_eq!(board.alarm.remaining_ms(), Some(remaining));
+        assert_eq!(board.matrix.state(), state);
+    }
+
+    assert!(board.alarm.fire());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+}
+
+#[test]
+fn command_7_informs_the_process_after_each_pass() {
+    let board = Board::new();
+
+    assert!(board.command(0, 7, 3, 0).is_success());
+    board.show_text(0, "AB");
+
+    let mut upcalls = Vec::new();
+    let mut states = vec![board.matrix.state()];
+    while board.alarm.fire() {
+        states.push(board.matrix.state());
+        upcalls.extend(board.process(0).take_upcalls());
+    }
+
+    let (a, b) = (glyph('A'), glyph('B'));
+    // The last character stays displayed once the display is over.
+    assert_eq!(states, [a, b, a, b, a, b, b]);
+    assert_eq!(upcalls, [(0, (0, 1, 0)), (0, (0, 2, 0)), (0, (0, 3, 1))]);
+}
+
+#[test]
+fn commands_7_and_8_only_apply_to_the_next_request() {
+    let board = Board::new();
+
+    assert!(board.command(0, 7, 2, 0).is_success());
+    assert!(board.command(0, 8, 100, 0).is_success());
+    board.show_text(0, "A");
+    while board.alarm.fire() {}
+    assert_eq!(
+        board.process(0).take_upcalls(),
+        [(0, (0, 1, 0)), (0, (0, 2, 1))]
+    );
+
+    // The next text is displayed once, without a gap.
+    board.show_text(0, "B");
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS as u32));
+    assert!(board.alarm.fire());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+}
+
+#[test]
+fn command_1_of_an_empty_text_is_done_right_away() {
+    let board = Board::new();
+
+    board.share(0, 0, b"A");
+    assert!(board.command(0, 1, 0, DELAY_MS).is_success());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.changes(), 0);
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+}
+
+#[test]
+fn command_3_plays_the_frames() {
+    let board = Board::new();
+
+    let frames: [u32; 2] = [
+        0b11111_00000_00000_00000_00000,
+        0b00100_00100_00100_00100_00100,
+    ];
+    let bytes: Vec<u8> = frames
+        .iter()
+        .flat_map(|frame| frame.to_le_bytes().to_vec())
+        .collect();
+    board.share(0, 1, &bytes);
+    assert!(board.command(0, 3, frames.len(), DELAY_MS).is_success());
+    assert_eq!(board.matrix.state(), frames[0]);
+
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), frames[1]);
+
+    assert!(board.alarm.fire());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+}
+
+#[test]
+fn command_4_stops_the_display_and_keeps_the_passes() {
+    let board = Board::new();
+
+    // Display the text forever
+    assert!(board.command(0, 7, 0, 0).is_success());
+    board.show_text(0, "A");
+    assert!(board.alarm.fire());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 0))]);
+
+    assert!(board.command(0, 4, 0, 0).is_success());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.state(), 0);
+    assert_eq!(
+        board.process(0).take_upcalls(),
+        [(0, (usize::from(ErrorCode::CANCEL), 1, 1))]
+    );
+    // There is nothing left to cancel
+    assert_eq!(
+        board.command(0, 4, 0, 0).get_failure(),
+        Some(ErrorCode::INVAL)
+    );
+}
+
+#[test]
+fn command_4_of_a_waiting_request_reports_no_pass() {
+    let board = Board::new();
+
+    // The grant of the second process keeps its previous display.
+    board.show_text(1, "B");
+    assert!(board.alarm.fire());
+    assert_eq!(board.process(1).take_upcalls(), [(0, (0, 1, 1))]);
+
+    board.show_text(0, "A");
+    board.show_text(1, "B");
+    assert!(board.command(1, 4, 0, 0).is_success());
+    assert_eq!(
+        board.process(1).take_upcalls(),
+        [(0, (usize::from(ErrorCode::CANCEL), 0, 1))]
+    );
+
+    // The display of the first process goes on.
+    assert_eq!(board.matrix.state(), glyph('A'));
+    assert!(board.alarm.is_armed());
+}
+
+#[test]
+fn allow_of_the_displayed_buffer_is_busy() {
+    let board = Board::new();
+
+    board.show_text(0, "AB");
+    let buffer = board.process(0).readonly_buffer(b"CD");
+    let res = board.driver.allow_readonly(board.process_id(0), 0, buffer);
+    assert_eq!(res.err().map(|(_, err)| err), Some(ErrorCode::BUSY));
+    // The frames are not displayed, so they can be shared.
+    let frames = board.process(0).readonly_buffer(&[0; 4]);
+    assert!(board
+        .driver
+        .allow_readonly(board.process_id(0), 1, frames)
+        .is_ok());
+
+    // The display goes on with the text it started with.
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('B'));
+    assert!(board.alarm.fire());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+    board.share(0, 0, b"CD");
+}
+
+#[test]
+fn command_5_freezes_the_display_until_command_6() {
+    let board = Board::new();
+
+    board.show_text(0, "AB");
+    assert!(!board.alarm.advance(100));
+
+    assert!(board.command(0, 5, 0, 0).is_success());
+    assert!(!board.alarm.is_armed());
+    assert_eq!(board.matrix.state(), glyph('A'));
+    assert_eq!(
+        board.command(0, 5, 0, 0).get_failure(),
+        Some(ErrorCode::INVAL)
+    );
+
+    // The character is displayed for its full time again.
+    assert!(board.command(0, 6, 0, 0).is_success());
+    assert_eq!(board.alarm.remaining_ms(), Some(DELAY_MS as u32));
+    assert!(board.alarm.fire());
+    assert_eq!(board.matrix.state(), glyph('B'));
+}
+
+#[test]
+fn a_request_waits_for_the_display_of_another_process() {
+    let board = Board::new();
+
+    board.show_text(0, "A");
+    board.show_text(1, "B");
+    assert_eq!(board.matrix.state(), glyph('A'));
+
+    // The first display is over, the second one starts.
+    assert!(board.alarm.fire());
+    assert_eq!(board.process(0).take_upcalls(), [(0, (0, 1, 1))]);
+    assert_eq!(board.matrix.state(), glyph('B'));
+    assert!(board.process(1).take_upcalls().is_empty());
+
+    assert!(board.alarm.fire());
+    assert_eq!(board.process(1).take_upcalls(), [(0, (0, 1, 1))]);
+    assert!(!board.alarm.is_armed());
+}
@@ -0,0 +1,55 @@
+//! Prints the font as a C header for the applications.
+//!
+//! The *text_display.service* application displays the same
+//! characters as the kernel's drivers using this header:
+//!
+//! ```text
+//! cargo run --example c_header > ../../chapter_12/applications/text_display/font5x5.h
+//! ```
+
+use font::{Font, Font5x5, GLYPH_LEDS};
+
+/// The first character of the table, the space
+const FIRST: char = ' ';
+
+/// The last character of the table, the tilde
+const LAST: char = '~';
+
+/// Returns the C header that stores the glyphs of the
+/// printable ASCII characters
+pub fn header() -> String {
+    let mut header = String::new();
+    header.push_str("// Generated from the font library by its c_header example, do not edit\n");
+    header.push_str("//\n");
+    header.push_str("// Each glyph stores the state of the 25 LEDs, the most significant\n");
+    header.push_str("// bit being LED 0 (upper left) and the least significant one LED 24\n");
+    header.push_str("// (lower right).\n\n");
+    header.push_str("#pragma once\n\n");
+    header.push_str("#include <stdint.h>\n\n");
+    header.push_str(&format!("#define FONT5X5_FIRST {}\n", char_literal(FIRST)));
+    header.push_str(&format!("#define FONT5X5_LAST {}\n\n", char_literal(LAST)));
+    header.push_str("static const uint32_t FONT5X5[] = {\n");
+    for character in FIRST..=LAST {
+        let glyph = Font5x5.glyph(character).unwrap();
+        header.push_str(&format!(
+            "    0b{:0width$b}, // {}\n",
+            glyph.bits(),
+            char_literal(character),
+            width = GLYPH_LEDS
+        ));
+    }
+    header.push_str("};\n");
+    header
+}
+
+/// Returns `character` as a C character literal
+fn char_literal(character: char) -> String {
+    match character {
+        '\'' | '\\' => format!("'\\{}'", character),
+        _ => format!("'{}'", character),
+    }
+}
+
+fn main() {
+    print!("{}", header());
+}
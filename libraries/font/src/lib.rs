@@ -0,0 +1,293 @@
+//! Font used by the LED matrix drivers.
+//!
+//! All the drivers display characters on a 5x5 LED matrix. This crate
+//! stores the font glyphs shared by them so that every driver displays
+//! the same characters the same way.
+
+#![forbid(unsafe_code)]
+#![no_std]
+
+/// The number of LEDs in a row of the matrix
+pub const GLYPH_WIDTH: usize = 5;
+
+/// The number of rows of the matrix
+pub const GLYPH_HEIGHT: usize = 5;
+
+/// The number of LEDs of the matrix
+pub const GLYPH_LEDS: usize = GLYPH_WIDTH * GLYPH_HEIGHT;
+
+/// A 5x5 font glyph
+///
+/// A font glyph is a set of bits that represents that
+/// state of the LEDs. The most significant of the 25 bits
+/// is LED 0 (upper left) and the least significant one is
+/// LED 24 (lower right).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Glyph(u32);
+
+impl Glyph {
+    /// The glyph that has all the LEDs turned off
+    pub const EMPTY: Glyph = Glyph(0);
+
+    /// Builds a glyph from its bits
+    pub const fn new(bits: u32) -> Glyph {
+        Glyph(bits)
+    }
+
+    /// Builds a glyph from its rows, row 0 being the upper one
+    ///
+    /// Only the 5 least significant bits of each row are used,
+    /// the most significant of them being the left LED.
+    pub fn from_rows(rows: [u8; GLYPH_HEIGHT]) -> Glyph {
+        Glyph(rows.iter().fold(0, |bits, row| {
+            (bits << GLYPH_WIDTH) | (*row as u32 & 0b11111)
+        }))
+    }
+
+    /// Returns the glyph's bits, in the order expected
+    /// by the drivers' *print* function
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns the `y` row of the glyph, row 0 being the upper one
+    pub fn row(&self, y: usize) -> u8 {
+        ((self.0 >> ((GLYPH_HEIGHT - 1 - y) * GLYPH_WIDTH)) & 0b11111) as u8
+    }
+
+    /// Returns whether the LED at column `x` and row `y` is on,
+    /// (0, 0) being the upper left LED
+    pub fn is_on(&self, x: usize, y: usize) -> bool {
+        (self.row(y) >> (GLYPH_WIDTH - 1 - x)) & 0x01 == 1
+    }
+}
+
+/// A font that can display characters on the LED matrix
+pub trait Font {
+    /// Returns the glyph of `character` or `None`
+    /// if the font cannot display it.
+    fn glyph(&self, character: char) -> Option<Glyph>;
+}
+
+/// Font glyph definition for the printable ASCII characters
+///
+/// The table starts with the space character (0x20) and
+/// ends with the tilde (0x7e).
+const ASCII: [Glyph; 95] = [
+    // (space)
+    Glyph::new(0b00000_00000_00000_00000_00000),
+    // !
+    Glyph::new(0b00100_00100_00100_00000_00100),
+    // "
+    Glyph::new(0b01010_01010_00000_00000_00000),
+    // #
+    Glyph::new(0b01010_11111_01010_11111_01010),
+    // $
+    Glyph::new(0b01111_10100_01110_00101_11110),
+    // %
+    Glyph::new(0b11001_11010_00100_01011_10011),
+    // &
+    Glyph::new(0b01100_10010_01101_10010_01101),
+    // '
+    Glyph::new(0b00100_00100_00000_00000_00000),
+    // (
+    Glyph::new(0b00010_00100_00100_00100_00010),
+    // )
+    Glyph::new(0b01000_00100_00100_00100_01000),
+    // *
+    Glyph::new(0b00100_10101_01110_10101_00100),
+    // +
+    Glyph::new(0b00000_00100_01110_00100_00000),
+    // ,
+    Glyph::new(0b00000_00000_00000_00100_01000),
+    // -
+    Glyph::new(0b00000_00000_01110_00000_00000),
+    // .
+    Glyph::new(0b00000_00000_00000_00000_00100),
+    // /
+    Glyph::new(0b00001_00010_00100_01000_10000),
+    // 0
+    Glyph::new(0b11111_10011_10101_11001_11111),
+    // 1
+    Glyph::new(0b00100_01100_00100_00100_01110),
+    // 2
+    Glyph::new(0b11110_00001_01110_10000_11111),
+    // 3
+    Glyph::new(0b11110_00001_11110_00001_11110),
+    // 4
+    Glyph::new(0b10000_10000_10100_11111_00100),
+    // 5
+    Glyph::new(0b11111_10000_11110_00001_11110),
+    // 6
+    Glyph::new(0b11111_10000_11111_10001_11111),
+    // 7
+    Glyph::new(0b11111_00001_00010_00100_00100),
+    // 8
+    Glyph::new(0b11111_10001_11111_10001_11111),
+    // 9
+    Glyph::new(0b11111_10001_11111_00001_11111),
+    // :
+    Glyph::new(0b00000_00100_00000_00100_00000),
+    // ;
+    Glyph::new(0b00000_00100_00000_00100_01000),
+    // <
+    Glyph::new(0b00010_00100_01000_00100_00010),
+    // =
+    Glyph::new(0b00000_01110_00000_01110_00000),
+    // >
+    Glyph::new(0b01000_00100_00010_00100_01000),
+    // ?
+    Glyph::new(0b01110_10001_00110_00000_00100),
+    // @
+    Glyph::new(0b01110_10001_10111_10000_01110),
+    // A
+    Glyph::new(0b01110_10001_11111_10001_10001),
+    // B
+    Glyph::new(0b11111_10001_11110_10001_11111),
+    // C
+    Glyph::new(0b11111_10000_10000_10000_11111),
+    // D
+    Glyph::new(0b11110_10001_10001_10001_11110),
+    // E
+    Glyph::new(0b11111_10000_11110_10000_11111),
+    // F
+    Glyph::new(0b11111_10000_11110_10000_10000),
+    // G
+    Glyph::new(0b11111_10000_10111_10001_11111),
+    // H
+    Glyph::new(0b10001_10001_11111_10001_10001),
+    // I
+    Glyph::new(0b11111_00100_00100_00100_11111),
+    // J
+    Glyph::new(0b00011_00001_00001_10001_11111),
+    // K
+    Glyph::new(0b10001_10010_11100_10010_10001),
+    // L
+    Glyph::new(0b10000_10000_10000_10000_11111),
+    // M
+    Glyph::new(0b10001_11011_10101_10001_10001),
+    // N
+    Glyph::new(0b10001_11001_10101_10011_10001),
+    // O
+    Glyph::new(0b01110_10001_10001_10001_01110),
+    // P
+    Glyph::new(0b11110_10001_11110_10000_10000),
+    // Q
+    Glyph::new(0b01110_10001_10001_01110_00011),
+    // R
+    Glyph::new(0b11110_10001_11110_10001_10001),
+    // S
+    Glyph::new(0b11111_10000_11111_00001_11111),
+    // T
+    Glyph::new(0b11111_00100_00100_00100_00100),
+    // U
+    Glyph::new(0b10001_10001_10001_10001_11111),
+    // V
+    Glyph::new(0b10001_10001_01010_01010_00100),
+    // W
+    Glyph::new(0b10001_10001_10101_10101_01010),
+    // X
+    Glyph::new(0b10001_01010_00100_01010_10001),
+    // Y
+    Glyph::new(0b10001_10001_01010_00100_00100),
+    // Z
+    Glyph::new(0b11111_00010_00100_01000_11111),
+    // [
+    Glyph::new(0b01110_01000_01000_01000_01110),
+    // \
+    Glyph::new(0b10000_01000_00100_00010_00001),
+    // ]
+    Glyph::new(0b01110_00010_00010_00010_01110),
+    // ^
+    Glyph::new(0b00100_01010_10001_00000_00000),
+    // _
+    Glyph::new(0b00000_00000_00000_00000_11111),
+    // `
+    Glyph::new(0b01000_00100_00000_00000_00000),
+    // a
+    Glyph::new(0b00000_01110_10010_10010_01111),
+    // b
+    Glyph::new(0b10000_10000_11110_10001_11110),
+    // c
+    Glyph::new(0b00000_01111_10000_10000_01111),
+    // d
+    Glyph::new(0b00001_00001_01111_10001_01111),
+    // e
+    Glyph::new(0b01110_10001_11111_10000_01110),
+    // f
+    Glyph::new(0b00110_01000_11100_01000_01000),
+    // g
+    Glyph::new(0b01111_10001_01111_00001_01110),
+    // h
+    Glyph::new(0b10000_10000_11110_10001_10001),
+    // i
+    Glyph::new(0b00100_00000_00100_00100_00100),
+    // j
+    Glyph::new(0b00010_00000_00010_10010_01100),
+    // k
+    Glyph::new(0b10000_10010_11100_10010_10001),
+    // l
+    Glyph::new(0b01000_01000_01000_01000_00110),
+    // m
+    Glyph::new(0b00000_11010_10101_10101_10101),
+    // n
+    Glyph::new(0b00000_11110_10001_10001_10001),
+    // o
+    Glyph::new(0b00000_01110_10001_10001_01110),
+    // p
+    Glyph::new(0b00000_11110_10001_11110_10000),
+    // q
+    Glyph::new(0b00000_01111_10001_01111_00001),
+    // r
+    Glyph::new(0b00000_10110_11000_10000_10000),
+    // s
+    Glyph::new(0b00000_01111_01100_00011_11110),
+    // t
+    Glyph::new(0b01000_11100_01000_01000_00110),
+    // u
+    Glyph::new(0b00000_10001_10001_10011_01101),
+    // v
+    Glyph::new(0b00000_10001_10001_01010_00100),
+    // w
+    Glyph::new(0b00000_10001_10101_10101_01010),
+    // x
+    Glyph::new(0b00000_10010_01100_01100_10010),
+    // y
+    Glyph::new(0b10001_10001_01111_00001_01110),
+    // z
+    Glyph::new(0b00000_11110_00100_01000_11110),
+    // {
+    Glyph::new(0b00110_00100_01100_00100_00110),
+    // |
+    Glyph::new(0b00100_00100_00100_00100_00100),
+    // }
+    Glyph::new(0b01100_00100_00110_00100_01100),
+    // ~
+    Glyph::new(0b00000_01000_10101_00010_00000),
+];
+
+/// Font glyph definition for the degree sign
+const DEGREE: Glyph = Glyph::new(0b01110_01010_01110_00000_00000);
+
+/// The 5x5 font used by the drivers
+///
+/// It displays all the printable ASCII characters, from
+/// the space (0x20) to the tilde (0x7e), and the degree sign.
+/// The drivers receive characters as bytes, the degree sign
+/// is the Latin-1 byte 0xb0.
+///
+/// Text encoded as UTF-8, such as a `"°"` literal in a C source
+/// file, stores the degree sign as two bytes (0xc2 0xb0) that are
+/// displayed as two unknown characters. Use the byte 0xb0, written
+/// `"\xb0"` in C, instead.
+pub struct Font5x5;
+
+impl Font for Font5x5 {
+    fn glyph(&self, character: char) -> Option<Glyph> {
+        match character {
+            ' '..='~' => Some(ASCII[character as usize - ' ' as usize]),
+            '\u{b0}' => Some(DEGREE),
+            _ => None,
+        }
+    }
+}
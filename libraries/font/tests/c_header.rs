@@ -0,0 +1,13 @@
+#[path = "../examples/c_header.rs"]
+#[allow(dead_code)]
+mod c_header;
+
+#[test]
+fn the_text_display_service_header_is_up_to_date() {
+    let header = include_str!("../../../chapter_12/applications/text_display/font5x5.h");
+
+    assert!(
+        header == c_header::header(),
+        "run `cargo run --example c_header` to update font5x5.h"
+    );
+}
@@ -0,0 +1,97 @@
+use font::{Font, Font5x5, Glyph, GLYPH_HEIGHT, GLYPH_LEDS, GLYPH_WIDTH};
+
+/// All the characters that the font is able to display
+fn characters() -> impl Iterator<Item = char> {
+    (' '..='~').chain(core::iter::once('\u{b0}'))
+}
+
+/// Returns the state of the LED `index` the way the drivers'
+/// *print* function computes it
+fn printed_led(glyph: Glyph, index: usize) -> bool {
+    (glyph.bits() >> (24 - index)) & 0x01 == 1
+}
+
+#[test]
+fn every_glyph_fits_in_25_bits() {
+    for character in characters() {
+        let glyph = Font5x5.glyph(character).unwrap();
+        assert_eq!(
+            glyph.bits() >> GLYPH_LEDS,
+            0,
+            "glyph of {:?} uses more than 25 bits",
+            character
+        );
+    }
+}
+
+#[test]
+fn every_glyph_round_trips_through_print_order() {
+    for character in characters() {
+        let glyph = Font5x5.glyph(character).unwrap();
+        let mut rows = [0; GLYPH_HEIGHT];
+        for index in 0..GLYPH_LEDS {
+            let (x, y) = (index % GLYPH_WIDTH, index / GLYPH_WIDTH);
+            assert_eq!(
+                printed_led(glyph, index),
+                glyph.is_on(x, y),
+                "LED ({}, {}) of {:?}",
+                x,
+                y,
+                character
+            );
+            if printed_led(glyph, index) {
+                rows[y] |= 1 << (GLYPH_WIDTH - 1 - x);
+            }
+        }
+        for (y, row) in rows.iter().enumerate() {
+            assert_eq!(glyph.row(y), *row, "row {} of {:?}", y, character);
+        }
+        assert_eq!(Glyph::from_rows(rows), glyph, "glyph of {:?}", character);
+    }
+}
+
+#[test]
+fn lowercase_letters_have_their_own_glyphs() {
+    for character in 'a'..='z' {
+        assert_ne!(
+            Font5x5.glyph(character),
+            Font5x5.glyph(character.to_ascii_uppercase()),
+            "glyph of {:?}",
+            character
+        );
+    }
+}
+
+#[test]
+fn only_the_space_is_empty() {
+    for character in characters() {
+        assert_eq!(
+            Font5x5.glyph(character) == Some(Glyph::EMPTY),
+            character == ' ',
+            "glyph of {:?}",
+            character
+        );
+    }
+}
+
+#[test]
+fn glyphs_are_distinct() {
+    for first in characters() {
+        for second in characters().filter(|second| *second != first) {
+            assert_ne!(
+                Font5x5.glyph(first),
+                Font5x5.glyph(second),
+                "{:?} and {:?} have the same glyph",
+                first,
+                second
+            );
+        }
+    }
+}
+
+#[test]
+fn unknown_characters_have_no_glyph() {
+    for character in ['\0', '\n', '\u{7f}', '\u{e9}'] {
+        assert_eq!(Font5x5.glyph(character), None);
+    }
+}
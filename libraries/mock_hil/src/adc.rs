@@ -0,0 +1,84 @@
+use core::cell::Cell;
+use kernel::hil::adc::{AdcChannel, Client};
+use kernel::utilities::cells::OptionalCell;
+use kernel::ErrorCode;
+
+/// An ADC channel whose voltage is set by the test
+///
+/// A sample completes only when the test asks it to.
+#[derive(Default)]
+pub struct MockAdcChannel {
+    /// The sample that the channel returns
+    value: Cell<u16>,
+
+    /// Stores if a sample has been requested
+    pending: Cell<bool>,
+
+    /// The number of samples requested
+    samples: Cell<usize>,
+
+    /// The client notified of the samples
+    client: OptionalCell<&'static dyn Client>,
+}
+
+impl MockAdcChannel {
+    /// Sets the sample that the channel returns, a 16 bits value
+    pub fn set_value(&self, value: u16) {
+        self.value.set(value);
+    }
+
+    /// Completes the requested sample, if any, and returns
+    /// whether there was one
+    pub fn complete(&self) -> bool {
+        if self.pending.replace(false) {
+            self.client
+                .map(|client| client.sample_ready(self.value.get()));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns whether a sample has been requested
+    pub fn is_sampling(&self) -> bool {
+        self.pending.get()
+    }
+
+    /// Returns the number of samples requested
+    pub fn samples(&self) -> usize {
+        self.samples.get()
+    }
+}
+
+impl AdcChannel for MockAdcChannel {
+    fn sample(&self) -> Result<(), ErrorCode> {
+        if self.pending.get() {
+            Err(ErrorCode::BUSY)
+        } else {
+            self.pending.set(true);
+            self.samples.set(self.samples.get() + 1);
+            Ok(())
+        }
+    }
+
+    fn sample_continuous(&self) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+
+    fn stop_sampling(&self) -> Result<(), ErrorCode> {
+        self.pending.set(false);
+        Ok(())
+    }
+
+    fn get_resolution_bits(&self) -> usize {
+        16
+    }
+
+    fn get_voltage_reference_mv(&self) -> Option<usize> {
+        Some(3300)
+    }
+
+    fn set_client(&self, client: &'static dyn Client) {
+        self.client.set(client);
+    }
+}
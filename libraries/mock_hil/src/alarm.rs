@@ -0,0 +1,108 @@
+use core::cell::Cell;
+use kernel::hil::time::{Alarm, AlarmClient, Freq1KHz, Ticks, Ticks32, Time};
+use kernel::utilities::cells::OptionalCell;
+use kernel::ErrorCode;
+
+/// An alarm that is advanced manually by the test
+///
+/// The alarm ticks at 1 KHz, so a tick is a millisecond.
+#[derive(Default)]
+pub struct MockAlarm<'a> {
+    /// The current time
+    now: Cell<u32>,
+
+    /// The time at which the alarm was set
+    reference: Cell<u32>,
+
+    /// The number of ticks after `reference` when the alarm expires
+    dt: Cell<u32>,
+
+    /// Whether the alarm is armed
+    armed: Cell<bool>,
+
+    /// The client notified when the alarm expires
+    client: OptionalCell<&'a dyn AlarmClient>,
+}
+
+impl<'a> MockAlarm<'a> {
+    /// Returns the number of milliseconds after which
+    /// the alarm expires, if it is armed
+    pub fn remaining_ms(&self) -> Option<u32> {
+        if self.armed.get() {
+            Some((self.reference.get().wrapping_add(self.dt.get())).wrapping_sub(self.now.get()))
+        } else {
+            None
+        }
+    }
+
+    /// Advances the time by `ms` milliseconds and fires the alarm
+    /// if it expires within this time
+    ///
+    /// Returns whether the alarm has fired.
+    pub fn advance(&self, ms: u32) -> bool {
+        match self.remaining_ms() {
+            Some(remaining) if remaining <= ms => {
+                self.now.set(self.now.get().wrapping_add(remaining));
+                self.fire();
+                true
+            }
+            _ => {
+                self.now.set(self.now.get().wrapping_add(ms));
+                false
+            }
+        }
+    }
+
+    /// Moves the time to the alarm's expiration and fires it
+    ///
+    /// Returns whether the alarm was armed.
+    pub fn fire(&self) -> bool {
+        if self.armed.get() {
+            self.now
+                .set(self.reference.get().wrapping_add(self.dt.get()));
+            self.armed.set(false);
+            self.client.map(|client| client.alarm());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<'a> Time for MockAlarm<'a> {
+    type Frequency = Freq1KHz;
+    type Ticks = Ticks32;
+
+    fn now(&self) -> Ticks32 {
+        Ticks32::from(self.now.get())
+    }
+}
+
+impl<'a> Alarm<'a> for MockAlarm<'a> {
+    fn set_alarm_client(&'a self, client: &'a dyn AlarmClient) {
+        self.client.set(client);
+    }
+
+    fn set_alarm(&self, reference: Ticks32, dt: Ticks32) {
+        self.reference.set(reference.into_u32());
+        self.dt.set(dt.into_u32());
+        self.armed.set(true);
+    }
+
+    fn get_alarm(&self) -> Ticks32 {
+        Ticks32::from(self.reference.get().wrapping_add(self.dt.get()))
+    }
+
+    fn disarm(&self) -> Result<(), ErrorCode> {
+        self.armed.set(false);
+        Ok(())
+    }
+
+    fn is_armed(&self) -> bool {
+        self.armed.get()
+    }
+
+    fn minimum_dt(&self) -> Ticks32 {
+        Ticks32::from(1)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::uart::MockUart;
+use kernel::collections::ring_buffer::RingBuffer;
+use kernel::debug::{self, DebugWriter, DebugWriterWrapper};
+use kernel::hil::uart::Transmit;
+use std::sync::{Mutex, MutexGuard, Once};
+
+/// The size of the debug writer's buffers
+const BUFFER_LEN: usize = 1024;
+
+/// The UART that the debug writer uses and the lock
+/// that the tests take turns on
+struct Capture {
+    uart: &'static MockUart<'static>,
+    lock: Mutex<()>,
+}
+
+static INIT: Once = Once::new();
+static mut CAPTURE: Option<Capture> = None;
+
+/// The kernel's debug output, held by a test
+///
+/// The kernel has a single debug writer, so the tests that
+/// print with `debug!` hold it one at a time, until the
+/// output is dropped.
+pub struct DebugOutput {
+    uart: &'static MockUart<'static>,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl DebugOutput {
+    /// Returns the text printed with `debug!` since the previous call
+    pub fn text(&self) -> String {
+        self.uart.output()
+    }
+}
+
+/// Routes the kernel's `debug!` messages to a mock UART
+///
+/// The drivers that print with `debug!` panic without a debug
+/// writer, the way they do on a board that does not set one.
+pub fn capture_debug() -> DebugOutput {
+    INIT.call_once(|| {
+        let uart: &'static MockUart<'static> = Box::leak(Box::new(MockUart::default()));
+        let out_buffer = Box::leak(vec![0; BUFFER_LEN].into_boxed_slice());
+        let ring_buffer = Box::leak(vec![0; BUFFER_LEN].into_boxed_slice());
+        let internal_buffer = Box::leak(Box::new(RingBuffer::new(ring_buffer)));
+        let debug_writer: &'static DebugWriter = Box::leak(Box::new(DebugWriter::new(
+            uart,
+            out_buffer,
+            internal_buffer,
+        )));
+        uart.set_transmit_client(debug_writer);
+        let wrapper = Box::leak(Box::new(DebugWriterWrapper::new(debug_writer)));
+        // The writer is only used while a test holds the lock
+        unsafe {
+            debug::set_debug_writer_wrapper(wrapper);
+            CAPTURE = Some(Capture {
+                uart,
+                lock: Mutex::new(()),
+            });
+        }
+    });
+    let capture = unsafe { CAPTURE.as_ref() }.unwrap();
+    // A test that failed while holding the lock does not
+    // prevent the others from printing.
+    let guard = capture
+        .lock
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+    // Drop the text printed by the previous test
+    capture.uart.output();
+    DebugOutput {
+        uart: capture.uart,
+        _guard: guard,
+    }
+}
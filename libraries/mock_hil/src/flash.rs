@@ -0,0 +1,81 @@
+use core::cell::{Cell, RefCell};
+use tickv::error_codes::ErrorCode;
+use tickv::flash_controller::FlashController;
+
+/// The value of an erased flash byte
+const ERASED: u8 = 0xff;
+
+/// A flash memory stored in RAM, made of regions of `S` bytes
+///
+/// Like a real flash, writes can only clear bits, erased regions
+/// have to be erased before they are written again.
+pub struct MockFlash<const S: usize> {
+    /// The content of the flash
+    data: RefCell<Vec<u8>>,
+
+    /// Stores if the writes fail, like those of a worn out flash
+    failing: Cell<bool>,
+}
+
+impl<const S: usize> MockFlash<S> {
+    /// Creates an erased flash of `regions` regions
+    pub fn new(regions: usize) -> MockFlash<S> {
+        MockFlash {
+            data: RefCell::new(vec![ERASED; regions * S]),
+            failing: Cell::new(false),
+        }
+    }
+
+    /// Returns the size of the flash in bytes
+    pub fn size(&self) -> usize {
+        self.data.borrow().len()
+    }
+
+    /// Makes the writes and the erases fail, leaving the flash as it is
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.set(failing);
+    }
+}
+
+/// The flash is used through a reference, so that a test
+/// can build a new key-value store on the same flash, the way
+/// a board does after a reset.
+impl<'a, const S: usize> FlashController<S> for &'a MockFlash<S> {
+    fn read_region(
+        &self,
+        region_number: usize,
+        offset: usize,
+        buf: &mut [u8; S],
+    ) -> Result<(), ErrorCode> {
+        let data = self.data.borrow();
+        let start = region_number * S + offset;
+        if start + S > data.len() {
+            return Err(ErrorCode::ReadFail);
+        }
+        buf.copy_from_slice(&data[start..start + S]);
+        Ok(())
+    }
+
+    fn write(&self, address: usize, buf: &[u8]) -> Result<(), ErrorCode> {
+        let mut data = self.data.borrow_mut();
+        if self.failing.get() || address + buf.len() > data.len() {
+            return Err(ErrorCode::WriteFail);
+        }
+        for (byte, value) in data[address..].iter_mut().zip(buf.iter()) {
+            *byte = *byte & *value;
+        }
+        Ok(())
+    }
+
+    fn erase_region(&self, region_number: usize) -> Result<(), ErrorCode> {
+        let mut data = self.data.borrow_mut();
+        let start = region_number * S;
+        if self.failing.get() || start + S > data.len() {
+            return Err(ErrorCode::EraseFail);
+        }
+        for byte in data[start..start + S].iter_mut() {
+            *byte = ERASED;
+        }
+        Ok(())
+    }
+}
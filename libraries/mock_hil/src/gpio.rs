@@ -0,0 +1,140 @@
+use core::cell::Cell;
+use kernel::hil::gpio::{
+    Client, Configuration, Configure, FloatingState, Input, Interrupt, InterruptEdge, InterruptPin,
+    Output,
+};
+use kernel::utilities::cells::OptionalCell;
+
+/// A pin whose level is set by the test
+///
+/// The pin notifies its client when the level changes, like a
+/// button's pin, if its interrupts are enabled for that edge.
+/// Its level can also be set by the driver, as an output.
+pub struct MockPin<'a> {
+    /// The level of the pin
+    level: Cell<bool>,
+
+    /// The configuration of the pin
+    configuration: Cell<Configuration>,
+
+    /// The floating state of the pin
+    floating_state: Cell<FloatingState>,
+
+    /// The edges that trigger an interrupt, if enabled
+    edge: Cell<Option<InterruptEdge>>,
+
+    /// The client notified of the interrupts
+    client: OptionalCell<&'a dyn Client>,
+}
+
+impl<'a> MockPin<'a> {
+    /// Creates an input pin with the `level` level
+    pub fn new(level: bool) -> MockPin<'a> {
+        MockPin {
+            level: Cell::new(level),
+            configuration: Cell::new(Configuration::LowPower),
+            floating_state: Cell::new(FloatingState::PullNone),
+            edge: Cell::new(None),
+            client: OptionalCell::empty(),
+        }
+    }
+
+    /// Sets the level of the pin and triggers an interrupt
+    /// if it changes on an enabled edge
+    pub fn set_level(&self, level: bool) {
+        let previous = self.level.replace(level);
+        let fired = match self.edge.get() {
+            Some(InterruptEdge::RisingEdge) => !previous && level,
+            Some(InterruptEdge::FallingEdge) => previous && !level,
+            Some(InterruptEdge::EitherEdge) => previous != level,
+            None => false,
+        };
+        if fired {
+            self.client.map(|client| client.fired());
+        }
+    }
+
+    /// Returns whether the interrupts are enabled
+    pub fn interrupts_enabled(&self) -> bool {
+        self.edge.get().is_some()
+    }
+}
+
+impl<'a> Configure for MockPin<'a> {
+    fn configuration(&self) -> Configuration {
+        self.configuration.get()
+    }
+
+    fn make_output(&self) -> Configuration {
+        self.configuration.set(Configuration::Output);
+        self.configuration.get()
+    }
+
+    fn disable_output(&self) -> Configuration {
+        self.configuration.set(Configuration::LowPower);
+        self.configuration.get()
+    }
+
+    fn make_input(&self) -> Configuration {
+        self.configuration.set(Configuration::Input);
+        self.configuration.get()
+    }
+
+    fn disable_input(&self) -> Configuration {
+        self.configuration.set(Configuration::LowPower);
+        self.configuration.get()
+    }
+
+    fn deactivate_to_low_power(&self) {
+        self.configuration.set(Configuration::LowPower);
+    }
+
+    fn set_floating_state(&self, state: FloatingState) {
+        self.floating_state.set(state);
+    }
+
+    fn floating_state(&self) -> FloatingState {
+        self.floating_state.get()
+    }
+}
+
+impl<'a> Input for MockPin<'a> {
+    fn read(&self) -> bool {
+        self.level.get()
+    }
+}
+
+impl<'a> Output for MockPin<'a> {
+    fn set(&self) {
+        self.set_level(true);
+    }
+
+    fn clear(&self) {
+        self.set_level(false);
+    }
+
+    fn toggle(&self) -> bool {
+        self.set_level(!self.level.get());
+        self.level.get()
+    }
+}
+
+impl<'a> Interrupt<'a> for MockPin<'a> {
+    fn set_client(&self, client: &'a dyn Client) {
+        self.client.set(client);
+    }
+
+    fn enable_interrupts(&self, mode: InterruptEdge) {
+        self.edge.set(Some(mode));
+    }
+
+    fn disable_interrupts(&self) {
+        self.edge.set(None);
+    }
+
+    fn is_pending(&self) -> bool {
+        false
+    }
+}
+
+impl<'a> InterruptPin<'a> for MockPin<'a> {}
@@ -0,0 +1,84 @@
+use core::cell::Cell;
+use kernel::hil::led::Led;
+
+/// The number of LEDs of the matrix
+pub const MATRIX_LEDS: usize = 25;
+
+/// An LED that records its state
+#[derive(Default)]
+pub struct MockLed {
+    /// Whether the LED is on
+    on: Cell<bool>,
+
+    /// The number of times the LED was turned on or off
+    changes: Cell<usize>,
+}
+
+impl MockLed {
+    /// Returns the number of times the LED was turned on or off
+    pub fn changes(&self) -> usize {
+        self.changes.get()
+    }
+
+    fn set(&self, on: bool) {
+        self.on.set(on);
+        self.changes.set(self.changes.get() + 1);
+    }
+}
+
+impl Led for MockLed {
+    fn init(&self) {
+        self.on.set(false);
+    }
+
+    fn on(&self) {
+        self.set(true);
+    }
+
+    fn off(&self) {
+        self.set(false);
+    }
+
+    fn toggle(&self) {
+        self.set(!self.on.get());
+    }
+
+    fn read(&self) -> bool {
+        self.on.get()
+    }
+}
+
+/// A 5x5 matrix of recording LEDs
+///
+/// LED 0 is upper left, LED 24 is lower right.
+#[derive(Default)]
+pub struct MockMatrix {
+    leds: [MockLed; MATRIX_LEDS],
+}
+
+impl MockMatrix {
+    /// Returns the references to the LEDs, the way
+    /// the board sends them to the drivers
+    pub fn leds(&self) -> [&MockLed; MATRIX_LEDS] {
+        let mut leds = [&self.leds[0]; MATRIX_LEDS];
+        for (index, led) in leds.iter_mut().enumerate() {
+            *led = &self.leds[index];
+        }
+        leds
+    }
+
+    /// Returns the state of the LEDs as a font glyph
+    ///
+    /// LED 0 is the most significant of the 25 bits,
+    /// the order used by the drivers' *print* function.
+    pub fn state(&self) -> u32 {
+        self.leds
+            .iter()
+            .fold(0, |state, led| (state << 1) | led.read() as u32)
+    }
+
+    /// Returns the number of times the LEDs were turned on or off
+    pub fn changes(&self) -> usize {
+        self.leds.iter().map(|led| led.changes()).sum()
+    }
+}
@@ -0,0 +1,49 @@
+//! Mock implementations of the kernel interfaces used by the drivers.
+//!
+//! The drivers are tested on the host computer, without a board.
+//! This crate provides the pieces of hardware and kernel that the
+//! drivers expect, so that tests can drive them and verify what
+//! the LED matrix displays.
+
+/// A recording LED and a 5x5 matrix made of them.
+pub mod led;
+
+/// An alarm that fires only when the test asks it to.
+pub mod alarm;
+
+/// A kernel that is able to provide process ids and grants
+/// and the processes that it loads.
+pub mod processes;
+
+/// A text screen that records what it displays and a text
+/// screen client that records the completed requests.
+pub mod text_screen;
+
+/// A flash memory stored in RAM for the key-value store.
+pub mod flash;
+
+/// A UART that receives the characters typed by the test.
+pub mod uart;
+
+/// A pin whose level is set by the test, like a button's pin.
+pub mod gpio;
+
+/// An accelerometer whose acceleration is set by the test.
+pub mod sensors;
+
+/// An ADC channel whose voltage is set by the test.
+pub mod adc;
+
+/// A debug writer that records the kernel's `debug!` messages.
+pub mod debug;
+
+pub use crate::adc::MockAdcChannel;
+pub use crate::alarm::MockAlarm;
+pub use crate::debug::{capture_debug, DebugOutput};
+pub use crate::flash::MockFlash;
+pub use crate::gpio::MockPin;
+pub use crate::led::{MockLed, MockMatrix};
+pub use crate::processes::{MockKernel, MockProcess};
+pub use crate::sensors::MockAccelerometer;
+pub use crate::text_screen::{MockTextScreen, MockTextScreenClient};
+pub use crate::uart::MockUart;
@@ -0,0 +1,455 @@
+use core::cell::{Cell, RefCell};
+use core::fmt::Write;
+use core::mem;
+use core::ptr::NonNull;
+use kernel::capabilities::{ExternalProcessCapability, MemoryAllocationCapability};
+use kernel::grant::Grant;
+use kernel::platform::mpu;
+use kernel::process::{
+    Error, FunctionCall, FunctionCallSource, Process, ProcessCustomGrantIdentifer, ProcessId,
+    State, Task,
+};
+use kernel::processbuffer::{ReadOnlyProcessBuffer, ReadWriteProcessBuffer};
+use kernel::syscall::{ContextSwitchReason, Syscall, SyscallReturn};
+use kernel::upcall::UpcallId;
+use kernel::utilities::cells::OptionalCell;
+use kernel::{create_capability, ErrorCode, Kernel};
+use std::alloc::{self, Layout};
+use std::collections::VecDeque;
+
+/// The number of processes that the mock kernel is able to hold
+const NUM_PROCS: usize = 4;
+
+/// The function pointer of the upcalls that the tests subscribe
+///
+/// The process never runs, so it only has to be valid for the kernel,
+/// which schedules the upcalls whose function pointer is not null.
+const UPCALL_FN: usize = 0x1000;
+
+/// The size of an upcall saved at the start of a grant's memory,
+/// its application data and its function pointer
+const SAVED_UPCALL_LEN: usize = 2 * mem::size_of::<usize>();
+
+/// A kernel, with or without processes
+///
+/// The drivers need a kernel to create grants and process ids. If
+/// no process is loaded, entering a grant fails the same way it does
+/// for a process that has been stopped. The loaded processes hold the
+/// drivers' grants, so that the tests can issue their system calls.
+pub struct MockKernel {
+    kernel: &'static Kernel,
+    processes: &'static [Option<&'static dyn Process>; NUM_PROCS],
+    mock_processes: [Option<&'static MockProcess>; NUM_PROCS],
+}
+
+impl MockKernel {
+    /// Creates a new kernel without processes
+    ///
+    /// The kernel and its processes array are leaked, as the kernel
+    /// expects them to live forever.
+    pub fn new() -> MockKernel {
+        MockKernel::with_processes(&[])
+    }
+
+    /// Creates a new kernel that has loaded a process for each
+    /// of the `names`, the first one at index 0
+    pub fn with_processes(names: &[&'static str]) -> MockKernel {
+        if names.len() > NUM_PROCS {
+            panic!(
+                "Expecting at most {} processes, {} supplied",
+                NUM_PROCS,
+                names.len()
+            );
+        }
+        let mut mock_processes = [None; NUM_PROCS];
+        let mut processes: [Option<&'static dyn Process>; NUM_PROCS] = [None; NUM_PROCS];
+        for (index, name) in names.iter().enumerate() {
+            let process: &'static MockProcess = Box::leak(Box::new(MockProcess::new(name)));
+            mock_processes[index] = Some(process);
+            processes[index] = Some(process);
+        }
+        let processes: &'static [Option<&'static dyn Process>; NUM_PROCS] =
+            Box::leak(Box::new(processes));
+        let mock_kernel = MockKernel {
+            kernel: Box::leak(Box::new(Kernel::new(processes))),
+            processes,
+            mock_processes,
+        };
+        for (index, process) in mock_processes.iter().enumerate() {
+            if let Some(process) = process {
+                process.process_id.set(mock_kernel.process_id(index));
+            }
+        }
+        mock_kernel
+    }
+
+    /// Returns the process id of the process at `index`
+    pub fn process_id(&self, index: usize) -> ProcessId {
+        let capability = create_capability!(ExternalProcessCapability);
+        ProcessId::new_external(self.kernel, index, index, &capability)
+    }
+
+    /// Returns the process loaded at `index`
+    pub fn process(&self, index: usize) -> &'static MockProcess {
+        self.mock_processes[index].expect("no process loaded at this index")
+    }
+
+    /// Returns the kernel's processes array, the way
+    /// the board hands it to its drivers
+    pub fn processes(&self) -> &'static [Option<&'static dyn Process>; NUM_PROCS] {
+        self.processes
+    }
+
+    /// Creates a new grant for the driver `driver_num`
+    pub fn create_grant<T: Default, const NUM_UPCALLS: usize>(
+        &self,
+        driver_num: usize,
+    ) -> Grant<T, NUM_UPCALLS> {
+        let capability = create_capability!(MemoryAllocationCapability);
+        self.kernel.create_grant(driver_num, &capability)
+    }
+}
+
+impl Default for MockKernel {
+    fn default() -> Self {
+        MockKernel::new()
+    }
+}
+
+/// The memory that a process has allocated for a grant
+struct GrantMemory {
+    /// The driver that the grant belongs to
+    driver_num: usize,
+
+    /// The start of the grant's memory
+    ptr: *mut u8,
+
+    /// Whether a driver is using the grant
+    entered: Cell<bool>,
+}
+
+/// A process that never runs
+///
+/// It has a name and an id, which is enough for the syscall filters
+/// and the fault policies. The drivers allocate their grants in its
+/// memory and read the buffers that the tests share, the upcalls that
+/// they schedule are recorded for the tests.
+pub struct MockProcess {
+    /// The name from the process' TBF header
+    name: &'static str,
+
+    /// The id given by the kernel that loaded the process
+    process_id: OptionalCell<ProcessId>,
+
+    /// The grants that the drivers have allocated, by grant number
+    grants: RefCell<Vec<Option<GrantMemory>>>,
+
+    /// The upcalls that the drivers have scheduled, in order
+    tasks: RefCell<VecDeque<FunctionCall>>,
+}
+
+impl MockProcess {
+    /// Creates a new process named `name`
+    pub fn new(name: &'static str) -> MockProcess {
+        MockProcess {
+            name,
+            process_id: OptionalCell::empty(),
+            grants: RefCell::new(Vec::new()),
+            tasks: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Subscribes to the upcall `subscribe_num` of the driver `driver_num`,
+    /// the way the process does with the subscribe system call
+    ///
+    /// The driver must have allocated its grant, which the kernel asks it
+    /// to do before the subscribe. The kernel stores the upcalls at the
+    /// start of the grant's memory, the application data and the function
+    /// pointer of each of them are set to the same value.
+    pub fn subscribe(&self, driver_num: usize, subscribe_num: usize) {
+        let grants = self.grants.borrow();
+        let grant = grants
+            .iter()
+            .flatten()
+            .find(|grant| grant.driver_num == driver_num)
+            .expect("the driver has not allocated its grant");
+        let upcall = unsafe { grant.ptr.add(subscribe_num * SAVED_UPCALL_LEN) } as *mut usize;
+        unsafe {
+            upcall.write(UPCALL_FN);
+            upcall.add(1).write(UPCALL_FN);
+        }
+    }
+
+    /// Returns the upcalls scheduled since the previous call, each
+    /// of them as its subscribe number and its arguments
+    pub fn take_upcalls(&self) -> Vec<(usize, (usize, usize, usize))> {
+        self.tasks
+            .borrow_mut()
+            .drain(..)
+            .filter_map(|call| match call.source {
+                FunctionCallSource::Driver(upcall_id) => Some((
+                    upcall_id.subscribe_num,
+                    (call.argument0, call.argument1, call.argument2),
+                )),
+                FunctionCallSource::Kernel => None,
+            })
+            .collect()
+    }
+
+    /// Copies `data` into the process' memory and returns it
+    /// as a buffer for the allow read-only system call
+    pub fn readonly_buffer(&self, data: &[u8]) -> ReadOnlyProcessBuffer {
+        let memory: &'static mut [u8] = Box::leak(data.to_vec().into_boxed_slice());
+        self.build_readonly_process_buffer(memory.as_ptr(), memory.len())
+            .expect("the buffer is not in the process' memory")
+    }
+}
+
+impl Process for MockProcess {
+    fn processid(&self) -> ProcessId {
+        self.process_id
+            .extract()
+            .expect("the process has not been loaded by a kernel")
+    }
+
+    fn enqueue_task(&self, task: Task) -> Result<(), ErrorCode> {
+        match task {
+            Task::FunctionCall(call) => {
+                self.tasks.borrow_mut().push_back(call);
+                Ok(())
+            }
+            // The process does not use IPC
+            _ => Err(ErrorCode::NOSUPPORT),
+        }
+    }
+
+    fn ready(&self) -> bool {
+        false
+    }
+
+    fn has_tasks(&self) -> bool {
+        !self.tasks.borrow().is_empty()
+    }
+
+    fn dequeue_task(&self) -> Option<Task> {
+        self.tasks.borrow_mut().pop_front().map(Task::FunctionCall)
+    }
+
+    fn pending_tasks(&self) -> usize {
+        self.tasks.borrow().len()
+    }
+
+    fn remove_pending_upcalls(&self, upcall_id: UpcallId) {
+        self.tasks.borrow_mut().retain(|call| match call.source {
+            FunctionCallSource::Driver(id) => {
+                id.driver_num != upcall_id.driver_num || id.subscribe_num != upcall_id.subscribe_num
+            }
+            FunctionCallSource::Kernel => true,
+        });
+    }
+
+    fn get_state(&self) -> State {
+        State::Yielded
+    }
+
+    fn set_yielded_state(&self) {}
+
+    fn stop(&self) {}
+
+    fn resume(&self) {}
+
+    fn set_fault_state(&self) {}
+
+    fn try_restart(&self, _completion_code: Option<u32>) {}
+
+    fn terminate(&self, _completion_code: Option<u32>) {}
+
+    fn get_restart_count(&self) -> usize {
+        0
+    }
+
+    fn get_process_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_completion_code(&self) -> Option<Option<u32>> {
+        None
+    }
+
+    fn brk(&self, _new_break: *const u8) -> Result<*const u8, Error> {
+        Err(Error::OutOfMemory)
+    }
+
+    fn sbrk(&self, _increment: isize) -> Result<*const u8, Error> {
+        Err(Error::OutOfMemory)
+    }
+
+    fn number_writeable_flash_regions(&self) -> usize {
+        0
+    }
+
+    fn get_writeable_flash_region(&self, _region_index: usize) -> (u32, u32) {
+        (0, 0)
+    }
+
+    fn update_stack_start_pointer(&self, _stack_pointer: *const u8) {}
+
+    fn update_heap_start_pointer(&self, _heap_pointer: *const u8) {}
+
+    fn build_readwrite_process_buffer(
+        &self,
+        _buf_start_addr: *mut u8,
+        _size: usize,
+    ) -> Result<ReadWriteProcessBuffer, ErrorCode> {
+        Err(ErrorCode::INVAL)
+    }
+
+    fn build_readonly_process_buffer(
+        &self,
+        buf_start_addr: *const u8,
+        size: usize,
+    ) -> Result<ReadOnlyProcessBuffer, ErrorCode> {
+        let capability = create_capability!(ExternalProcessCapability);
+        // The tests only share memory that they have leaked
+        Ok(unsafe {
+            ReadOnlyProcessBuffer::new_external(buf_start_addr, size, self.processid(), &capability)
+        })
+    }
+
+    unsafe fn set_byte(&self, _addr: *mut u8, _value: u8) -> bool {
+        false
+    }
+
+    fn flash_non_protected_start(&self) -> *const u8 {
+        core::ptr::null()
+    }
+
+    fn setup_mpu(&self) {}
+
+    fn add_mpu_region(
+        &self,
+        _unallocated_memory_start: *const u8,
+        _unallocated_memory_size: usize,
+        _min_region_size: usize,
+    ) -> Option<mpu::Region> {
+        None
+    }
+
+    fn remove_mpu_region(&self, _region: mpu::Region) -> Result<(), ErrorCode> {
+        Err(ErrorCode::INVAL)
+    }
+
+    fn allocate_grant(
+        &self,
+        grant_num: usize,
+        driver_num: usize,
+        size: usize,
+        align: usize,
+    ) -> bool {
+        let mut grants = self.grants.borrow_mut();
+        if grants.len() <= grant_num {
+            grants.resize_with(grant_num + 1, || None);
+        }
+        if grants[grant_num].is_some() {
+            return false;
+        }
+        // The grant's memory lives as long as the process
+        let ptr = match Layout::from_size_align(size.max(1), align) {
+            Ok(layout) => unsafe { alloc::alloc_zeroed(layout) },
+            Err(_) => return false,
+        };
+        if ptr.is_null() {
+            return false;
+        }
+        grants[grant_num] = Some(GrantMemory {
+            driver_num,
+            ptr,
+            entered: Cell::new(false),
+        });
+        true
+    }
+
+    fn grant_is_allocated(&self, grant_num: usize) -> Option<bool> {
+        Some(matches!(self.grants.borrow().get(grant_num), Some(Some(_))))
+    }
+
+    fn allocate_custom_grant(
+        &self,
+        _size: usize,
+        _align: usize,
+    ) -> Option<(ProcessCustomGrantIdentifer, NonNull<u8>)> {
+        None
+    }
+
+    fn enter_grant(&self, grant_num: usize) -> Result<*mut u8, Error> {
+        match self.grants.borrow().get(grant_num) {
+            Some(Some(grant)) if grant.entered.get() => Err(Error::AlreadyInUse),
+            Some(Some(grant)) => {
+                grant.entered.set(true);
+                Ok(grant.ptr)
+            }
+            _ => Err(Error::OutOfMemory),
+        }
+    }
+
+    fn enter_custom_grant(
+        &self,
+        _identifier: ProcessCustomGrantIdentifer,
+    ) -> Result<*mut u8, Error> {
+        Err(Error::InactiveApp)
+    }
+
+    fn leave_grant(&self, grant_num: usize) {
+        if let Some(Some(grant)) = self.grants.borrow().get(grant_num) {
+            grant.entered.set(false);
+        }
+    }
+
+    fn grant_allocated_count(&self) -> Option<usize> {
+        Some(self.grants.borrow().iter().flatten().count())
+    }
+
+    fn lookup_grant_from_driver_num(&self, driver_num: usize) -> Result<usize, Error> {
+        self.grants
+            .borrow()
+            .iter()
+            .position(|grant| {
+                grant
+                    .as_ref()
+                    .map_or(false, |grant| grant.driver_num == driver_num)
+            })
+            .ok_or(Error::OutOfMemory)
+    }
+
+    fn is_valid_upcall_function_pointer(&self, upcall_fn: NonNull<()>) -> bool {
+        upcall_fn.as_ptr() as usize == UPCALL_FN
+    }
+
+    unsafe fn set_syscall_return_value(&self, _return_value: SyscallReturn) {}
+
+    unsafe fn set_process_function(&self, _callback: FunctionCall) {}
+
+    unsafe fn switch_to(&self) -> Option<ContextSwitchReason> {
+        None
+    }
+
+    fn debug_syscall_count(&self) -> usize {
+        0
+    }
+
+    fn debug_dropped_upcall_count(&self) -> usize {
+        0
+    }
+
+    fn debug_timeslice_expiration_count(&self) -> usize {
+        0
+    }
+
+    fn debug_timeslice_expired(&self) {}
+
+    fn debug_syscall_called(&self, _last_syscall: Syscall) {}
+
+    unsafe fn print_memory_map(&self, _writer: &mut dyn Write) {}
+
+    unsafe fn print_full_process(&self, _writer: &mut dyn Write) {}
+}
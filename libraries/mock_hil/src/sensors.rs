@@ -0,0 +1,69 @@
+use core::cell::Cell;
+use kernel::hil::sensors::{NineDof, NineDofClient};
+use kernel::utilities::cells::OptionalCell;
+use kernel::ErrorCode;
+
+/// An accelerometer whose acceleration is set by the test
+///
+/// A reading completes only when the test asks it to, like
+/// a sensor that answers over a bus.
+#[derive(Default)]
+pub struct MockAccelerometer<'a> {
+    /// The acceleration in mg along the x, y and z axes
+    acceleration: Cell<(i32, i32, i32)>,
+
+    /// Stores if a reading has been requested
+    pending: Cell<bool>,
+
+    /// The number of readings requested
+    readings: Cell<usize>,
+
+    /// The client notified of the readings
+    client: OptionalCell<&'a dyn NineDofClient>,
+}
+
+impl<'a> MockAccelerometer<'a> {
+    /// Sets the acceleration in mg along the x, y and z axes
+    pub fn set_acceleration(&self, x: i32, y: i32, z: i32) {
+        self.acceleration.set((x, y, z));
+    }
+
+    /// Completes the requested reading, if any, and returns
+    /// whether there was one
+    pub fn complete(&self) -> bool {
+        if self.pending.replace(false) {
+            let (x, y, z) = self.acceleration.get();
+            self.client.map(|client| {
+                client.callback(
+                    x as isize as usize,
+                    y as isize as usize,
+                    z as isize as usize,
+                )
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the number of readings requested
+    pub fn readings(&self) -> usize {
+        self.readings.get()
+    }
+}
+
+impl<'a> NineDof<'a> for MockAccelerometer<'a> {
+    fn set_client(&self, client: &'a dyn NineDofClient) {
+        self.client.set(client);
+    }
+
+    fn read_accelerometer(&self) -> Result<(), ErrorCode> {
+        if self.pending.get() {
+            Err(ErrorCode::BUSY)
+        } else {
+            self.pending.set(true);
+            self.readings.set(self.readings.get() + 1);
+            Ok(())
+        }
+    }
+}
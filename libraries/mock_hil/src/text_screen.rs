@@ -0,0 +1,230 @@
+use core::cell::{Cell, RefCell};
+use kernel::hil::text_screen::{TextScreen, TextScreenClient};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+
+/// The number of characters that `MockTextScreen` displays
+const SCREEN_COLUMNS: usize = 5;
+
+/// A `TextScreen` client that records the completed requests
+pub struct MockTextScreenClient {
+    /// The number of completed commands
+    commands: Cell<usize>,
+
+    /// The number of completed *print* requests
+    writes: Cell<usize>,
+
+    /// The number of characters written by the last *print* request
+    written_len: Cell<usize>,
+
+    /// The result of the last completed request
+    result: Cell<Option<Result<(), ErrorCode>>>,
+
+    /// The buffer returned by the last *print* request
+    buffer: TakeCell<'static, [u8]>,
+}
+
+impl MockTextScreenClient {
+    /// Returns the number of completed commands
+    pub fn commands(&self) -> usize {
+        self.commands.get()
+    }
+
+    /// Returns the number of completed *print* requests
+    pub fn writes(&self) -> usize {
+        self.writes.get()
+    }
+
+    /// Returns the number of characters written by the last *print* request
+    pub fn written_len(&self) -> usize {
+        self.written_len.get()
+    }
+
+    /// Returns the result of the last completed request
+    pub fn result(&self) -> Option<Result<(), ErrorCode>> {
+        self.result.get()
+    }
+
+    /// Takes the buffer returned by the last *print* request
+    pub fn take_buffer(&self) -> Option<&'static mut [u8]> {
+        self.buffer.take()
+    }
+}
+
+impl Default for MockTextScreenClient {
+    fn default() -> Self {
+        MockTextScreenClient {
+            commands: Cell::new(0),
+            writes: Cell::new(0),
+            written_len: Cell::new(0),
+            result: Cell::new(None),
+            buffer: TakeCell::empty(),
+        }
+    }
+}
+
+impl TextScreenClient for MockTextScreenClient {
+    fn command_complete(&self, r: Result<(), ErrorCode>) {
+        self.commands.set(self.commands.get() + 1);
+        self.result.set(Some(r));
+    }
+
+    fn write_complete(&self, buffer: &'static mut [u8], len: usize, r: Result<(), ErrorCode>) {
+        self.writes.set(self.writes.get() + 1);
+        self.written_len.set(len);
+        self.result.set(Some(r));
+        self.buffer.replace(buffer);
+    }
+}
+
+/// The request that a `MockTextScreen` completes next
+#[derive(Copy, Clone, PartialEq)]
+enum Pending {
+    /// There is no request in progress
+    Nothing,
+    /// A command
+    Command,
+    /// A *print* request of this number of characters
+    Print(usize),
+}
+
+/// A text screen that records the text it displays
+///
+/// The requests complete when the test calls `complete`,
+/// the way a screen completes them from a deferred call.
+/// The cursor is not recorded, text is always printed
+/// after the previous one.
+pub struct MockTextScreen<'a> {
+    /// The printed text
+    text: RefCell<String>,
+
+    /// Whether the display is on
+    on: Cell<bool>,
+
+    /// The request in progress
+    pending: Cell<Pending>,
+
+    /// The buffer of the *print* request in progress
+    buffer: TakeCell<'static, [u8]>,
+
+    /// The client notified when a request completes
+    client: OptionalCell<&'a dyn TextScreenClient>,
+}
+
+impl<'a> MockTextScreen<'a> {
+    /// Returns the printed text
+    pub fn text(&self) -> String {
+        self.text.borrow().clone()
+    }
+
+    /// Returns whether the display is on
+    pub fn is_on(&self) -> bool {
+        self.on.get()
+    }
+
+    /// Completes the request in progress
+    ///
+    /// Returns whether a request was in progress.
+    pub fn complete(&self) -> bool {
+        match self.pending.replace(Pending::Nothing) {
+            Pending::Nothing => false,
+            Pending::Command => {
+                self.client.map(|client| client.command_complete(Ok(())));
+                true
+            }
+            Pending::Print(len) => {
+                if let Some(buffer) = self.buffer.take() {
+                    self.client
+                        .map(move |client| client.write_complete(buffer, len, Ok(())));
+                }
+                true
+            }
+        }
+    }
+
+    /// Starts a command that changes nothing else
+    fn command(&self) -> Result<(), ErrorCode> {
+        if self.pending.get() == Pending::Nothing {
+            self.pending.set(Pending::Command);
+            Ok(())
+        } else {
+            Err(ErrorCode::BUSY)
+        }
+    }
+}
+
+impl<'a> Default for MockTextScreen<'a> {
+    fn default() -> Self {
+        MockTextScreen {
+            text: RefCell::new(String::new()),
+            on: Cell::new(false),
+            pending: Cell::new(Pending::Nothing),
+            buffer: TakeCell::empty(),
+            client: OptionalCell::empty(),
+        }
+    }
+}
+
+impl<'a> TextScreen<'a> for MockTextScreen<'a> {
+    fn set_client(&self, client: Option<&'a dyn TextScreenClient>) {
+        match client {
+            Some(client) => self.client.set(client),
+            None => self.client.clear(),
+        }
+    }
+
+    fn get_size(&self) -> (usize, usize) {
+        (SCREEN_COLUMNS, 1)
+    }
+
+    fn print(
+        &self,
+        buffer: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if self.pending.get() != Pending::Nothing {
+            Err((ErrorCode::BUSY, buffer))
+        } else if len > buffer.len() {
+            Err((ErrorCode::SIZE, buffer))
+        } else {
+            self.text
+                .borrow_mut()
+                .extend(buffer[..len].iter().map(|byte| *byte as char));
+            self.buffer.replace(buffer);
+            self.pending.set(Pending::Print(len));
+            Ok(())
+        }
+    }
+
+    fn set_cursor(&self, _x_position: usize, _y_position: usize) -> Result<(), ErrorCode> {
+        self.command()
+    }
+
+    fn hide_cursor(&self) -> Result<(), ErrorCode> {
+        self.command()
+    }
+
+    fn show_cursor(&self) -> Result<(), ErrorCode> {
+        self.command()
+    }
+
+    fn blink_cursor_on(&self) -> Result<(), ErrorCode> {
+        self.command()
+    }
+
+    fn blink_cursor_off(&self) -> Result<(), ErrorCode> {
+        self.command()
+    }
+
+    fn display_on(&self) -> Result<(), ErrorCode> {
+        self.command().map(|()| self.on.set(true))
+    }
+
+    fn display_off(&self) -> Result<(), ErrorCode> {
+        self.command().map(|()| self.on.set(false))
+    }
+
+    fn clear(&self) -> Result<(), ErrorCode> {
+        self.command().map(|()| self.text.borrow_mut().clear())
+    }
+}
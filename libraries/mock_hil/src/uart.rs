@@ -0,0 +1,129 @@
+use core::cell::{Cell, RefCell};
+use kernel::hil::uart::{self, Receive, ReceiveClient, Transmit, TransmitClient, UartData};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+
+/// A UART that receives the characters typed by the test
+/// and records the transmitted ones
+pub struct MockUart<'a> {
+    /// The client notified when a transmission is done
+    transmit_client: OptionalCell<&'a dyn TransmitClient>,
+
+    /// The client that receives the typed characters
+    receive_client: OptionalCell<&'a dyn ReceiveClient>,
+
+    /// The buffer of the transmission in progress
+    tx_buffer: TakeCell<'static, [u8]>,
+
+    /// The length of the transmission in progress
+    tx_len: Cell<usize>,
+
+    /// The buffer of the reception in progress
+    rx_buffer: TakeCell<'static, [u8]>,
+
+    /// The characters transmitted so far
+    output: RefCell<Vec<u8>>,
+}
+
+impl<'a> MockUart<'a> {
+    /// Types `text`, one character at a time
+    ///
+    /// Panics if the client does not receive all the characters.
+    pub fn type_text(&self, text: &str) {
+        for character in text.bytes() {
+            let rx_buffer = self.rx_buffer.take().expect("the client is not receiving");
+            rx_buffer[0] = character;
+            self.receive_client
+                .map(|client| client.received_buffer(rx_buffer, 1, Ok(()), uart::Error::None));
+        }
+    }
+
+    /// Completes the transmissions in progress, including those that
+    /// their client starts, and returns the characters transmitted
+    /// since the previous call
+    pub fn output(&self) -> String {
+        while let Some(tx_buffer) = self.tx_buffer.take() {
+            let len = self.tx_len.get();
+            self.output
+                .borrow_mut()
+                .extend_from_slice(&tx_buffer[..len]);
+            self.transmit_client
+                .map(|client| client.transmitted_buffer(tx_buffer, len, Ok(())));
+        }
+        String::from_utf8(self.output.replace(Vec::new())).unwrap()
+    }
+}
+
+impl<'a> Default for MockUart<'a> {
+    fn default() -> Self {
+        MockUart {
+            transmit_client: OptionalCell::empty(),
+            receive_client: OptionalCell::empty(),
+            tx_buffer: TakeCell::empty(),
+            tx_len: Cell::new(0),
+            rx_buffer: TakeCell::empty(),
+            output: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<'a> Transmit<'a> for MockUart<'a> {
+    fn set_transmit_client(&self, client: &'a dyn TransmitClient) {
+        self.transmit_client.set(client);
+    }
+
+    fn transmit_buffer(
+        &self,
+        tx_buffer: &'static mut [u8],
+        tx_len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if self.tx_buffer.is_some() {
+            Err((ErrorCode::BUSY, tx_buffer))
+        } else if tx_len > tx_buffer.len() {
+            Err((ErrorCode::SIZE, tx_buffer))
+        } else {
+            self.tx_buffer.replace(tx_buffer);
+            self.tx_len.set(tx_len);
+            Ok(())
+        }
+    }
+
+    fn transmit_word(&self, _word: u32) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+
+    fn transmit_abort(&self) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+}
+
+impl<'a> Receive<'a> for MockUart<'a> {
+    fn set_receive_client(&self, client: &'a dyn ReceiveClient) {
+        self.receive_client.set(client);
+    }
+
+    fn receive_buffer(
+        &self,
+        rx_buffer: &'static mut [u8],
+        rx_len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if self.rx_buffer.is_some() {
+            Err((ErrorCode::BUSY, rx_buffer))
+        } else if rx_len == 0 || rx_len > rx_buffer.len() {
+            Err((ErrorCode::SIZE, rx_buffer))
+        } else {
+            self.rx_buffer.replace(rx_buffer);
+            Ok(())
+        }
+    }
+
+    fn receive_word(&self) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+
+    fn receive_abort(&self) -> Result<(), ErrorCode> {
+        Err(ErrorCode::NOSUPPORT)
+    }
+}
+
+impl<'a> UartData<'a> for MockUart<'a> {}